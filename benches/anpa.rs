@@ -0,0 +1,246 @@
+use anpa::core::{*};
+use anpa::{*};
+use anpa::combinators::{*};
+use anpa::findbyte::{eq, find_byte, take_while_byteclass};
+use anpa::number::integer;
+use anpa::parsers::{*};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+fn read_file(name: &str) -> String {
+    let path = format!("{}/test/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|_| panic!("File \"{}\" not found", path))
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+enum Item<'a> {
+    Action { name: &'a str, com: &'a str },
+    Info { name: &'a str, com: &'a str },
+    Separator,
+    Space,
+    Ignore,
+    SyntaxError { description: &'a str }
+}
+
+const KEYWORDS: [&str; 8] = ["let", "letrec", "in", "if", "then", "else", "fn", "match"];
+
+/// A list of integers, e.g. `[1,22,333]`, used for `many` and the comparisons.
+fn integer_list() -> String {
+    let numbers: Vec<_> = (0..10000u32).map(|n| n.wrapping_mul(2654435761) % 100000).map(|n| n.to_string()).collect();
+    format!("[{}]", numbers.join(","))
+}
+
+fn bench_combinators(c: &mut Criterion) {
+    let mut group = c.benchmark_group("combinators");
+
+    let inputs = KEYWORDS.map(|k| format!("{} x", k));
+    let keyword = |i: usize| skip(KEYWORDS[i]).map(move |_| i);
+    let or_chain = or!(keyword(0), keyword(1), keyword(2), keyword(3), keyword(4), keyword(5), keyword(6), keyword(7));
+    let greedy = greedy_or!(keyword(0), keyword(1), keyword(2), keyword(3), keyword(4), keyword(5), keyword(6), keyword(7));
+
+    group.bench_function("or", |b| b.iter(|| {
+        inputs.iter().map(|i| parse(or_chain, black_box(i.as_str())).result.unwrap()).sum::<usize>()
+    }));
+    group.bench_function("greedy_or", |b| b.iter(|| {
+        inputs.iter().map(|i| parse(greedy, black_box(i.as_str())).result.unwrap()).sum::<usize>()
+    }));
+
+    let list = integer_list();
+    let p = middle(skip('['), many_to_vec(integer::<u32, _, _, _>(), true, separator(skip(','), false)), skip(']'));
+    group.throughput(Throughput::Bytes(list.len() as u64));
+    group.bench_function("many_to_vec", |b| b.iter(|| parse(p, black_box(list.as_str())).result.unwrap()));
+
+    let text = format!("{}\n", "lorem ipsum dolor sit amet ".repeat(2500));
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.bench_function("until", |b| b.iter(|| parse(until('\n'), black_box(text.as_str())).result.unwrap()));
+    group.bench_function("find_byte", |b| b.iter(|| parse(find_byte(eq(b'\n')), black_box(text.as_str())).result.unwrap()));
+
+    group.finish();
+}
+
+fn bench_findbyte(c: &mut Criterion) {
+    let mut group = c.benchmark_group("findbyte");
+
+    // A long scan, where the word loop dominates.
+    let text = format!("{}\n", "lorem ipsum dolor sit amet ".repeat(200_000));
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.bench_function("find_byte", |b| b.iter(|| parse(find_byte(eq(b'\n')), black_box(text.as_str())).result.unwrap()));
+    group.bench_function("take_while_byteclass", |b| b.iter(|| {
+        parse(take_while_byteclass(!eq(b'\n')), black_box(text.as_str())).result.unwrap()
+    }));
+    group.bench_function("position", |b| b.iter(|| black_box(text.as_bytes()).iter().position(|b| *b == b'\n').unwrap()));
+
+    // The same scan starting at every offset within a word.
+    group.bench_function("find_byte_unaligned", |b| b.iter(|| {
+        (0..8).map(|i| parse(find_byte(eq(b'\n')), black_box(&text.as_bytes()[i..])).result.unwrap().len()).sum::<usize>()
+    }));
+
+    group.finish();
+}
+
+fn bench_hubb(c: &mut Criterion) {
+    let parse_command = split_on('=').filter(|(_, cmd): &(&str, &str)| !cmd.is_empty());
+    let parse_action = right!(skip!("Com:"), parse_command.map(|(name, cmd)| action(name, cmd)));
+    let parse_info = right!(skip!("Info:"), parse_command.map(|(name, cmd)| info(name, cmd)));
+    let parse_separator = skip!("Separator").map(|_| Item::Separator);
+    let parse_space = skip!("Space").map(|_| Item::Space);
+    let parse_error = map!(syntax_error, rest());
+    let parse_item = or!(parse_action, parse_info, parse_separator, parse_space, parse_error);
+    let item_to_state = lift_to_state(|x: &mut Vec<_>, y| x.push(y), parse_item);
+    let ignore = or_diff!(empty(), skip!('#'));
+    let state_parser = or_diff!(ignore, item_to_state);
+    let buffer_parser = left(lines(state_parser), empty());
+
+    let buffer = read_file("hubb");
+    let lines: Vec<&str> = buffer.lines().collect();
+    let mut vec: Vec<Item> = Vec::with_capacity(lines.len());
+
+    let mut group = c.benchmark_group("hubb");
+    group.throughput(Throughput::Bytes(buffer.len() as u64));
+
+    group.bench_function("lines", |b| b.iter(|| {
+        vec.clear();
+        for l in &lines {
+            parse_state(state_parser, black_box(*l), &mut vec).result.unwrap();
+        }
+    }));
+
+    group.bench_function("buffer", |b| b.iter(|| {
+        vec.clear();
+        parse_state(buffer_parser, black_box(buffer.as_str()), &mut vec).result.unwrap();
+    }));
+
+    group.bench_function("handrolled", |b| b.iter(|| {
+        vec.clear();
+        for l in &lines {
+            match parse_handrolled(black_box(l)).unwrap() {
+                Item::Ignore => {},
+                res => vec.push(res)
+            }
+        }
+    }));
+
+    group.finish();
+}
+
+fn bench_json(c: &mut Criterion) {
+    let string = read_file("test.json");
+
+    // Similar to canada.json, i.e. dominated by coordinates, here with six decimals.
+    let mut x = 1u64;
+    let mut coordinate = || {
+        x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (x >> 11) as f64 / (1u64 << 53) as f64 * 180.0 - 90.0
+    };
+    let points: Vec<_> = (0..50000).map(|_| format!("[{:.6},{:.6}]", coordinate(), coordinate())).collect();
+    let floats = format!("{{\"coordinates\":[{}]}}", points.join(","));
+
+    let mut group = c.benchmark_group("json");
+    let p = json::object_parser::<&str>();
+
+    group.throughput(Throughput::Bytes(string.len() as u64));
+    group.bench_function("object", |b| b.iter(|| parse(p, black_box(string.as_str())).result.unwrap()));
+
+    group.throughput(Throughput::Bytes(floats.len() as u64));
+    group.sample_size(20);
+    group.bench_function("floats", |b| b.iter(|| parse(p, black_box(floats.as_str())).result.unwrap()));
+
+    group.finish();
+}
+
+fn bench_semver(c: &mut Criterion) {
+    let v = "123432134.43213421.5432344-SNAPSHOT+some.build.id";
+    c.bench_function("semver", |b| b.iter(|| semver::parse_inline(black_box(v)).unwrap()));
+}
+
+/// Comparisons with equivalent parsers written with other parser combinator libraries.
+#[cfg(feature = "bench_compare")]
+fn bench_compare(c: &mut Criterion) {
+    use nom::Parser as _;
+    use winnow::Parser as _;
+
+    fn nom_list(input: &str) -> nom::IResult<&str, Vec<u32>> {
+        use nom::{bytes::complete::tag, character::complete::u32, multi::separated_list0, sequence::delimited};
+        delimited(tag("["), separated_list0(tag(","), u32), tag("]")).parse(input)
+    }
+
+    fn winnow_list(input: &mut &str) -> winnow::ModalResult<Vec<u32>> {
+        use winnow::{ascii::dec_uint, combinator::{delimited, separated}};
+        delimited('[', separated(0.., dec_uint::<_, u32, _>, ','), ']').parse_next(input)
+    }
+
+    fn nom_keyword(input: &str) -> nom::IResult<&str, &str> {
+        use nom::{branch::alt, bytes::complete::tag};
+        alt((tag("let"), tag("letrec"), tag("in"), tag("if"), tag("then"), tag("else"), tag("fn"), tag("match"))).parse(input)
+    }
+
+    fn winnow_keyword<'a>(input: &mut &'a str) -> winnow::ModalResult<&'a str> {
+        use winnow::combinator::alt;
+        alt(("let", "letrec", "in", "if", "then", "else", "fn", "match")).parse_next(input)
+    }
+
+    let list = integer_list();
+    let p = middle(skip('['), many_to_vec(integer::<u32, _, _, _>(), true, separator(skip(','), false)), skip(']'));
+
+    let mut group = c.benchmark_group("compare_list");
+    group.throughput(Throughput::Bytes(list.len() as u64));
+    group.bench_function("anpa", |b| b.iter(|| parse(p, black_box(list.as_str())).result.unwrap()));
+    group.bench_function("nom", |b| b.iter(|| nom_list(black_box(list.as_str())).unwrap()));
+    group.bench_function("winnow", |b| b.iter(|| winnow_list.parse_peek(black_box(list.as_str())).unwrap()));
+    group.finish();
+
+    let inputs = KEYWORDS.map(|k| format!("{} x", k));
+    let keyword = or!(skip("let"), skip("letrec"), skip("in"), skip("if"), skip("then"), skip("else"), skip("fn"), skip("match"));
+
+    let mut group = c.benchmark_group("compare_or");
+    group.bench_function("anpa", |b| b.iter(|| inputs.iter().map(|i| parse(keyword, black_box(i.as_str())).state.len()).sum::<usize>()));
+    group.bench_function("nom", |b| b.iter(|| inputs.iter().map(|i| nom_keyword(black_box(i.as_str())).unwrap().0.len()).sum::<usize>()));
+    group.bench_function("winnow", |b| b.iter(|| inputs.iter().map(|i| winnow_keyword.parse_peek(black_box(i.as_str())).unwrap().0.len()).sum::<usize>()));
+    group.finish();
+}
+
+#[cfg(not(feature = "bench_compare"))]
+fn bench_compare(_: &mut Criterion) {}
+
+fn action<'a>(name: &'a str, com: &'a str) -> Item<'a> {
+    Item::Action {name, com}
+}
+
+fn info<'a>(name: &'a str, com: &'a str) -> Item<'a> {
+    Item::Info { name, com }
+}
+
+fn syntax_error(description: &str) -> Item<'_> {
+    Item::SyntaxError {description}
+}
+
+fn parse_handrolled(input: &str) -> Option<Item<'_>> {
+    fn parse_command_tuple(input: &str) -> Option<(&str, &str)> {
+        let equal_pos = input.find('=')?;
+        if equal_pos == input.len() - 1 { return None }
+        Some((&input[..equal_pos], &input[(equal_pos + 1)..]))
+    }
+
+    fn parse_and_get_rest<'a>(source: &'a str, sought: &str) -> Option<&'a str> {
+        source.strip_prefix(sought)
+    }
+    if let Some(rest) = parse_and_get_rest(input, "Com:") {
+        let (name, com) = parse_command_tuple(rest)?;
+        Some(action(name, com))
+    } else if let Some(rest) = parse_and_get_rest(input, "Info:") {
+        let (name, com) = parse_command_tuple(rest)?;
+        Some(info(name, com))
+    } else if parse_and_get_rest(input, "Separator").is_some() {
+        Some(Item::Separator)
+    } else if parse_and_get_rest(input, "Space").is_some() {
+        Some(Item::Space)
+    } else if parse_and_get_rest(input, "#").is_some() || input.is_empty() {
+        Some(Item::Ignore)
+    } else {
+        Some(syntax_error(input))
+    }
+}
+
+criterion_group!(benches, bench_combinators, bench_findbyte, bench_hubb, bench_json, bench_semver, bench_compare);
+criterion_main!(benches);
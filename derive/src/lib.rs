@@ -0,0 +1,165 @@
+//! Derive macros for [anpa](https://crates.io/crates/anpa). Use these through the `derive`
+//! feature of anpa instead of depending on this crate directly.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Expr, Fields, GenericParam, Ident, Lifetime, LifetimeParam, Result, Type};
+
+/// The parsers given by `#[anpa(...)]` attributes on a type, variant or field.
+#[derive(Default)]
+struct Attrs {
+    input: Option<Type>,
+    state: Option<Type>,
+    with: Option<Expr>,
+    before: Vec<Expr>,
+    after: Vec<Expr>
+}
+
+impl Attrs {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut res = Attrs::default();
+
+        for attr in attrs.iter().filter(|a| a.path().is_ident("anpa")) {
+            attr.parse_nested_meta(|meta| {
+                let value = meta.value()?;
+                if meta.path.is_ident("input") {
+                    res.input = Some(value.parse()?);
+                } else if meta.path.is_ident("state") {
+                    res.state = Some(value.parse()?);
+                } else if meta.path.is_ident("with") {
+                    res.with = Some(value.parse()?);
+                } else if meta.path.is_ident("before") {
+                    res.before.push(value.parse()?);
+                } else if meta.path.is_ident("after") {
+                    res.after.push(value.parse()?);
+                } else {
+                    return Err(meta.error("expected `input`, `state`, `with`, `before` or `after`"))
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(res)
+    }
+}
+
+/// Generate the statements parsing `fields`, followed by the construction of `constructor`.
+fn parse_fields(constructor: TokenStream,
+                fields: &Fields,
+                attrs: &Attrs,
+                input: &Type,
+                state: &Type) -> Result<TokenStream> {
+    let mut stmts = Vec::new();
+    let mut names = Vec::new();
+    let mut vars = Vec::new();
+
+    for (i, field) in fields.iter().enumerate() {
+        let field_attrs = Attrs::parse(&field.attrs)?;
+        if let Some(a) = field_attrs.input.as_ref().or(field_attrs.state.as_ref()) {
+            return Err(Error::new_spanned(a, "`input` and `state` are only allowed on the type"))
+        }
+
+        let ty = &field.ty;
+        // Bind the result to a generated name, which can't shadow the state or other fields.
+        let var = format_ident!("__anpa_field_{}", i);
+        let parser = field_attrs.with.map_or_else(
+            || quote!(<#ty as ::anpa::core::Parse<#input, #state>>::parser()),
+            |with| quote!(#with));
+        let before = &field_attrs.before;
+        let after = &field_attrs.after;
+
+        stmts.push(quote! {
+            #((#before)(__anpa_state)?;)*
+            let #var: #ty = (#parser)(__anpa_state)?;
+            #((#after)(__anpa_state)?;)*
+        });
+        names.extend(field.ident.clone());
+        vars.push(var);
+    }
+
+    let construct = match fields {
+        Fields::Named(_) => quote!(#constructor { #(#names: #vars),* }),
+        Fields::Unnamed(_) => quote!(#constructor(#(#vars),*)),
+        Fields::Unit => constructor
+    };
+
+    let before = &attrs.before;
+    let after = &attrs.after;
+
+    Ok(quote! {
+        ::anpa::create_parser!(__anpa_state, {
+            #((#before)(__anpa_state)?;)*
+            #(#stmts)*
+            #((#after)(__anpa_state)?;)*
+            Some(#construct)
+        })
+    })
+}
+
+fn derive_parse_impl(input: DeriveInput) -> Result<TokenStream> {
+    let attrs = Attrs::parse(&input.attrs)?;
+    if let Some(with) = &attrs.with {
+        return Err(Error::new_spanned(with, "`with` is only allowed on fields"))
+    }
+
+    let input_type = attrs.input.clone().unwrap_or_else(|| parse_quote!(&'a str));
+    let state_type = attrs.state.clone().unwrap_or_else(|| parse_quote!(()));
+
+    let body = match &input.data {
+        Data::Struct(data) => parse_fields(quote!(Self), &data.fields, &attrs, &input_type, &state_type)?,
+        Data::Enum(data) => {
+            if data.variants.is_empty() {
+                return Err(Error::new_spanned(&input.ident, "can't derive `Parse` for an empty enum"))
+            }
+
+            let variants = data.variants.iter().map(|v| {
+                let variant_attrs = Attrs::parse(&v.attrs)?;
+                if let Some(a) = variant_attrs.input.as_ref().or(variant_attrs.state.as_ref()) {
+                    return Err(Error::new_spanned(a, "`input` and `state` are only allowed on the type"))
+                }
+                let ident = &v.ident;
+                parse_fields(quote!(Self::#ident), &v.fields, &variant_attrs, &input_type, &state_type)
+            }).collect::<Result<Vec<_>>>()?;
+
+            let before = &attrs.before;
+            let after = &attrs.after;
+
+            quote! {
+                ::anpa::create_parser!(__anpa_state, {
+                    #((#before)(__anpa_state)?;)*
+                    let __anpa_result = ::anpa::or!(#(#variants),*)(__anpa_state)?;
+                    #((#after)(__anpa_state)?;)*
+                    Some(__anpa_result)
+                })
+            }
+        }
+        Data::Union(_) => return Err(Error::new_spanned(&input.ident, "can't derive `Parse` for a union"))
+    };
+
+    // The input type will typically use the lifetime `'a`, so declare it if needed.
+    let mut impl_generics = input.generics.clone();
+    if !impl_generics.lifetimes().any(|l| l.lifetime.ident == "a") {
+        let a = LifetimeParam::new(Lifetime::new("'a", proc_macro2::Span::call_site()));
+        impl_generics.params.insert(0, GenericParam::Lifetime(a));
+    }
+
+    let name: &Ident = &input.ident;
+    let (impl_generics, _, _) = impl_generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::anpa::core::Parse<#input_type, #state_type> for #name #ty_generics #where_clause {
+            fn parser() -> impl ::anpa::core::Parser<#input_type, Self, #state_type> {
+                #body
+            }
+        }
+    })
+}
+
+/// Derive `anpa::core::Parse` for a struct or enum. See the documentation of `Parse` in anpa.
+#[proc_macro_derive(Parse, attributes(anpa))]
+pub fn derive_parse(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive_parse_impl(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,16 @@
+#![no_main]
+
+use anpa::{color::color, commit::commit_parser, core::{parse, parse_iter}, markdown::inline_parser,
+           toml::document_parser_with_max_depth, xml::event_parser};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_iter(event_parser(), data).count();
+    let _ = parse_iter(inline_parser(), data).count();
+    let _ = parse(commit_parser(), data);
+    let _ = parse(color(), data);
+
+    if let Ok(text) = core::str::from_utf8(data) {
+        let _ = parse(document_parser_with_max_depth(128), text);
+    }
+});
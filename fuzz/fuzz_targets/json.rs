@@ -0,0 +1,19 @@
+#![no_main]
+
+use anpa::{core::parse, json::{lazy_value_parser, value_parser_with_limits, JsonLimits, JsonValue}};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = core::str::from_utf8(data) else { return };
+
+    if let Some(value) = parse(value_parser_with_limits::<&str>(JsonLimits::UNTRUSTED), text).result {
+        assert!(!matches!(value, JsonValue::Num(n) if !n.is_finite()));
+    }
+
+    // The lazy parser doesn't limit the depth, but only parses one level at a time.
+    if let Some(JsonValue::Dic(map)) = &parse(lazy_value_parser::<&str>(), text).result {
+        for value in map.values() {
+            let _ = value.parse_value();
+        }
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use anpa::semver::{parse_const, parse_inline};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = core::str::from_utf8(data) else { return };
+
+    let version = parse_inline(text);
+    assert_eq!(version, parse_const(text));
+
+    // Valid versions have no redundant characters, so they are displayed as is.
+    if let Some(version) = version {
+        assert_eq!(version.to_string(), text);
+    }
+});
@@ -0,0 +1,72 @@
+/* C interface of anpa, enabled with the feature "capi". See src/lib/capi.rs.
+ *
+ * Inputs are passed as a pointer and a length, and are never owned or retained by the
+ * library. Nothing needs to be freed: results refer to the input using byte offsets. */
+
+#ifndef ANPA_H
+#define ANPA_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum AnpaStatus {
+    ANPA_OK = 0,
+    ANPA_INVALID = 1,
+    ANPA_INVALID_UTF8 = 2,
+    ANPA_NULL_POINTER = 3,
+    ANPA_END = 4
+} AnpaStatus;
+
+/* A part of the input. Parts that are not present are empty spans at offset 0. */
+typedef struct AnpaSpan {
+    size_t offset;
+    size_t len;
+} AnpaSpan;
+
+typedef struct AnpaSemver {
+    uint64_t major;
+    uint64_t minor;
+    uint64_t patch;
+    AnpaSpan pre_release;
+    AnpaSpan build;
+} AnpaSemver;
+
+typedef enum AnpaXmlKind {
+    ANPA_XML_START_TAG = 0,
+    ANPA_XML_END_TAG = 1,
+    ANPA_XML_TEXT = 2,
+    ANPA_XML_COMMENT = 3,
+    ANPA_XML_CDATA = 4,
+    ANPA_XML_PROCESSING_INSTRUCTION = 5,
+    ANPA_XML_DECLARATION = 6
+} AnpaXmlKind;
+
+typedef struct AnpaXmlToken {
+    AnpaXmlKind kind;
+    AnpaSpan span;
+    AnpaSpan value;
+    AnpaSpan attrs;
+    bool self_closing;
+} AnpaXmlToken;
+
+/* Parse a SemVer version. The whole input must be a version. */
+AnpaStatus anpa_semver_parse(const uint8_t *data, size_t len, AnpaSemver *out);
+
+/* Validate a JSON value. On failure, the offset of the error is written to error_offset,
+ * unless it is NULL. */
+AnpaStatus anpa_json_validate(const uint8_t *data, size_t len, size_t *error_offset);
+
+/* Scan the XML or HTML token at *pos and advance *pos past it. Returns ANPA_END at the end
+ * of the input. */
+AnpaStatus anpa_xml_next_token(const uint8_t *data, size_t len, size_t *pos, AnpaXmlToken *out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
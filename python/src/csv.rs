@@ -0,0 +1,23 @@
+use std::{borrow::Cow, string::String, vec::Vec};
+
+use anpa::{combinators::{count_consumed, cut, fold, many_to_vec, middle, no_separator, not_empty, or, right, separator}, core::{Parser, ParserExt}, findbyte::{eq, take_while_byteclass}, parsers::{skip, take}};
+
+/// A field, either unquoted, or quoted with `"`, where `""` denotes a quote. Only quoted fields
+/// may contain the delimiter or line breaks, and are the only ones that need to be copied.
+fn field<'a>(delimiter: u8) -> impl Parser<&'a str, Cow<'a, str>> {
+    let part = or(not_empty(take_while_byteclass(!eq(b'"'))), take("\"\"").map(|_| "\""));
+    let quoted = cut(middle(skip('"'), fold(part, String::new, |s, p| s.push_str(p), true, no_separator()), skip('"')));
+    let unquoted = take_while_byteclass(!(eq(delimiter) | eq(b'\r') | eq(b'\n')));
+    or(quoted.map(Cow::Owned), unquoted.map(Cow::Borrowed))
+}
+
+/// Create a parser for CSV records separated by line breaks, with fields separated by
+/// `delimiter`, which must be ASCII. Blank lines are skipped.
+pub fn csv_parser<'a>(delimiter: u8) -> impl Parser<&'a str, Vec<Vec<Cow<'a, str>>>> {
+    let line_breaks = take_while_byteclass(eq(b'\r') | eq(b'\n'));
+    let record = many_to_vec(field(delimiter), false, separator(skip(delimiter as char), false));
+
+    // An empty record is a blank line at the end of the input.
+    let record = count_consumed(record).filter(|(n, _)| *n > 0).map(|(_, r)| r);
+    right(line_breaks, many_to_vec(record, true, separator(not_empty(line_breaks), true)))
+}
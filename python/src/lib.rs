@@ -0,0 +1,116 @@
+//! Python bindings for the JSON and SemVer parsers of anpa, and a CSV parser built with it.
+//!
+//! Build with e.g. `maturin develop --release`, and use from Python:
+//! ```python
+//! import anpa
+//!
+//! anpa.parse_json(b'{"a": [1, 2]}')  # {'a': [1.0, 2.0]}
+//! anpa.is_valid_json(memoryview(data))
+//! anpa.parse_semver("1.2.3-beta+build")  # (1, 2, 3, 'beta', 'build')
+//! anpa.parse_csv('a,"b ""c"""\n1,2\n')  # [['a', 'b "c"'], ['1', '2']]
+//! ```
+//!
+//! All functions accept a `str` or any object supporting the buffer protocol, e.g. `bytes`,
+//! `bytearray`, `memoryview` or `mmap`, which is parsed without copying it.
+
+mod csv;
+
+use std::{slice, str};
+
+use anpa::{core::{parse_all, AnpaError}, json::{self, JsonLimits, JsonValue}, semver::semver};
+use pyo3::{buffer::PyBuffer, exceptions::PyValueError, prelude::*, types::{PyDict, PyList, PyString, PyTuple}, IntoPyObjectExt};
+
+/// Call `f` with the UTF-8 contents of `data`, without copying it.
+fn with_str<R>(data: &Bound<'_, PyAny>, f: impl FnOnce(&str) -> PyResult<R>) -> PyResult<R> {
+    if let Ok(s) = data.cast::<PyString>() {
+        return f(s.to_str()?)
+    }
+
+    let buffer = PyBuffer::<u8>::get(data)?;
+    if !buffer.is_c_contiguous() {
+        return Err(PyValueError::new_err("the buffer must be contiguous"))
+    }
+    let bytes = match buffer.len_bytes() {
+        0 => &[][..],
+        // SAFETY: the buffer is contiguous, and is kept alive and locked against resizing
+        // until it's dropped at the end of this function.
+        len => unsafe { slice::from_raw_parts(buffer.buf_ptr() as *const u8, len) }
+    };
+    let text = str::from_utf8(bytes).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    f(text)
+}
+
+fn to_value_error(e: AnpaError<&str>) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+fn json_to_py<'py>(py: Python<'py>, value: &JsonValue<&str>) -> PyResult<Bound<'py, PyAny>> {
+    match value {
+        JsonValue::Null => Ok(py.None().into_bound(py)),
+        JsonValue::Bool(b) => b.into_bound_py_any(py),
+        JsonValue::Str(s) | JsonValue::RawObject(s) | JsonValue::RawArray(s) => s.into_bound_py_any(py),
+        JsonValue::Num(n) => n.into_bound_py_any(py),
+        JsonValue::Dic(map) => {
+            let dict = PyDict::new(py);
+            for (k, v) in map {
+                dict.set_item(k, json_to_py(py, v)?)?;
+            }
+            Ok(dict.into_any())
+        },
+        JsonValue::Arr(values) => {
+            let values = values.iter().map(|v| json_to_py(py, v)).collect::<PyResult<Vec<_>>>()?;
+            Ok(PyList::new(py, values)?.into_any())
+        }
+    }
+}
+
+/// Parse a JSON document into the corresponding Python objects. Numbers are parsed as
+/// `float`. Raises `ValueError` if the document is invalid.
+#[pyfunction]
+fn parse_json<'py>(py: Python<'py>, data: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    with_str(data, |text| {
+        let value = parse_all(json::value_parser_with_limits::<&str>(JsonLimits::UNTRUSTED), text).map_err(to_value_error)?;
+        json_to_py(py, &value)
+    })
+}
+
+/// Check whether the input is a valid JSON document, without building any Python objects.
+#[pyfunction]
+fn is_valid_json(data: &Bound<'_, PyAny>) -> PyResult<bool> {
+    with_str(data, |text| Ok(parse_all(json::value_parser_with_limits::<&str>(JsonLimits::UNTRUSTED), text).is_ok()))
+}
+
+/// Parse a SemVer version into a tuple `(major, minor, patch, pre_release, build)`, where the
+/// last two are empty strings if not present. Raises `ValueError` if the version is invalid.
+#[pyfunction]
+fn parse_semver<'py>(py: Python<'py>, data: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyTuple>> {
+    with_str(data, |text| {
+        let v = parse_all(semver::<&str>(), text).map_err(to_value_error)?;
+        (v.major, v.minor, v.patch, v.pre_release, v.build).into_pyobject(py)
+    })
+}
+
+/// Parse CSV records into lists of fields. Fields may be quoted with `"`, and `""` denotes a
+/// quote within a quoted field. Blank lines are skipped. Raises `ValueError` if the input is
+/// invalid, e.g. for an unterminated quoted field.
+#[pyfunction]
+#[pyo3(signature = (data, delimiter = ','))]
+fn parse_csv<'py>(py: Python<'py>, data: &Bound<'py, PyAny>, delimiter: char) -> PyResult<Bound<'py, PyList>> {
+    if !delimiter.is_ascii() || matches!(delimiter, '"' | '\r' | '\n') {
+        return Err(PyValueError::new_err("the delimiter must be ASCII, and not a quote or a line break"))
+    }
+    with_str(data, |text| {
+        let records = parse_all(csv::csv_parser(delimiter as u8), text).map_err(to_value_error)?;
+        PyList::new(py, records)
+    })
+}
+
+#[pymodule]
+#[pyo3(name = "anpa")]
+fn anpa_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(parse_json, m)?)?;
+    m.add_function(wrap_pyfunction!(is_valid_json, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_semver, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_csv, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use bumpalo::{collections::Vec as BumpVec, Bump};
+
+use crate::{combinators::{fold, Separator}, core::Parser, slicelike::SliceLike};
+
+/// A handle to a bump arena that can be used as, or be part of, the user state, so that
+/// parse trees can be allocated in the arena instead of using a `Vec` or `Box` per node.
+///
+/// The parsers in this module require the user state to implement `AsMut<Arena<'a>>`,
+/// which is implemented for `Arena` itself. For a custom user state, implement `AsMut` to
+/// return its arena. The results borrow the arena, not the user state, so they can be kept
+/// after the parse.
+///
+/// Note: values allocated in the arena are never dropped, so they shouldn't own any
+/// resources, e.g. a `String` or a `Vec`. Use slices allocated in the arena instead.
+///
+/// ### Example
+/// ```
+/// use anpa::create_parser;
+/// use anpa::arena::{in_arena, many_to_arena_slice, Arena};
+/// use anpa::combinators::{middle, separator};
+/// use anpa::core::*;
+/// use anpa::number::integer;
+/// use anpa::parsers::skip;
+/// use bumpalo::Bump;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Tree<'a> {
+///     Leaf(u32),
+///     Node(&'a [&'a Tree<'a>])
+/// }
+///
+/// fn tree<'i, 'a: 'i>() -> impl Parser<&'i str, &'a Tree<'a>, Arena<'a>> {
+///     let node = middle(skip('('), many_to_arena_slice(create_parser!(s, tree()(s)), true, separator(skip(' '), false)), skip(')'));
+///     in_arena(integer().map(Tree::Leaf).or(node.map(Tree::Node)))
+/// }
+///
+/// let bump = Bump::new();
+/// let mut arena = Arena::new(&bump);
+/// let root = parse_state(tree(), "(1 (2 3) ())", &mut arena).result.unwrap();
+///
+/// let Tree::Node(children) = root else { panic!() };
+/// assert_eq!(children[1], &Tree::Node(&[&Tree::Leaf(2), &Tree::Leaf(3)]));
+/// assert_eq!(children[2], &Tree::Node(&[]));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Arena<'a> {
+    bump: &'a Bump
+}
+
+impl<'a> Arena<'a> {
+    /// Create a handle to `bump`.
+    pub fn new(bump: &'a Bump) -> Self {
+        Self { bump }
+    }
+
+    /// The underlying arena.
+    pub fn bump(self) -> &'a Bump {
+        self.bump
+    }
+
+    /// Allocate `value` in the arena.
+    pub fn alloc<T>(self, value: T) -> &'a mut T {
+        self.bump.alloc(value)
+    }
+}
+
+impl<'a> AsMut<Arena<'a>> for Arena<'a> {
+    fn as_mut(&mut self) -> &mut Arena<'a> {
+        self
+    }
+}
+
+/// Transform a parser to a parser that allocates its result in the [`Arena`] of the user
+/// state, and returns a reference to it.
+///
+/// ### Arguments
+/// * `p` - the parser
+#[inline]
+pub fn in_arena<'a, I: SliceLike, O: 'a, S: AsMut<Arena<'a>>>(p: impl Parser<I, O, S>) -> impl Parser<I, &'a O, S> {
+    create_parser!(s, {
+        let res = p(s)?;
+        let arena = *s.user_state.as_mut();
+        Some(&*arena.alloc(res))
+    })
+}
+
+/// Apply a parser until it fails and store the results in a slice allocated in the
+/// [`Arena`] of the user state. Otherwise the same as
+/// [`many_to_vec`](crate::combinators::many_to_vec).
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `allow_empty` - whether no parse should be considered successful.
+/// * `separator` - the separator to be used between parses. Use the `no_separator`/`separator`
+///   functions to construct this parameter, and `terminated` to add a terminator.
+#[inline]
+pub fn many_to_arena_slice<'a, I: SliceLike, O: 'a, O2, S: AsMut<Arena<'a>>>(p: impl Parser<I, O, S>,
+                                                                           allow_empty: bool,
+                                                                           separator: impl Separator<I, O2, S>,
+) -> impl Parser<I, &'a [O], S> {
+    create_parser!(s, {
+        let bump = s.user_state.as_mut().bump();
+        let v = fold(p, move || BumpVec::new_in(bump), |v, x| v.push(x), allow_empty, separator)(s)?;
+        Some(v.into_bump_slice())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bumpalo::Bump;
+
+    use crate::{arena::{in_arena, many_to_arena_slice, Arena}, combinators::{no_separator, right}, core::{parse_state, ParserExt}, parsers::{item_if, skip}};
+
+    #[test]
+    fn arena() {
+        let bump = Bump::new();
+        let mut arena = Arena::new(&bump);
+        let word = many_to_arena_slice(item_if(|c: char| c.is_alphabetic()), false, no_separator());
+        let words = many_to_arena_slice(in_arena(right(skip(' '), word)), true, no_separator());
+
+        let res = parse_state(words, " ab c", &mut arena).result.unwrap();
+        assert_eq!((res.len(), *res[0], *res[1]), (2, &['a', 'b'][..], &['c'][..]));
+        assert_eq!(parse_state(words.left(skip('!')), "", &mut arena).result, None);
+        assert_eq!(parse_state(word, "1", &mut arena).result, None);
+        assert!(bump.allocated_bytes() > 0);
+    }
+}
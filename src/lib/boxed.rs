@@ -0,0 +1,114 @@
+use alloc::{boxed::Box, rc::Rc};
+
+use crate::{core::{AnpaState, Parser}, slicelike::SliceLike};
+
+/// The object safe form of a parser. Since parsers are `Copy`, they can be called any number
+/// of times through `Fn`.
+type DynParser<'a, I, O, S> = dyn Fn(&mut AnpaState<I, S>) -> Option<O> + 'a;
+
+/// A type erased parser, allocated on the heap.
+///
+/// Since every parser has its own unnameable type, parsers can't be stored in collections or
+/// struct fields, or be chosen at runtime. A `BoxedParser` has a nameable type that only depends
+/// on the input, result and user state, which makes this possible.
+///
+/// A `BoxedParser` is not `Copy`, and is therefore not a parser itself. Use
+/// [`as_parser`](BoxedParser::as_parser) to get a parser referencing it.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::boxed::BoxedParser;
+/// use anpa::number::{float, integer};
+///
+/// // Choose the parser for a value at runtime.
+/// let parsers: Vec<(&str, BoxedParser<&str, f64>)> = vec![
+///     ("int", integer::<u32, _, _, _>().map(f64::from).boxed()),
+///     ("float", float().boxed())
+/// ];
+///
+/// let (_, p) = parsers.iter().find(|(name, _)| *name == "float").unwrap();
+/// assert_eq!(parse(p.as_parser(), "1.5").result, Some(1.5));
+/// ```
+pub struct BoxedParser<'a, I: SliceLike, O = I, S = ()> {
+    parser: Box<DynParser<'a, I, O, S>>
+}
+
+impl<'a, I: SliceLike, O, S> BoxedParser<'a, I, O, S> {
+    /// Create a new boxed parser from `p`.
+    /// Also available as an extension function: [`boxed`](crate::core::ParserExt::boxed)
+    pub fn new(p: impl Parser<I, O, S> + 'a) -> Self {
+        Self { parser: Box::new(move |s| p(s)) }
+    }
+
+    /// Create a new boxed parser from a function that isn't `Copy`, e.g. a closure owning
+    /// a `Vec`. See [`RcParser`] for an example.
+    pub fn from_fn(f: impl Fn(&mut AnpaState<I, S>) -> Option<O> + 'a) -> Self {
+        Self { parser: Box::new(f) }
+    }
+
+    /// Get a parser that applies this boxed parser.
+    #[inline]
+    pub fn as_parser(&self) -> impl Parser<I, O, S> + '_ {
+        let p = &self.parser;
+        create_parser!(s, p(s))
+    }
+}
+
+/// A type erased, reference counted parser. This is a cloneable version of [`BoxedParser`].
+///
+/// Use [`from_fn`](RcParser::from_fn) to create parsers that own data that isn't `Copy`,
+/// e.g. a table of `String`. Use [`as_parser`](RcParser::as_parser) to get a parser
+/// referencing it.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::boxed::RcParser;
+///
+/// let keywords: Vec<String> = vec!["let".to_owned(), "fn".to_owned()];
+///
+/// let keyword = RcParser::from_fn(move |s: &mut AnpaState<&str, ()>| {
+///     let keyword = keywords.iter().find(|k| s.input.starts_with(k.as_str()))?;
+///     s.input = &s.input[keyword.len()..];
+///     Some(keyword.clone())
+/// });
+///
+/// let shared = keyword.clone();
+/// assert_eq!(parse(shared.as_parser(), "fn main").result, Some("fn".to_owned()));
+/// assert_eq!(parse(keyword.as_parser(), "struct").result, None);
+/// ```
+pub struct RcParser<'a, I: SliceLike, O = I, S = ()> {
+    parser: Rc<DynParser<'a, I, O, S>>
+}
+
+impl<'a, I: SliceLike, O, S> RcParser<'a, I, O, S> {
+    /// Create a new reference counted parser from `p`.
+    pub fn new(p: impl Parser<I, O, S> + 'a) -> Self {
+        Self { parser: Rc::new(move |s| p(s)) }
+    }
+
+    /// Create a new reference counted parser from a function that isn't `Copy`.
+    pub fn from_fn(f: impl Fn(&mut AnpaState<I, S>) -> Option<O> + 'a) -> Self {
+        Self { parser: Rc::new(f) }
+    }
+
+    /// Get a parser that applies this reference counted parser.
+    #[inline]
+    pub fn as_parser(&self) -> impl Parser<I, O, S> + '_ {
+        let p = &*self.parser;
+        create_parser!(s, p(s))
+    }
+}
+
+impl<I: SliceLike, O, S> Clone for RcParser<'_, I, O, S> {
+    fn clone(&self) -> Self {
+        Self { parser: self.parser.clone() }
+    }
+}
+
+impl<'a, I: SliceLike, O, S> From<BoxedParser<'a, I, O, S>> for RcParser<'a, I, O, S> {
+    fn from(p: BoxedParser<'a, I, O, S>) -> Self {
+        Self { parser: p.parser.into() }
+    }
+}
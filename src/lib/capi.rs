@@ -0,0 +1,262 @@
+use core::{slice, str};
+
+use crate::{core::{parse, parse_all}, json::{value_parser_with_limits, JsonLimits}, semver::semver, xml::{event_parser, Event}};
+
+// The C interface. All functions take the input as a pointer and a length, and never take
+// ownership of it or allocate anything that the caller must free. Results refer to the
+// input as byte offsets (see `AnpaSpan`), so they stay valid as long as the input does.
+// The functions return an `AnpaStatus`, and only write to the output parameters on success,
+// unless stated otherwise. The declarations are in `include/anpa.h`.
+
+/// The status returned by the C interface.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnpaStatus {
+    /// The parse was successful.
+    Ok = 0,
+
+    /// The input is not valid.
+    Invalid = 1,
+
+    /// The input is not valid UTF-8, but the parser requires it.
+    InvalidUtf8 = 2,
+
+    /// A required pointer was null.
+    NullPointer = 3,
+
+    /// There is no more input to scan.
+    End = 4
+}
+
+/// A part of the input, given as a byte offset from the start of the input and a length in
+/// bytes. Parts that are not present are represented by an empty span at offset 0.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnpaSpan {
+    pub offset: usize,
+    pub len: usize
+}
+
+impl AnpaSpan {
+    fn of(input: &[u8], part: &[u8]) -> Self {
+        if part.is_empty() {
+            return Self::default()
+        }
+        Self { offset: part.as_ptr() as usize - input.as_ptr() as usize, len: part.len() }
+    }
+}
+
+/// A SemVer version, as parsed by [`anpa_semver_parse`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnpaSemver {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre_release: AnpaSpan,
+    pub build: AnpaSpan
+}
+
+/// The kind of an [`AnpaXmlToken`]. See [`Event`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnpaXmlKind {
+    StartTag = 0,
+    EndTag = 1,
+    Text = 2,
+    Comment = 3,
+    CData = 4,
+    ProcessingInstruction = 5,
+    Declaration = 6
+}
+
+/// An XML or HTML token, as scanned by [`anpa_xml_next_token`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnpaXmlToken {
+    pub kind: AnpaXmlKind,
+
+    /// The whole token, including any markup.
+    pub span: AnpaSpan,
+
+    /// The name for start and end tags, otherwise the content, e.g. the text of a comment.
+    pub value: AnpaSpan,
+
+    /// The unparsed attributes of a start tag, otherwise empty.
+    pub attrs: AnpaSpan,
+
+    /// Whether this is an empty element tag, e.g. `<br/>`.
+    pub self_closing: bool
+}
+
+/// Get the input given by `data` and `len`. A null pointer is only allowed for empty input.
+unsafe fn input<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    match (data.is_null(), len) {
+        (true, 0) => Some(&[]),
+        (true, _) => None,
+        _ => Some(slice::from_raw_parts(data, len))
+    }
+}
+
+/// Parse the SemVer version in the UTF-8 input `data` of `len` bytes into `out`. The whole
+/// input must be a version.
+///
+/// ### Safety
+/// `data` must point to `len` readable bytes, or be null if `len` is 0, and `out` must be
+/// null or point to writable memory for an `AnpaSemver`.
+#[no_mangle]
+pub unsafe extern "C" fn anpa_semver_parse(data: *const u8, len: usize, out: *mut AnpaSemver) -> AnpaStatus {
+    let (Some(bytes), false) = (input(data, len), out.is_null()) else {
+        return AnpaStatus::NullPointer
+    };
+    let Ok(text) = str::from_utf8(bytes) else {
+        return AnpaStatus::InvalidUtf8
+    };
+    let Ok(version) = parse_all(semver::<&str>(), text) else {
+        return AnpaStatus::Invalid
+    };
+
+    out.write(AnpaSemver {
+        major: version.major,
+        minor: version.minor,
+        patch: version.patch,
+        pre_release: AnpaSpan::of(bytes, version.pre_release.as_bytes()),
+        build: AnpaSpan::of(bytes, version.build.as_bytes())
+    });
+    AnpaStatus::Ok
+}
+
+/// Validate that the UTF-8 input `data` of `len` bytes is a single JSON value, using
+/// [`JsonLimits::UNTRUSTED`].
+///
+/// If the input is invalid and `error_offset` isn't null, the byte offset where the parse
+/// failed is written to it.
+///
+/// ### Safety
+/// `data` must point to `len` readable bytes, or be null if `len` is 0, and `error_offset`
+/// must be null or point to a writable `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn anpa_json_validate(data: *const u8, len: usize, error_offset: *mut usize) -> AnpaStatus {
+    let Some(bytes) = input(data, len) else {
+        return AnpaStatus::NullPointer
+    };
+    let (status, offset) = match str::from_utf8(bytes) {
+        Ok(text) => match parse_all(value_parser_with_limits::<&str>(JsonLimits::UNTRUSTED), text) {
+            Ok(_) => return AnpaStatus::Ok,
+            Err(e) => (AnpaStatus::Invalid, e.offset)
+        },
+        Err(e) => (AnpaStatus::InvalidUtf8, e.valid_up_to())
+    };
+
+    if !error_offset.is_null() {
+        error_offset.write(offset);
+    }
+    status
+}
+
+/// Scan the next XML or HTML token in the input `data` of `len` bytes, starting at the byte
+/// offset `*pos`, into `out`, and advance `*pos` past it. Returns `AnpaStatus::End` when
+/// `*pos` is at the end of the input. Call repeatedly to tokenize a whole document.
+///
+/// The input doesn't need to be UTF-8.
+///
+/// ### Safety
+/// `data` must point to `len` readable bytes, or be null if `len` is 0, and `pos` and `out`
+/// must be null or point to a readable and writable `size_t` and `AnpaXmlToken`
+/// respectively.
+#[no_mangle]
+pub unsafe extern "C" fn anpa_xml_next_token(data: *const u8, len: usize, pos: *mut usize, out: *mut AnpaXmlToken) -> AnpaStatus {
+    let (Some(bytes), false, false) = (input(data, len), pos.is_null(), out.is_null()) else {
+        return AnpaStatus::NullPointer
+    };
+    let Some(rest) = bytes.get(*pos..) else {
+        return AnpaStatus::Invalid
+    };
+    if rest.is_empty() {
+        return AnpaStatus::End
+    }
+
+    let res = parse(event_parser::<&[u8], ()>(), rest);
+    let Some(event) = res.result else {
+        return AnpaStatus::Invalid
+    };
+
+    let span = &rest[..rest.len() - res.state.len()];
+    let (kind, value, attrs, self_closing) = match event {
+        Event::StartTag { name, attrs, self_closing } => (AnpaXmlKind::StartTag, name, attrs.raw(), self_closing),
+        Event::EndTag(name) => (AnpaXmlKind::EndTag, name, &[][..], false),
+        Event::Text(t) => (AnpaXmlKind::Text, t, &[][..], false),
+        Event::Comment(t) => (AnpaXmlKind::Comment, t, &[][..], false),
+        Event::CData(t) => (AnpaXmlKind::CData, t, &[][..], false),
+        Event::ProcessingInstruction(t) => (AnpaXmlKind::ProcessingInstruction, t, &[][..], false),
+        Event::Declaration(t) => (AnpaXmlKind::Declaration, t, &[][..], false)
+    };
+
+    out.write(AnpaXmlToken {
+        kind,
+        span: AnpaSpan::of(bytes, span),
+        value: AnpaSpan::of(bytes, value),
+        attrs: AnpaSpan::of(bytes, attrs),
+        self_closing
+    });
+    *pos += span.len();
+    AnpaStatus::Ok
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ptr;
+
+    use crate::capi::*;
+
+    #[test]
+    fn semver() {
+        let input = "1.2.3-beta+build";
+        let mut out = AnpaSemver { major: 0, minor: 0, patch: 0, pre_release: AnpaSpan::default(), build: AnpaSpan::default() };
+        unsafe {
+            assert_eq!(anpa_semver_parse(input.as_ptr(), input.len(), &mut out), AnpaStatus::Ok);
+            assert_eq!((out.major, out.minor, out.patch), (1, 2, 3));
+            assert_eq!((out.pre_release, out.build), (AnpaSpan { offset: 6, len: 4 }, AnpaSpan { offset: 11, len: 5 }));
+
+            assert_eq!(anpa_semver_parse("1.2".as_ptr(), 3, &mut out), AnpaStatus::Invalid);
+            assert_eq!(anpa_semver_parse(b"1.2.\xff".as_ptr(), 5, &mut out), AnpaStatus::InvalidUtf8);
+            assert_eq!(anpa_semver_parse(ptr::null(), 1, &mut out), AnpaStatus::NullPointer);
+            assert_eq!(anpa_semver_parse("1.2.3".as_ptr(), 5, &mut out), AnpaStatus::Ok);
+            assert_eq!(out.pre_release, AnpaSpan::default());
+        }
+    }
+
+    #[test]
+    fn json() {
+        let mut offset = 0;
+        unsafe {
+            assert_eq!(anpa_json_validate("[1, {}]".as_ptr(), 7, &mut offset), AnpaStatus::Ok);
+            assert_eq!(anpa_json_validate("[1, }".as_ptr(), 5, &mut offset), AnpaStatus::Invalid);
+            assert_eq!(offset, 4);
+            assert_eq!(anpa_json_validate(b"\"\xff\"".as_ptr(), 3, &mut offset), AnpaStatus::InvalidUtf8);
+            assert_eq!(offset, 1);
+            assert_eq!(anpa_json_validate(ptr::null(), 0, ptr::null_mut()), AnpaStatus::Invalid);
+        }
+    }
+
+    #[test]
+    fn xml() {
+        let input = b"<a x=\"1\">t<br/></a>";
+        let mut pos = 0;
+        let mut out = AnpaXmlToken { kind: AnpaXmlKind::Text, span: AnpaSpan::default(), value: AnpaSpan::default(), attrs: AnpaSpan::default(), self_closing: false };
+        let mut tokens = [out; 4];
+        unsafe {
+            for token in &mut tokens {
+                assert_eq!(anpa_xml_next_token(input.as_ptr(), input.len(), &mut pos, &mut out), AnpaStatus::Ok);
+                *token = out;
+            }
+            assert_eq!(anpa_xml_next_token(input.as_ptr(), input.len(), &mut pos, &mut out), AnpaStatus::End);
+            assert_eq!(anpa_xml_next_token(input.as_ptr(), input.len(), ptr::null_mut(), &mut out), AnpaStatus::NullPointer);
+        }
+
+        assert_eq!(tokens.map(|t| t.kind), [AnpaXmlKind::StartTag, AnpaXmlKind::Text, AnpaXmlKind::StartTag, AnpaXmlKind::EndTag]);
+        assert_eq!((tokens[0].span, tokens[0].value, tokens[0].attrs), (AnpaSpan { offset: 0, len: 9 }, AnpaSpan { offset: 1, len: 1 }, AnpaSpan { offset: 2, len: 6 }));
+        assert_eq!((tokens[2].value, tokens[2].self_closing), (AnpaSpan { offset: 11, len: 2 }, true));
+        assert_eq!((tokens[3].span, tokens[3].value), (AnpaSpan { offset: 15, len: 4 }, AnpaSpan { offset: 17, len: 1 }));
+    }
+}
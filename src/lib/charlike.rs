@@ -1,6 +1,100 @@
 /// Common trait for types that can be safely converted to `char`.
+///
+/// Implemented for `u8`, `char`, UTF-16 code units (`u16`) and UTF-32 code units (`u32`).
+/// Code units that are not valid characters by themselves, e.g. UTF-16 surrogates, are
+/// converted to `char::REPLACEMENT_CHARACTER`, so they never match e.g. digits or
+/// whitespace.
+///
+/// All character classification methods are implemented in terms of [`as_char`](CharLike::as_char),
+/// so that all inputs get identical behavior. The methods with the same name as a method on
+/// `char` have the same semantics.
 pub trait CharLike: Copy {
+    /// Convert to a `char`.
     fn as_char(self) -> char;
+
+    /// Check if this is a digit in the provided radix.
+    #[inline(always)]
+    fn is_digit(self, radix: u32) -> bool {
+        char::is_digit(self.as_char(), radix)
+    }
+
+    /// Convert this to a digit in the provided radix.
+    #[inline(always)]
+    fn to_digit(self, radix: u32) -> Option<u32> {
+        char::to_digit(self.as_char(), radix)
+    }
+
+    /// Check if this is an ASCII decimal digit.
+    #[inline(always)]
+    fn is_ascii_digit(self) -> bool {
+        char::is_ascii_digit(&self.as_char())
+    }
+
+    /// Check if this is an ASCII hexadecimal digit.
+    #[inline(always)]
+    fn is_ascii_hexdigit(self) -> bool {
+        char::is_ascii_hexdigit(&self.as_char())
+    }
+
+    /// Check if this is alphabetic, according to Unicode.
+    #[inline(always)]
+    fn is_alphabetic(self) -> bool {
+        char::is_alphabetic(self.as_char())
+    }
+
+    /// Check if this is an ASCII letter.
+    #[inline(always)]
+    fn is_ascii_alphabetic(self) -> bool {
+        char::is_ascii_alphabetic(&self.as_char())
+    }
+
+    /// Check if this is alphanumeric, according to Unicode.
+    #[inline(always)]
+    fn is_alphanumeric(self) -> bool {
+        char::is_alphanumeric(self.as_char())
+    }
+
+    /// Check if this is an ASCII letter or digit.
+    #[inline(always)]
+    fn is_ascii_alphanumeric(self) -> bool {
+        char::is_ascii_alphanumeric(&self.as_char())
+    }
+
+    /// Check if this is whitespace, according to Unicode.
+    #[inline(always)]
+    fn is_whitespace(self) -> bool {
+        char::is_whitespace(self.as_char())
+    }
+
+    /// Check if this is ASCII whitespace.
+    #[inline(always)]
+    fn is_ascii_whitespace(self) -> bool {
+        char::is_ascii_whitespace(&self.as_char())
+    }
+
+    /// Check if this is ASCII punctuation.
+    #[inline(always)]
+    fn is_ascii_punctuation(self) -> bool {
+        char::is_ascii_punctuation(&self.as_char())
+    }
+
+    /// Convert to a `char`, with ASCII letters converted to lowercase.
+    #[inline(always)]
+    fn to_lowercase_ascii(self) -> char {
+        char::to_ascii_lowercase(&self.as_char())
+    }
+
+    /// Convert to a `char`, with ASCII letters converted to uppercase.
+    #[inline(always)]
+    fn to_uppercase_ascii(self) -> char {
+        char::to_ascii_uppercase(&self.as_char())
+    }
+
+    /// Check if this is equal to `c`, ignoring the case of ASCII letters.
+    #[inline(always)]
+    fn eq_ignore_ascii_case(self, c: char) -> bool {
+        char::eq_ignore_ascii_case(&self.as_char(), &c)
+    }
 }
 
 impl<C: CharLike> CharLike for &C {
@@ -22,4 +116,56 @@ macro_rules! impl_CharLike {
 }
 
 impl_CharLike!(u8);
-impl_CharLike!(char);
\ No newline at end of file
+impl_CharLike!(char);
+
+impl CharLike for u16 {
+    #[inline(always)]
+    fn as_char(self) -> char {
+        // Fails for surrogates.
+        char::from_u32(self as u32).unwrap_or(char::REPLACEMENT_CHARACTER)
+    }
+}
+
+impl CharLike for u32 {
+    #[inline(always)]
+    fn as_char(self) -> char {
+        char::from_u32(self).unwrap_or(char::REPLACEMENT_CHARACTER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{charlike::CharLike, core::parse, number::integer, parsers::item_while, whitespace::{skip_ascii_whitespace, skip_whitespace}};
+
+    #[test]
+    fn utf16() {
+        let input = b"123  abc".map(u16::from);
+        let res = parse(integer(), input.as_slice());
+        assert_eq!(res.result, Some(123));
+
+        let res = parse(skip_ascii_whitespace(), res.state);
+        let res = parse(item_while(|c: &u16| c.is_alphabetic()), res.state);
+        assert_eq!(res.result, Some([97, 98, 99].as_slice()));
+
+        // A non-breaking space followed by a surrogate pair (U+1F600).
+        let input = [0xA0, 0xD83D, 0xDE00];
+        assert_eq!(parse(skip_whitespace(), input.as_slice()).state, &input[1..]);
+        assert_eq!(0xD83Du16.as_char(), char::REPLACEMENT_CHARACTER);
+    }
+
+    #[test]
+    fn classification() {
+        fn classes(c: impl CharLike) -> [bool; 4] {
+            [c.is_ascii_digit(), c.is_alphabetic(), c.is_ascii_whitespace(), c.eq_ignore_ascii_case('a')]
+        }
+
+        for c in ['7', 'A', 'a', ' ', '-'] {
+            let expected = classes(c);
+            assert_eq!(classes(c as u8), expected);
+            assert_eq!(classes(c as u16), expected);
+            assert_eq!(classes(c as u32), expected);
+            assert_eq!((c as u8).to_digit(16), c.to_digit(16));
+            assert_eq!((c as u16).to_lowercase_ascii(), c.to_ascii_lowercase());
+        }
+    }
+}
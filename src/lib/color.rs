@@ -0,0 +1,260 @@
+use crate::{core::Parser, findbyte::ByteInput};
+
+/// A color with 8-bit red, green, blue and alpha channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+
+    /// The alpha channel, where 255 is opaque.
+    pub a: u8
+}
+
+impl Rgba {
+    /// Create an opaque color.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Rgba {
+        Rgba { r, g, b, a: 255 }
+    }
+}
+
+/// An argument of a color function.
+#[derive(Debug, Clone, Copy)]
+enum Arg {
+    Number(f32),
+    Percent(f32)
+}
+
+impl Arg {
+    /// Get the argument as a fraction, where 1.0 is either `max` or 100%.
+    fn fraction(self, max: f32) -> f32 {
+        match self {
+            Arg::Number(n) => n / max,
+            Arg::Percent(p) => p / 100.0
+        }
+    }
+}
+
+/// Convert a fraction to a channel value, clamping it to the valid range.
+fn channel(fraction: f32) -> u8 {
+    (fraction.clamp(0.0, 1.0) * 255.0 + 0.5) as u8
+}
+
+fn skip_whitespace(bytes: &[u8], pos: usize) -> usize {
+    pos + bytes[pos..].iter().take_while(|b| b.is_ascii_whitespace()).count()
+}
+
+/// Parse a number, e.g. `-12.5`, followed by an optional `%` or `deg`. Returns the argument
+/// and its length.
+fn argument(bytes: &[u8]) -> Option<(Arg, usize)> {
+    let neg = bytes.first() == Some(&b'-');
+    let mut pos = neg as usize;
+    let mut value = 0.0f32;
+    let mut scale = 1.0;
+    let mut digits = 0;
+    let mut fraction = false;
+
+    while let Some(b) = bytes.get(pos) {
+        match b {
+            b'0'..=b'9' if fraction => {
+                scale /= 10.0;
+                value += (b - b'0') as f32 * scale;
+            },
+            b'0'..=b'9' => value = value * 10.0 + (b - b'0') as f32,
+            b'.' if !fraction => fraction = true,
+            _ => break
+        }
+        digits += b.is_ascii_digit() as usize;
+        pos += 1;
+    }
+
+    if digits == 0 {
+        return None
+    }
+
+    let value = if neg { -value } else { value };
+    match &bytes[pos..] {
+        [b'%', ..] => Some((Arg::Percent(value), pos + 1)),
+        [b'd', b'e', b'g', ..] => Some((Arg::Number(value), pos + 3)),
+        _ => Some((Arg::Number(value), pos))
+    }
+}
+
+/// Parse a color function with the name `name`, e.g. `rgb(1, 2, 3)`, where the name is
+/// optionally followed by an `a`. Returns the arguments, where a missing alpha is 1, and the
+/// length of the function.
+fn function(bytes: &[u8], name: &[u8]) -> Option<([Arg; 4], usize)> {
+    if !bytes.get(..name.len())?.eq_ignore_ascii_case(name) {
+        return None
+    }
+
+    let mut pos = name.len() + bytes.get(name.len()).is_some_and(|b| b.eq_ignore_ascii_case(&b'a')) as usize;
+    if bytes.get(pos) != Some(&b'(') {
+        return None
+    }
+
+    let mut args = [Arg::Number(1.0); 4];
+    let mut count = 0;
+    pos = skip_whitespace(bytes, pos + 1);
+
+    loop {
+        let (arg, len) = argument(&bytes[pos..])?;
+        args[count] = arg;
+        count += 1;
+        pos = skip_whitespace(bytes, pos + len);
+
+        match bytes.get(pos)? {
+            b')' if count >= 3 => return Some((args, pos + 1)),
+            b',' if count < 4 => pos = skip_whitespace(bytes, pos + 1),
+            b'/' if count == 3 => pos = skip_whitespace(bytes, pos + 1),
+            _ if count < 3 && bytes[pos - 1].is_ascii_whitespace() => {},
+            _ => return None
+        }
+    }
+}
+
+/// Create a parser for a hexadecimal color, i.e. `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA`.
+///
+/// ### Consuming
+/// Consumes the color, on successful parse. The parser fails if the number of hexadecimal
+/// digits is not 3, 4, 6 or 8.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::color::{hex, Rgba};
+///
+/// assert_eq!(parse(hex(), "#f80").result, Some(Rgba::rgb(0xff, 0x88, 0x00)));
+/// assert_eq!(parse(hex(), "#12345678").result, Some(Rgba { r: 0x12, g: 0x34, b: 0x56, a: 0x78 }));
+/// assert_eq!(parse(hex(), "#12345").result, None);
+/// ```
+#[inline]
+pub fn hex<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Rgba, S> {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice().strip_prefix(b"#")?;
+        let len = bytes.iter().take_while(|b| b.is_ascii_hexdigit()).count();
+        let digit = |i: usize| (bytes[i] as char).to_digit(16).unwrap() as u8;
+
+        let [r, g, b, a] = match len {
+            3 | 4 => [0, 1, 2, 3].map(|i| if i < len { digit(i) * 0x11 } else { 255 }),
+            6 | 8 => [0, 1, 2, 3].map(|i| if 2 * i < len { digit(2 * i) << 4 | digit(2 * i + 1) } else { 255 }),
+            _ => return None
+        };
+
+        s.input = s.input.slice_from(1 + len);
+        Some(Rgba { r, g, b, a })
+    })
+}
+
+/// Create a parser for a CSS `rgb()` or `rgba()` color, e.g. `rgb(255, 128, 0)` or
+/// `rgb(100% 50% 0% / 0.5)`. The color channels are numbers from 0 to 255 or percentages,
+/// and the optional alpha is a number from 0 to 1 or a percentage. Values out of range are
+/// clamped.
+///
+/// Both the comma separated and the space separated syntax are accepted, and the function
+/// name is case insensitive.
+///
+/// ### Consuming
+/// Consumes the color, on successful parse.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::color::{rgb, Rgba};
+///
+/// assert_eq!(parse(rgb(), "rgb(255, 128, 0)").result, Some(Rgba::rgb(255, 128, 0)));
+/// assert_eq!(parse(rgb(), "rgba(100% 50% 300 / 50%)").result, Some(Rgba { r: 255, g: 128, b: 255, a: 128 }));
+/// ```
+#[inline]
+pub fn rgb<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Rgba, S> {
+    create_parser!(s, {
+        let ([r, g, b, a], len) = function(s.input.as_byte_slice(), b"rgb")?;
+        s.input = s.input.slice_from(len);
+        Some(Rgba { r: channel(r.fraction(255.0)), g: channel(g.fraction(255.0)),
+                    b: channel(b.fraction(255.0)), a: channel(a.fraction(1.0)) })
+    })
+}
+
+/// Create a parser for a CSS `hsl()` or `hsla()` color, e.g. `hsl(120deg, 100%, 50%)` or
+/// `hsl(120 100% 50% / 0.5)`. The hue is in degrees, the saturation and lightness are
+/// percentages, and the optional alpha is a number from 0 to 1 or a percentage.
+///
+/// Both the comma separated and the space separated syntax are accepted, and the function
+/// name is case insensitive.
+///
+/// ### Consuming
+/// Consumes the color, on successful parse.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::color::{hsl, Rgba};
+///
+/// assert_eq!(parse(hsl(), "hsl(120deg, 100%, 25%)").result, Some(Rgba::rgb(0, 128, 0)));
+/// assert_eq!(parse(hsl(), "hsl(-60 100% 50% / .5)").result, Some(Rgba { r: 255, g: 0, b: 255, a: 128 }));
+/// ```
+#[inline]
+pub fn hsl<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Rgba, S> {
+    create_parser!(s, {
+        let ([Arg::Number(h), saturation, lightness, alpha], len) = function(s.input.as_byte_slice(), b"hsl")? else {
+            return None
+        };
+
+        let (saturation, lightness) = (saturation.fraction(100.0).clamp(0.0, 1.0), lightness.fraction(100.0).clamp(0.0, 1.0));
+        let a = saturation * lightness.min(1.0 - lightness);
+        let f = |n: f32| {
+            let k = (n + h / 30.0) % 12.0;
+            let k = if k < 0.0 { k + 12.0 } else { k };
+            channel(lightness - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0))
+        };
+
+        s.input = s.input.slice_from(len);
+        Some(Rgba { r: f(0.0), g: f(8.0), b: f(4.0), a: channel(alpha.fraction(1.0)) })
+    })
+}
+
+/// Create a parser for a color in any of the formats of [`hex`], [`rgb`] or [`hsl`].
+///
+/// ### Consuming
+/// Consumes the color, on successful parse.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::color::{color, Rgba};
+///
+/// for input in ["#0f0", "#00ff00ff", "rgb(0,255,0)", "HSLA(120, 100%, 50%, 1)"] {
+///     assert_eq!(parse(color(), input).result, Some(Rgba::rgb(0, 255, 0)));
+/// }
+/// ```
+#[inline]
+pub fn color<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Rgba, S> {
+    create_parser!(s, {
+        match s.input.as_byte_slice().first()? {
+            b'#' => hex()(s),
+            b'r' | b'R' => rgb()(s),
+            _ => hsl()(s)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{color::{color, Rgba}, core::parse};
+
+    #[test]
+    fn colors() {
+        let res = parse(color(), "#ABCDx");
+        assert_eq!((res.result, res.state), (Some(Rgba { r: 0xaa, g: 0xbb, b: 0xcc, a: 0xdd }), "x"));
+        assert_eq!(parse(color(), b"#a0b1c2;".as_slice()).result, Some(Rgba::rgb(0xa0, 0xb1, 0xc2)));
+        assert_eq!(parse(color(), "rgb( -1 , 127.5 , 256 , 2 )").result, Some(Rgba::rgb(0, 128, 255)));
+        assert_eq!(parse(color(), "rgba(0 0 0/0)").result, Some(Rgba::default()));
+        assert_eq!(parse(color(), "hsl(0, 0%, 100%)").result, Some(Rgba::rgb(255, 255, 255)));
+        assert_eq!(parse(color(), "hsl(540, 50%, 50%)").result, Some(Rgba::rgb(64, 191, 191)));
+
+        for invalid in ["", "#", "#12", "#1234567", "rgb(1, 2)", "rgb(1, 2, 3, 4, 5)", "rgb(1 2 3 4)",
+                        "rgb 1 2 3", "rgb(1,2,3", "rgb(1, 2 / 3)", "hsl(10%, 1%, 1%)", "rgbx(1,2,3)"] {
+            assert_eq!(parse(color(), invalid).result, None, "{}", invalid);
+        }
+    }
+}
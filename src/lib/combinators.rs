@@ -1,7 +1,12 @@
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
 #[cfg(feature = "std")]
-use std::{collections::{BTreeMap, HashMap}, vec::Vec, hash::Hash};
+use std::{collections::HashMap, hash::Hash};
+
+use core::ops::ControlFlow;
 
-use crate::{core::{AnpaState, Parser}, parsers::success, slicelike::SliceLike};
+use crate::{charlike::CharLike, core::{AnpaState, Parser}, parsers::{line, rest, success}, slicelike::{consumed_input, consumed_len, SliceLike}};
 
 /// Create a new parser by taking the result of `p`, and applying `f`.
 /// This can be used to create a new parser based on the result of another.
@@ -168,10 +173,56 @@ pub fn filter<I: SliceLike, O, S>(p: impl Parser<I, O, S>,
     create_parser!(s, p(s).filter(f))
 }
 
+/// Create a parser that validates the result of `p` together with the input it consumed.
+/// Unlike [`filter`], `f` can reject with a reason, which is reported by
+/// [`parse_all`](crate::core::parse_all) in [`AnpaError::expected`](crate::core::AnpaError::expected),
+/// with the position where `p` started, the same way as for [`labelled`].
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `f` - the validation function, given the consumed input and the result of `p`
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{right, verify_with_span};
+/// use anpa::parsers::{item_while, skip};
+///
+/// let ident = verify_with_span(item_while(|c: char| c.is_alphanumeric()), |span: &str, _| {
+///     if span.is_empty() { return Err("identifier") }
+///     if span.len() > 8 { return Err("identifier of at most 8 bytes") }
+///     Ok(())
+/// });
+/// let decl = right(skip("let "), ident);
+///
+/// assert_eq!(parse_all(decl, "let abc"), Ok("abc"));
+///
+/// let err = parse_all(decl, "let abcdefghi").unwrap_err();
+/// assert_eq!((err.offset, err.expected), (4, Some("identifier of at most 8 bytes")));
+/// ```
+#[inline]
+pub fn verify_with_span<I: SliceLike, O, S>(p: impl Parser<I, O, S>,
+                                            f: impl FnOnce(I, &O) -> Result<(), &'static str> + Copy
+) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let remaining = s.input.slice_len();
+        let (parsed, res) = and_parsed(p)(s)?;
+        match f(parsed, &res) {
+            Ok(()) => Some(res),
+            Err(reason) => {
+                s.expect(reason, remaining);
+                None
+            }
+        }
+    })
+}
+
 /// Transform a parser to a parser that always succeeds. The resulting parser will
 /// have its result type changed to `Option`, to allow for introspection of the result
 /// of the parse.
 ///
+/// Also available as an extension function: [`opt`](crate::core::ParserExt::opt)
+///
 /// ### Arguments
 /// * `p` - the parser
 ///
@@ -192,7 +243,78 @@ pub fn filter<I: SliceLike, O, S>(p: impl Parser<I, O, S>,
 #[inline]
 pub fn succeed<I:SliceLike, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, Option<O>, S> {
     create_parser!(s, {
-        Some(p(s))
+        let res = p(s);
+        if res.is_none() && s.cut {
+            return None
+        }
+        Some(res)
+    })
+}
+
+/// Transform a parser to a parser that succeeds with `O::default()` if the parser fails
+/// without consuming any input. If the parser fails after consuming input, the resulting
+/// parser fails as well.
+///
+/// Also available as an extension function: [`or_default`](crate::core::ParserExt::or_default)
+///
+/// ### Arguments
+/// * `p` - the parser
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{or_default, right};
+/// use anpa::number::integer;
+/// use anpa::parsers::skip;
+///
+/// let parse_sign = or_default(right(skip('-'), integer::<u32, _, _, _>()));
+///
+/// assert_eq!(parse(parse_sign, "-12").result, Some(12));
+/// assert_eq!(parse(parse_sign, "12").result, Some(0));
+/// assert_eq!(parse(parse_sign, "-x").result, None);
+/// ```
+#[inline]
+pub fn or_default<I: SliceLike, O: Default, S>(p: impl Parser<I, O, S>) -> impl Parser<I, O, S> {
+    optional_with_fn(p, O::default)
+}
+
+/// Transform a parser to a parser that succeeds with `default` if the parser fails without
+/// consuming any input. If the parser fails after consuming input, the resulting parser fails
+/// as well.
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `default` - the result if the parser fails
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{optional_with, right};
+/// use anpa::parsers::{item_while, skip};
+///
+/// let parse_port = optional_with(right(skip(':'), item_while(|c: char| c.is_ascii_digit())), "80");
+///
+/// assert_eq!(parse(parse_port, ":8080").result, Some("8080"));
+/// assert_eq!(parse(parse_port, "/").result, Some("80"));
+/// ```
+#[inline]
+pub fn optional_with<I: SliceLike, O: Copy, S>(p: impl Parser<I, O, S>, default: O) -> impl Parser<I, O, S> {
+    optional_with_fn(p, move || default)
+}
+
+#[inline]
+fn optional_with_fn<I: SliceLike, O, S>(p: impl Parser<I, O, S>,
+                                        default: impl FnOnce() -> O + Copy
+) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let pos = s.input;
+        p(s).or_else(|| {
+            if s.cut || s.input.slice_len() != pos.slice_len() {
+                None
+            } else {
+                Some(default())
+            }
+        })
     })
 }
 
@@ -287,6 +409,95 @@ pub fn attempt<I: SliceLike, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, O,
     })
 }
 
+/// Transform a parser to a parser that commits to its parse once it has consumed input. If `p`
+/// fails after consuming input, the failure is turned into a hard failure, where alternatives
+/// (e.g. [`or`](fn@or), [`succeed`] or the end of [`many`]) are not tried, so that the whole parse fails.
+///
+/// This is useful to prevent backtracking once it's clear which alternative is being parsed,
+/// e.g. after a keyword, which otherwise could make a parse succeed in an unexpected way.
+///
+/// The hard failure can be recovered from using [`recover_with`].
+///
+/// Also available as an extension function: [`cut`](crate::core::ParserExt::cut)
+///
+/// ### Arguments
+/// * `p` - the parser
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{cut, or, right};
+/// use anpa::number::integer;
+/// use anpa::parsers::{rest, skip};
+///
+/// let statement = or(cut(right(skip("let "), integer::<u32, _, _, _>()).void()),
+///                    rest().void());
+///
+/// assert_eq!(parse(statement, "let 1").result, Some(()));
+/// assert_eq!(parse(statement, "x = 1").result, Some(()));
+///
+/// // Without `cut`, this would be parsed by `rest`.
+/// assert_eq!(parse(statement, "let x").result, None);
+/// ```
+#[inline]
+pub fn cut<I: SliceLike, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let pos = s.input;
+        let res = p(s);
+        if res.is_none() && s.input.slice_len() != pos.slice_len() {
+            s.cut = true;
+        }
+        res
+    })
+}
+
+/// Transform a parser to a parser that names what it expects, e.g. "version number", for
+/// error reporting. If `p` fails, the label is reported by
+/// [`parse_all`](crate::core::parse_all) in [`AnpaError::expected`](crate::core::AnpaError::expected),
+/// with the position where `p` started, e.g. as "expected version number at offset 3".
+///
+/// If several labelled parsers fail, the label of the one that started furthest into the
+/// input is reported. For labelled parsers starting at the same position, the last one to
+/// fail is reported, i.e. an outer label takes precedence over the labels of its parts.
+///
+/// Labels don't affect the parse. When `p` fails, the label is recorded in the parser state,
+/// which costs a comparison and a store. This is also done when using e.g.
+/// [`parse`](crate::core::parse), which doesn't report the label.
+///
+/// Also available as an extension function: [`labelled`](crate::core::ParserExt::labelled)
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `label` - what `p` expects
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{labelled, right};
+/// use anpa::number::integer;
+/// use anpa::parsers::skip;
+///
+/// let version = right(skip('v'), labelled(integer::<u32, _, _, _>(), "version number"));
+///
+/// let err = parse_all(version, "vx").unwrap_err();
+/// assert_eq!(err.expected, Some("version number"));
+/// assert_eq!(err.offset, 1);
+/// assert_eq!(err.to_string(), "expected version number at offset 1");
+/// ```
+#[inline]
+pub fn labelled<I: SliceLike, O, S>(p: impl Parser<I, O, S>,
+                                    label: &'static str
+) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let remaining = s.input.slice_len();
+        let res = p(s);
+        if res.is_none() {
+            s.expect(label, remaining);
+        }
+        res
+    })
+}
+
 /// Transform a parser to a parser that along with its result also returns how many items that
 /// were parsed.
 ///
@@ -310,10 +521,9 @@ pub fn attempt<I: SliceLike, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, O,
 #[inline]
 pub fn count_consumed<I: SliceLike, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, (I::Idx, O), S> {
     create_parser!(s, {
-        let old = s.input.slice_len();
+        let old = s.input;
         let res = p(s)?;
-        let count = old - s.input.slice_len();
-        Some((count, res))
+        Some((consumed_len(old, s.input), res))
     })
 }
 
@@ -340,7 +550,7 @@ pub fn and_parsed<I: SliceLike, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I,
     create_parser!(s, {
         let old_input = s.input;
         let res = p(s)?;
-        Some((old_input.slice_to(old_input.slice_len() - s.input.slice_len()), res))
+        Some((consumed_input(old_input, s.input), res))
     })
 }
 
@@ -368,7 +578,239 @@ pub fn get_parsed<I: SliceLike, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I,
     create_parser!(s, {
         let old_input = s.input;
         p(s)?;
-        Some(old_input.slice_to(old_input.slice_len() - s.input.slice_len()))
+        Some(consumed_input(old_input, s.input))
+    })
+}
+
+/// Create a parser that parses until `p` succeeds, trying `p` at every position of the input.
+/// This is the equivalent of [`until`](crate::parsers::until) for terminators that can't be
+/// expressed as a [`Needle`](crate::needle::Needle).
+///
+/// On a successful parse, all items until the position where `p` succeeded will be returned,
+/// together with the result of `p`.
+///
+/// Prefer [`until`](crate::parsers::until) when possible, or
+/// [`until_parser_from`](crate::findbyte::until_parser_from) if the first byte of the
+/// terminator is known, since this parser tries `p` at every position of the input.
+///
+/// ### Consuming
+/// Consumes all items before the terminator, and the input consumed by `p`. Nothing is consumed
+/// on failure.
+///
+/// ### Arguments
+/// * `p` - the parser for the terminator
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{right, until_parser};
+/// use anpa::parsers::{line, skip};
+///
+/// // Parse until a line starting with `---`.
+/// let p = until_parser(right(skip("\n---"), line()));
+///
+/// let res = parse(p, "a\nb\n--- end\nc");
+/// assert_eq!(res.result, Some(("a\nb", " end")));
+/// assert_eq!(res.state, "c");
+/// ```
+#[inline]
+pub fn until_parser<I: SliceLike, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, (I, O), S> {
+    create_parser!(s, {
+        let start = s.input;
+
+        loop {
+            let pos = s.input;
+            if let Some(res) = p(s) {
+                return Some((start.slice_to(start.slice_len() - pos.slice_len()), res))
+            } else if s.cut {
+                return None
+            }
+
+            match pos.slice_first_if(|_| true) {
+                Some((_, rest)) => s.input = rest,
+                None => {
+                    s.input = start;
+                    return None
+                }
+            }
+        }
+    })
+}
+
+/// Create a parser that recovers from failures of `p`, e.g. for parsing documents that
+/// contain errors. If `p` fails, the input is skipped until `sync` succeeds, as in
+/// [`until_parser`], and the skipped input is returned as `Err`. If `sync` never succeeds,
+/// the rest of the input is skipped.
+///
+/// This allows grammars to report multiple errors, instead of stopping at the first one.
+/// Failures of parsers wrapped in [`cut`] are recovered from as well.
+///
+/// ### Consuming
+/// On success of `p`, what `p` consumes. Otherwise, the skipped input and the input consumed
+/// by `sync`. Fails without consuming if `p` fails and the input is empty.
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `sync` - the parser for the synchronization point, e.g. `;` or a newline
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{left, many_to_vec, no_separator, recover_with};
+/// use anpa::number::integer;
+/// use anpa::parsers::skip;
+///
+/// let statement = recover_with(left(integer(), skip(';')), skip(';'));
+/// let p = many_to_vec(statement, true, no_separator());
+///
+/// assert_eq!(parse(p, "1;x;3;4y;5").result,
+///            Some(vec![Ok(1), Err("x"), Ok(3), Err("4y"), Err("5")]));
+/// ```
+#[inline]
+pub fn recover_with<I: SliceLike, O, O2, S>(p: impl Parser<I, O, S>,
+                                            sync: impl Parser<I, O2, S>
+) -> impl Parser<I, Result<O, I>, S> {
+    create_parser!(s, {
+        let pos = s.input;
+        if let Some(res) = p(s) {
+            return Some(Ok(res))
+        }
+
+        s.input = pos;
+        s.cut = false;
+        if pos.slice_is_empty() {
+            return None
+        }
+
+        match until_parser(sync)(s) {
+            Some((skipped, _)) => Some(Err(skipped)),
+            None => Some(Err(rest()(s)?))
+        }
+    })
+}
+
+/// Create a parser that scans the input for the first position where `p` succeeds, discarding
+/// any items before it. This is useful for extracting data from noisy input, e.g. logs.
+///
+/// On a successful parse, the offset of the match from the start of the scan is returned,
+/// together with the result of `p`. Use [`until_parser`] to get the discarded input instead.
+///
+/// Note: For `&str`, the offset is in bytes.
+///
+/// ### Consuming
+/// Consumes all items before the match, and the input consumed by `p`. Nothing is consumed
+/// on failure.
+///
+/// ### Arguments
+/// * `p` - the parser to scan for
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{right, scan_for};
+/// use anpa::number::integer;
+/// use anpa::parsers::skip;
+///
+/// let status = scan_for(right(skip("status="), integer::<u32, _, _, _>()));
+///
+/// let res = parse(status, "GET /index.html status=404 1ms");
+/// assert_eq!(res.result, Some((16, 404)));
+/// assert_eq!(res.state, " 1ms");
+/// ```
+#[inline]
+pub fn scan_for<I: SliceLike, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, (I::Idx, O), S> {
+    map(until_parser(p), |(skipped, res): (I, O)| (skipped.slice_len(), res))
+}
+
+#[cfg(feature = "alloc")]
+/// Create a parser that scans the whole input for all non-overlapping matches of `p`,
+/// discarding all items between them. This parser always succeeds.
+///
+/// The result contains the offset of each match from the start of the scan, together with
+/// the result of `p`. A failure of `p` inside [`cut`] is treated like any other failure, i.e.
+/// the scan continues at the next item.
+///
+/// Note: For `&str`, the offsets are in bytes.
+///
+/// ### Consuming
+/// The whole input.
+///
+/// ### Arguments
+/// * `p` - the parser to scan for
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{right, scan_all};
+/// use anpa::number::integer;
+/// use anpa::parsers::skip;
+///
+/// let ids = scan_all(right(skip('#'), integer::<u32, _, _, _>()));
+///
+/// assert_eq!(parse(ids, "fixes #12 and #3, see # and #40").result,
+///            Some(vec![(6, 12), (14, 3), (28, 40)]));
+/// ```
+#[inline]
+pub fn scan_all<I: SliceLike, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, Vec<(I::Idx, O)>, S> {
+    create_parser!(s, {
+        let start = s.input;
+        let mut matches = Vec::new();
+
+        loop {
+            let pos = s.input;
+            match p(s) {
+                Some(res) => {
+                    matches.push((start.slice_len() - pos.slice_len(), res));
+
+                    // Skip an item after an empty match, to guarantee progress.
+                    if s.input.slice_len() != pos.slice_len() {
+                        continue
+                    }
+                }
+                None => s.cut = false
+            }
+
+            match pos.slice_first_if(|_| true) {
+                Some((_, rest)) => s.input = rest,
+                None => {
+                    s.input = pos;
+                    return Some(matches)
+                }
+            }
+        }
+    })
+}
+
+/// Create a parser for a region of input that is followed by a checksum, e.g. in binary
+/// protocols. The input consumed by `region` is passed to `verify` along with the result of
+/// `checksum`, and the parser fails if `verify` returns `false`.
+///
+/// ### Arguments
+/// * `region` - the parser for the region covered by the checksum
+/// * `checksum` - the parser for the checksum, following the region
+/// * `verify` - a function validating the parsed region against the checksum
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{times, verified};
+/// use anpa::parsers::item;
+///
+/// let sum = |bytes: &[u8]| bytes.iter().fold(0u8, |acc, b| acc.wrapping_add(*b));
+/// let packet = verified(times(3, item()), item(), move |region, checksum: &&u8| sum(region) == **checksum);
+///
+/// assert_eq!(parse(packet, [1, 2, 3, 6].as_slice()).result, Some([1, 2, 3].as_slice()));
+/// assert_eq!(parse(packet, [1, 2, 3, 7].as_slice()).result, None);
+/// ```
+#[inline]
+pub fn verified<I: SliceLike, O, C, S>(region: impl Parser<I, O, S>,
+                                       checksum: impl Parser<I, C, S>,
+                                       verify: impl FnOnce(I, &C) -> bool + Copy
+) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let (parsed, res) = and_parsed(region)(s)?;
+        let sum = checksum(s)?;
+        verify(parsed, &sum).then_some(res)
     })
 }
 
@@ -400,10 +842,72 @@ pub fn times<I: SliceLike, O, S>(times: u32, p: impl Parser<I, O, S>) -> impl Pa
         for _ in 0..times {
             p(s)?;
         }
-        Some(old_input.slice_to(old_input.slice_len() - s.input.slice_len()))
+        Some(consumed_input(old_input, s.input))
+    })
+}
+
+/// Create a parser that parses a line using [`line`](fn@line), and then applies `p` to the
+/// contents of the line. Any part of the line not consumed by `p` is ignored.
+///
+/// ### Arguments
+/// * `p` - the parser for the contents of the line
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::in_line;
+/// use anpa::number::integer;
+///
+/// let p = in_line(integer());
+///
+/// let res = parse(p, "123 and more\n456");
+/// assert_eq!(res.result, Some(123));
+/// assert_eq!(res.state, "456");
+/// ```
+#[inline]
+pub fn in_line<A: CharLike, I: SliceLike<RefItem = A>, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let end = s.input.slice_len();
+        let line = line()(s)?;
+        let mut line_state = AnpaState::new(line, s.user_state);
+        let res = p(&mut line_state);
+        s.cut = line_state.cut;
+
+        // Translate the position of any expectation to the outer input.
+        if let Some((label, remaining)) = line_state.expected {
+            s.expect(label, end - (line.slice_len() - remaining));
+        }
+        res
     })
 }
 
+/// Create a parser that applies `p` to each line of the input using [`in_line`], until `p`
+/// fails or there are no lines left. Returns the parsed input.
+///
+/// Use the user state, or combine [`in_line`] with e.g. [`fold`], to collect the results.
+///
+/// ### Arguments
+/// * `p` - the parser for the contents of each line
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{lift_to_state, lines};
+/// use anpa::number::integer;
+///
+/// let p = lines(lift_to_state(|sum: &mut u32, n: u32| *sum += n, integer()));
+///
+/// let mut sum = 0;
+/// let res = parse_state(p, "1\n2\r\n3\nx", &mut sum);
+/// assert_eq!(res.result, Some("1\n2\r\n3\n"));
+/// assert_eq!(res.state.input, "x");
+/// assert_eq!(sum, 6);
+/// ```
+#[inline]
+pub fn lines<A: CharLike, I: SliceLike<RefItem = A>, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, I, S> {
+    many(attempt(in_line(p)), true, no_separator())
+}
+
 /// Combine one parser with another, while ignoring the result of the former.
 /// The second parser will only be attempted if the first succeeds.
 ///
@@ -499,6 +1003,8 @@ macro_rules! internal_or {
         ///
         /// Also available as variadic macro.
         ///
+        #[doc=concat!("Also available as an extension function: [`", stringify!($id), "`](crate::core::ParserExt::", stringify!($id), ")")]
+        ///
         #[doc=$comment]
         ///
         /// ### Arguments
@@ -529,7 +1035,7 @@ macro_rules! internal_or {
             create_parser!(s, {
                 let pos = s.input;
                 p1(s).or_else(|| {
-                    if !$allow_partial && s.input.slice_len() != pos.slice_len() {
+                    if s.cut || (!$allow_partial && s.input.slice_len() != pos.slice_len()) {
                         None
                     } else {
                         s.input = pos;
@@ -585,7 +1091,7 @@ macro_rules! internal_or_diff {
                 if p1(s).is_some() {
                     Some(())
                 } else {
-                    if (!$allow_partial && s.input.slice_len() != pos.slice_len()) {
+                    if s.cut || (!$allow_partial && s.input.slice_len() != pos.slice_len()) {
                         None
                     } else {
                         s.input = pos;
@@ -631,8 +1137,345 @@ pub fn lift_to_state<I: SliceLike, S, O1, O2>(f: impl FnOnce(&mut S, O1) -> O2 +
     })
 }
 
-/// Only for use with the `many` family of combinators. Use this function to create the separator
-/// argument when parsing multiple elements.
+/// Create a parser that allows for using and modifying a part of the user state while
+/// transforming the result. The user state provides the part by implementing `AsMut<T>`, the
+/// same way as e.g. [`Diagnostics`](crate::diagnostics::Diagnostics) is provided, so that the
+/// parser can be used with any user state providing it.
+///
+/// ### Arguments
+/// * `f` - a transformation function that is also allowed to use and modify the part of the
+///   user state.
+/// * `p` - the parser
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::lift_to_substate;
+/// use anpa::number::integer;
+///
+/// struct Totals(u32);
+///
+/// // Can be used with any user state that provides `Totals`.
+/// fn amount<'a, S: AsMut<Totals>>() -> impl StrParser<'a, u32, S> {
+///     lift_to_substate(|t: &mut Totals, n: u32| { t.0 += n; n }, integer())
+/// }
+///
+/// struct State { totals: Totals, lines: usize }
+///
+/// impl AsMut<Totals> for State {
+///     fn as_mut(&mut self) -> &mut Totals {
+///         &mut self.totals
+///     }
+/// }
+///
+/// let mut state = State { totals: Totals(5), lines: 0 };
+/// assert_eq!(parse_state(amount(), "10", &mut state).result, Some(10));
+/// assert_eq!(state.totals.0, 15);
+/// ```
+#[inline]
+pub fn lift_to_substate<I: SliceLike, S: AsMut<T>, T: ?Sized, O1, O2>(f: impl FnOnce(&mut T, O1) -> O2 + Copy,
+                                                                     p: impl Parser<I, O1, S>
+) -> impl Parser<I, O2, S> {
+    create_parser!(s, {
+        p(s).map(|res| f(s.user_state.as_mut(), res))
+    })
+}
+
+/// Transform a parser using a part of the user state, provided by implementing `AsMut<T>`,
+/// to a parser that can be used with the whole user state. This allows for using parsers
+/// written for e.g. [`Diagnostics`](crate::diagnostics::Diagnostics) as user state together
+/// with application specific state.
+///
+/// See [`stateless`] for parsers without user state.
+///
+/// ### Arguments
+/// * `p` - the parser using the part of the user state
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{depth_limited, left, middle, or, with_substate};
+/// use anpa::defer_parser;
+/// use anpa::parsers::{skip, take};
+///
+/// struct Depth(usize);
+///
+/// impl AsMut<usize> for Depth {
+///     fn as_mut(&mut self) -> &mut usize {
+///         &mut self.0
+///     }
+/// }
+///
+/// // A parser with the nesting depth as user state.
+/// fn nested<'a>() -> impl StrParser<'a, &'a str, usize> {
+///     defer_parser!(or(take("x"), depth_limited(|d| d, 2, middle(skip('('), nested(), skip(')')))))
+/// }
+///
+/// let p = left(with_substate(nested()), skip(';'));
+/// assert_eq!(parse_state(p, "((x));", &mut Depth(0)).result, Some("x"));
+/// assert_eq!(parse_state(p, "(((x)));", &mut Depth(0)).result, None);
+/// ```
+#[inline]
+pub fn with_substate<I: SliceLike, O, S: AsMut<T>, T>(p: impl Parser<I, O, T>) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let mut inner = AnpaState { input: s.input, user_state: s.user_state.as_mut(), cut: s.cut, expected: s.expected };
+        let res = p(&mut inner);
+        let AnpaState { input, cut, expected, .. } = inner;
+        (s.input, s.cut, s.expected) = (input, cut, expected);
+        res
+    })
+}
+
+/// Transform a parser without user state, e.g. [`json::value_parser`](crate::json::value_parser),
+/// to a parser that can be used with any user state.
+///
+/// ### Arguments
+/// * `p` - the parser without user state
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{lift_to_state, right, stateless};
+/// use anpa::number::integer;
+/// use anpa::parsers::skip;
+///
+/// let number = stateless(integer::<u32, _, _, _>());
+/// let p = lift_to_state(|count: &mut usize, n| { *count += 1; n }, right(skip('#'), number));
+///
+/// let mut count = 0;
+/// assert_eq!(parse_state(p, "#12", &mut count).result, Some(12));
+/// assert_eq!(count, 1);
+/// ```
+#[inline]
+pub fn stateless<I: SliceLike, O, S>(p: impl Parser<I, O, ()>) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let mut inner = AnpaState { input: s.input, user_state: &mut (), cut: s.cut, expected: s.expected };
+        let res = p(&mut inner);
+        (s.input, s.cut, s.expected) = (inner.input, inner.cut, inner.expected);
+        res
+    })
+}
+
+/// Create a parser that temporarily replaces a value in the user state while running `p`.
+/// The new value is computed from the current one using `f`, and the original value is
+/// always restored afterwards, regardless of whether `p` succeeds or fails.
+///
+/// This is useful for context that is only valid for a part of the parse, e.g. indentation
+/// levels, recursion depth or flags like "inside string".
+///
+/// ### Arguments
+/// * `field` - a function selecting the value in the user state.
+/// * `f` - a function producing the new value from the current one.
+/// * `p` - the parser
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{lift_to_state, with_state_update};
+/// use anpa::parsers::take;
+///
+/// struct Context { depth: u32 }
+///
+/// let current_depth = lift_to_state(|c: &mut Context, _| c.depth, take('x'));
+/// let p = with_state_update(|c: &mut Context| &mut c.depth, |d| d + 1, current_depth);
+///
+/// let mut context = Context { depth: 0 };
+/// assert_eq!(parse_state(p, "x", &mut context).result, Some(1));
+/// assert_eq!(context.depth, 0);
+/// ```
+#[inline]
+pub fn with_state_update<I: SliceLike, O, S, T>(field: impl FnOnce(&mut S) -> &mut T + Copy,
+                                                f: impl FnOnce(&T) -> T + Copy,
+                                                p: impl Parser<I, O, S>
+) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let value = field(s.user_state);
+        let new = f(value);
+        let old = core::mem::replace(value, new);
+        let res = p(s);
+        *field(s.user_state) = old;
+        res
+    })
+}
+
+/// Create a parser that temporarily sets a value in the user state to `value` while running
+/// `p`. The original value is always restored afterwards, regardless of whether `p` succeeds
+/// or fails.
+///
+/// See [`with_state_update`] for a version where the new value depends on the current one.
+///
+/// ### Arguments
+/// * `field` - a function selecting the value in the user state.
+/// * `value` - the value to use while running `p`.
+/// * `p` - the parser
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{filter, lift_to_state, with_state_value};
+/// use anpa::parsers::item;
+///
+/// // Only allow spaces when inside a string.
+/// let string_char = filter(lift_to_state(|in_string: &mut bool, c| (*in_string, c), item()),
+///                          |(in_string, c)| *in_string || *c != ' ')
+///     .map(|(_, c)| c);
+/// let in_string = with_state_value(|s: &mut bool| s, true, string_char);
+///
+/// let mut state = false;
+/// assert_eq!(parse_state(string_char, " ", &mut state).result, None);
+/// assert_eq!(parse_state(in_string, " ", &mut state).result, Some(' '));
+/// assert!(!state);
+/// ```
+#[inline]
+pub fn with_state_value<I: SliceLike, O, S, T: Copy>(field: impl FnOnce(&mut S) -> &mut T + Copy,
+                                                     value: T,
+                                                     p: impl Parser<I, O, S>
+) -> impl Parser<I, O, S> {
+    with_state_update(field, move |_| value, p)
+}
+
+/// Create a parser that limits how deeply `p` can be nested, e.g. in recursive parsers.
+/// The current depth is tracked in the user state, and the parser fails without running `p`
+/// if the depth has already reached `max`.
+///
+/// This can be used to protect against stack overflows for deeply nested input.
+///
+/// ### Arguments
+/// * `field` - a function selecting the current depth in the user state. The depth should
+///   be initialized to 0.
+/// * `max` - the maximum allowed depth.
+/// * `p` - the parser
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::defer_parser;
+/// use anpa::combinators::{depth_limited, middle, not_empty, or};
+/// use anpa::parsers::{item_while, take};
+///
+/// fn in_parens<'a>() -> impl StrParser<'a, &'a str, usize> {
+///     defer_parser!(or(not_empty(item_while(|c: char| c.is_alphanumeric())),
+///                      depth_limited(|d| d, 3, middle(take('('), in_parens(), take(')')))))
+/// }
+///
+/// assert_eq!(parse_state(in_parens(), "(((x)))", &mut 0).result, Some("x"));
+/// assert_eq!(parse_state(in_parens(), "((((x))))", &mut 0).result, None);
+/// ```
+#[inline]
+pub fn depth_limited<I: SliceLike, O, S>(field: impl FnOnce(&mut S) -> &mut usize + Copy,
+                                         max: usize,
+                                         p: impl Parser<I, O, S>
+) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let depth = field(s.user_state);
+        if *depth >= max {
+            return None
+        }
+        *depth += 1;
+        let res = p(s);
+        *field(s.user_state) -= 1;
+        res
+    })
+}
+
+/// Trait for the separator argument of the `many` family of combinators. Use the
+/// [`separator`], [`no_separator`], [`terminated`] and [`unguarded`] functions to construct it.
+///
+/// `O` is the result type of the separator parser.
+pub trait Separator<I: SliceLike, O, S>: Copy {
+    /// Parse the separator following an element. Returns whether it was successful.
+    fn parse_separator(self, s: &mut AnpaState<I, S>) -> bool;
+
+    /// Whether a separator is allowed after the last element.
+    fn allow_trailing(self) -> bool;
+
+    /// Parse what follows the last element. Returns whether it was successful.
+    fn parse_terminator(self, _s: &mut AnpaState<I, S>) -> bool {
+        true
+    }
+
+    /// Whether each element and its separator must consume input. If not, the repetition
+    /// stops after the first element that doesn't. See [`unguarded`].
+    fn require_progress(self) -> bool {
+        true
+    }
+}
+
+impl<I: SliceLike, O, S, P: Parser<I, O, S>> Separator<I, O, S> for Option<(bool, P)> {
+    #[inline(always)]
+    fn parse_separator(self, s: &mut AnpaState<I, S>) -> bool {
+        self.map_or(true, |(_, sep)| sep(s).is_some())
+    }
+
+    #[inline(always)]
+    fn allow_trailing(self) -> bool {
+        self.map_or(true, |(allow_trailing, _)| allow_trailing)
+    }
+}
+
+/// A separator followed by a terminator. See [`terminated`].
+#[derive(Clone, Copy)]
+pub struct Terminated<Sep, T> {
+    separator: Sep,
+    terminator: T,
+    required: bool
+}
+
+impl<I: SliceLike, O, S, Sep: Separator<I, O, S>, T: Parser<I, (), S>> Separator<I, O, S> for Terminated<Sep, T> {
+    #[inline(always)]
+    fn parse_separator(self, s: &mut AnpaState<I, S>) -> bool {
+        self.separator.parse_separator(s)
+    }
+
+    #[inline(always)]
+    fn allow_trailing(self) -> bool {
+        self.separator.allow_trailing()
+    }
+
+    #[inline(always)]
+    fn parse_terminator(self, s: &mut AnpaState<I, S>) -> bool {
+        let pos = s.input;
+        if (self.terminator)(s).is_some() {
+            return true
+        }
+        s.input = pos;
+        !self.required
+    }
+
+    #[inline(always)]
+    fn require_progress(self) -> bool {
+        self.separator.require_progress()
+    }
+}
+
+/// A separator without the check that the elements consume input. See [`unguarded`].
+#[derive(Clone, Copy)]
+pub struct Unguarded<Sep>(Sep);
+
+impl<I: SliceLike, O, S, Sep: Separator<I, O, S>> Separator<I, O, S> for Unguarded<Sep> {
+    #[inline(always)]
+    fn parse_separator(self, s: &mut AnpaState<I, S>) -> bool {
+        self.0.parse_separator(s)
+    }
+
+    #[inline(always)]
+    fn allow_trailing(self) -> bool {
+        self.0.allow_trailing()
+    }
+
+    #[inline(always)]
+    fn parse_terminator(self, s: &mut AnpaState<I, S>) -> bool {
+        self.0.parse_terminator(s)
+    }
+
+    #[inline(always)]
+    fn require_progress(self) -> bool {
+        false
+    }
+}
+
+/// Only for use with the `many` family of combinators. Use this function to create the separator
+/// argument when parsing multiple elements.
 ///
 /// ### Arguments
 /// * `p` - a parser for the separator
@@ -653,41 +1496,121 @@ pub fn no_separator<I: SliceLike, S>() -> Option<(bool, impl Parser<I, (), S>)>
     Some((false, success()))
 }
 
+/// Only for use with the `many` family of combinators. Use this function to add a terminator
+/// to a separator argument, e.g. for elements separated by `,` and ended by `;`. The
+/// terminator is parsed after the last element, or after the trailing separator if allowed,
+/// and is also parsed if there are no elements.
+///
+/// ### Arguments
+/// * `separator` - the separator, created using [`separator`] or [`no_separator`]
+/// * `terminator` - a parser for the terminator
+/// * `required` - whether the terminator is required. If not, the input is left as is if
+///   the terminator fails.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{many_to_vec, separator, terminated};
+/// use anpa::number::integer;
+/// use anpa::parsers::skip;
+///
+/// let statement = many_to_vec(integer(),
+///                             false,
+///                             terminated(separator(skip(','), false), skip(';'), true));
+///
+/// let res = parse(statement, "1,2,3;4");
+/// assert_eq!(res.result, Some(vec![1, 2, 3]));
+/// assert_eq!(res.state, "4");
+///
+/// assert_eq!(parse(statement, "1,2,3").result, None);
+/// assert_eq!(parse(statement, "1,2,;").result, None);
+/// assert_eq!(parse(statement, ";").result, None);
+/// ```
+#[inline]
+pub fn terminated<I: SliceLike, O, O2, S>(separator: impl Separator<I, O, S>,
+                                          terminator: impl Parser<I, O2, S>,
+                                          required: bool
+) -> impl Separator<I, O, S> {
+    Terminated { separator, terminator: map(terminator, |_| ()), required }
+}
+
+/// Only for use with the `many` family of combinators. Use this function to disable the check
+/// that each element, along with its separator, consumes input.
+///
+/// By default, the repetition stops after an element that doesn't consume input, since
+/// applying the same parser to the same input would otherwise loop forever. Disable this only
+/// for parsers that intentionally don't consume input, and eventually fail for another
+/// reason, e.g. depending on the user state.
+///
+/// ### Arguments
+/// * `separator` - the separator, created using [`separator`], [`no_separator`] or
+///   [`terminated`]
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{lift_to_state, many_count, no_separator, unguarded};
+/// use anpa::create_parser;
+///
+/// // Count down the user state without consuming input.
+/// let countdown = lift_to_state(|n: &mut u32, _| *n -= 1, create_parser!(s, (*s.user_state > 0).then_some(())));
+///
+/// assert_eq!(parse_state(many_count(countdown, true, unguarded(no_separator())), "", &mut 3).result, Some(3));
+/// assert_eq!(parse_state(many_count(countdown, true, no_separator()), "", &mut 3).result, Some(1));
+/// ```
+#[inline]
+pub fn unguarded<I: SliceLike, O, S>(separator: impl Separator<I, O, S>) -> impl Separator<I, O, S> {
+    Unguarded(separator)
+}
+
 #[inline(always)]
 fn many_internal<I: SliceLike, O, O2, S>(
     s: &mut AnpaState<I, S>,
     p: impl Parser<I, O, S>,
-    mut f: impl FnMut(O),
+    mut f: impl FnMut(O) -> ControlFlow<()>,
     allow_empty: bool,
-    separator: Option<(bool, impl Parser<I, O2, S>)>
+    separator: impl Separator<I, O2, S>
 ) -> bool {
     let mut successes = false;
     let mut has_trailing_sep = false;
+    let mut len = s.input.slice_len();
 
     while let Some(res) = p(s) {
         has_trailing_sep = false;
         successes = true;
-        f(res);
+        if f(res).is_break() {
+            break;
+        }
 
-        if let Some((_, sep)) = separator {
-            if sep(s).is_none() {
-                break;
-            }
-            has_trailing_sep = true;
+        if !separator.parse_separator(s) {
+            break;
+        }
+
+        // Without progress, the next iteration would be identical.
+        let new_len = s.input.slice_len();
+        if new_len == len && separator.require_progress() {
+            break;
         }
+        len = new_len;
+        has_trailing_sep = true;
     }
 
-    !separator.is_some_and(|(allow_trailing, _)| !allow_trailing && has_trailing_sep)
+    !s.cut && (!has_trailing_sep || separator.allow_trailing())
         && (allow_empty || successes)
+        && separator.parse_terminator(s)
 }
 
 /// Apply a parser until it fails and return the parsed input.
 ///
+/// If an element and its separator don't consume any input, the repetition stops after that
+/// element, since it would otherwise never end. This applies to all combinators in the `many`
+/// family, and can be disabled using [`unguarded`].
+///
 /// ### Arguments
 /// * `p` - the parser
 /// * `allow_empty` - whether no parse should be considered successful.
 /// * `separator` - the separator to be used between parses. Use the `no_separator`/`separator`
-///                 functions to construct this parameter.
+///   functions to construct this parameter, and `terminated` to add a terminator.
 ///
 /// ### Example
 /// ```
@@ -708,12 +1631,47 @@ fn many_internal<I: SliceLike, O, O2, S>(
 #[inline]
 pub fn many<I: SliceLike, O, O2, S>(p: impl Parser<I, O, S>,
                                     allow_empty: bool,
-                                    separator: Option<(bool, impl Parser<I, O2, S>)>,
+                                    separator: impl Separator<I, O2, S>,
 ) -> impl Parser<I, I, S> {
     create_parser!(s, {
         let old_input = s.input;
-        many_internal(s, p, |_| {}, allow_empty, separator)
-            .then_some(old_input.slice_to(old_input.slice_len() - s.input.slice_len()))
+        many_internal(s, p, |_| ControlFlow::Continue(()), allow_empty, separator)
+            .then_some(consumed_input(old_input, s.input))
+    })
+}
+
+/// Apply a parser until it fails and return the number of successful parses.
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `allow_empty` - whether no parse should be considered successful.
+/// * `separator` - the separator to be used between parses. Use the `no_separator`/`separator`
+///   functions to construct this parameter, and `terminated` to add a terminator.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{many_count, right, separator};
+/// use anpa::parsers::{item_while, skip};
+///
+/// // Require at least three path components.
+/// let components = many_count(item_while(|c: char| c != '/'), false, separator(skip('/'), false))
+///     .filter(|n| *n >= 3);
+///
+/// assert_eq!(parse(right(skip('/'), components), "/usr/local/bin").result, Some(3));
+/// assert_eq!(parse(right(skip('/'), components), "/usr/bin").result, None);
+/// ```
+#[inline]
+pub fn many_count<I: SliceLike, O, O2, S>(p: impl Parser<I, O, S>,
+                                          allow_empty: bool,
+                                          separator: impl Separator<I, O2, S>,
+) -> impl Parser<I, usize, S> {
+    create_parser!(s, {
+        let mut count = 0;
+        many_internal(s, p, |_| {
+            count += 1;
+            ControlFlow::Continue(())
+        }, allow_empty, separator).then_some(count)
     })
 }
 
@@ -723,10 +1681,10 @@ pub fn many<I: SliceLike, O, O2, S>(p: impl Parser<I, O, S>,
 /// * `p` - the parser
 /// * `init` - a function producing the initial result
 /// * `f` - a function taking the accumulator as `&mut` along with the result of each
-///         successful parse
+///   successful parse
 /// * `allow_empty` - whether no parse should be considered successful.
 /// * `separator` - the separator to be used between parses. Use the `no_separator`/`separator`
-///                 functions to construct this parameter.
+///   functions to construct this parameter, and `terminated` to add a terminator.
 ///
 /// ### Example
 /// ```
@@ -751,7 +1709,61 @@ pub fn fold<I: SliceLike, O, O2, S, R>(p: impl Parser<I, O, S>,
                                        init: impl FnOnce() -> R + Copy,
                                        f: impl FnOnce(&mut R, O) + Copy,
                                        allow_empty: bool,
-                                       separator: Option<(bool, impl Parser<I, O2, S>)>,
+                                       separator: impl Separator<I, O2, S>,
+) -> impl Parser<I, R, S> {
+    create_parser!(s, {
+        let mut res = init();
+        many_internal(s, p, |x| {
+            f(&mut res, x);
+            ControlFlow::Continue(())
+        }, allow_empty, separator).then_some(res)
+    })
+}
+
+/// Apply a parser repeatedly and accumulate a result in the spirit of fold, while the
+/// accumulating function `f` returns `ControlFlow::Continue`. Once `f` returns
+/// `ControlFlow::Break`, no more elements will be parsed.
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `init` - a function producing the initial result
+/// * `f` - a function taking the accumulator as `&mut` along with the result of each
+///   successful parse, returning whether to continue.
+/// * `allow_empty` - whether no parse should be considered successful.
+/// * `separator` - the separator to be used between parses. Use the `no_separator`/`separator`
+///   functions to construct this parameter, and `terminated` to add a terminator.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{fold_while, separator};
+/// use anpa::number::integer;
+/// use anpa::parsers::skip;
+/// use std::ops::ControlFlow;
+///
+/// // Sum numbers until the sum exceeds 5
+/// let parse_nums = fold_while(
+///     integer().map(|n: u32| n),
+///     || 0,
+///     |acc, n: u32| {
+///         *acc += n;
+///         if *acc > 5 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+///     },
+///     false,
+///     separator(skip(','), false));
+///
+/// let input = "1,2,3,4";
+///
+/// let res = parse(parse_nums, input);
+/// assert_eq!(res.result, Some(6));
+/// assert_eq!(res.state, ",4");
+/// ```
+#[inline]
+pub fn fold_while<I: SliceLike, O, O2, S, R>(p: impl Parser<I, O, S>,
+                                             init: impl FnOnce() -> R + Copy,
+                                             f: impl FnOnce(&mut R, O) -> ControlFlow<()> + Copy,
+                                             allow_empty: bool,
+                                             separator: impl Separator<I, O2, S>,
 ) -> impl Parser<I, R, S> {
     create_parser!(s, {
         let mut res = init();
@@ -760,14 +1772,14 @@ pub fn fold<I: SliceLike, O, O2, S, R>(p: impl Parser<I, O, S>,
     })
 }
 
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 /// Apply a parser until it fails and store the results in a `Vec`.
 ///
 /// ### Arguments
 /// * `p` - the parser
 /// * `allow_empty` - whether no parse should be considered successful.
 /// * `separator` - the separator to be used between parses. Use the `no_separator`/`separator`
-///                 functions to construct this parameter.
+///   functions to construct this parameter, and `terminated` to add a terminator.
 ///
 /// ### Example
 /// ```
@@ -788,11 +1800,44 @@ pub fn fold<I: SliceLike, O, O2, S, R>(p: impl Parser<I, O, S>,
 #[inline]
 pub fn many_to_vec<I: SliceLike, O, O2, S>(p: impl Parser<I, O, S>,
                                            allow_empty: bool,
-                                           separator: Option<(bool, impl Parser<I, O2, S>)>,
+                                           separator: impl Separator<I, O2, S>,
 ) -> impl Parser<I, Vec<O>, S> {
     fold(p, Vec::new, |v, x| v.push(x), allow_empty, separator)
 }
 
+#[cfg(feature = "alloc")]
+/// Apply a parser until it fails and append the results to a `String`. The result type
+/// of `p` can be anything that `String` can be extended with, e.g. `char` or `&str`.
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `allow_empty` - whether no parse should be considered successful.
+/// * `separator` - the separator to be used between parses. Use the `no_separator`/`separator`
+///   functions to construct this parameter, and `terminated` to add a terminator.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{many_to_string, no_separator, right};
+/// use anpa::parsers::{item_if, skip};
+/// use anpa::or;
+///
+/// // Unescape a string where `\` is used as escape character
+/// let unescaped = or!(right(skip('\\'), item_if(|_| true)), item_if(|c| c != '\\'));
+/// let parse_string = many_to_string(unescaped, true, no_separator());
+///
+/// let input = r"a\bc\\d";
+///
+/// assert_eq!(parse(parse_string, input).result, Some(r"abc\d".to_owned()));
+/// ```
+#[inline]
+pub fn many_to_string<I: SliceLike, O, O2, S>(p: impl Parser<I, O, S>,
+                                              allow_empty: bool,
+                                              separator: impl Separator<I, O2, S>,
+) -> impl Parser<I, String, S> where String: Extend<O> {
+    fold(p, String::new, |string, x| string.extend(core::iter::once(x)), allow_empty, separator)
+}
+
 #[cfg(feature = "std")]
 /// Apply a parser until it fails and store the results in a `HashMap`.
 /// The parser `p` must have a result type `(K, V)`, where the key `K: Hash + Eq`.
@@ -801,7 +1846,7 @@ pub fn many_to_vec<I: SliceLike, O, O2, S>(p: impl Parser<I, O, S>,
 /// * `p` - the parser
 /// * `allow_empty` - whether no parse should be considered successful.
 /// * `separator` - the separator to be used between parses. Use the `no_separator`/`separator`
-///                 functions to construct this parameter.
+///   functions to construct this parameter, and `terminated` to add a terminator.
 ///
 /// ### Example
 /// ```
@@ -830,12 +1875,12 @@ pub fn many_to_vec<I: SliceLike, O, O2, S>(p: impl Parser<I, O, S>,
 #[inline]
 pub fn many_to_map<I: SliceLike, K: Hash + Eq, V, O2, S>(p: impl Parser<I, (K, V), S>,
                                                          allow_empty: bool,
-                                                         separator: Option<(bool, impl Parser<I, O2, S>)>,
+                                                         separator: impl Separator<I, O2, S>,
 ) -> impl Parser<I, HashMap<K, V>, S> {
     fold(p, HashMap::new, |m, (k, v)| { m.insert(k, v); }, allow_empty, separator)
 }
 
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 /// Apply a parser until it fails and store the results in a `BTreeMap`.
 /// The parser `p` must have a result type `(K, V)`, where the key `K: Ord`.
 /// This might give better performance than `many_to_map`.
@@ -844,18 +1889,142 @@ pub fn many_to_map<I: SliceLike, K: Hash + Eq, V, O2, S>(p: impl Parser<I, (K, V
 /// * `p` - the parser
 /// * `allow_empty` - whether no parse should be considered successful.
 /// * `separator` - the separator to be used between parses. Use the `no_separator`/`separator`
-///                 functions to construct this parameter.
+///   functions to construct this parameter, and `terminated` to add a terminator.
 ///
 /// ### Example
 /// See [`many_to_map`]
 #[inline]
 pub fn many_to_map_ordered<I: SliceLike, K: Ord, V, O2, S>(p: impl Parser<I, (K, V), S>,
                                                            allow_empty: bool,
-                                                           separator: Option<(bool, impl Parser<I, O2, S>)>,
+                                                           separator: impl Separator<I, O2, S>,
 ) -> impl Parser<I, BTreeMap<K, V>, S> {
     fold(p, BTreeMap::new, |m, (k, v)| { m.insert(k, v); }, allow_empty, separator)
 }
 
+/// Trait describing one level of a nested structure, e.g. an array or an object,
+/// for use with [`fold_nested`].
+pub trait NestedFrame<I: SliceLike, S> {
+    /// The type of the values, both nested and atomic.
+    type Value;
+
+    /// Parse anything that precedes an element in this frame, e.g. the key of an object entry.
+    fn element_prefix(&mut self, _s: &mut AnpaState<I, S>) -> Option<()> {
+        Some(())
+    }
+
+    /// Parse the end of this frame, e.g. a closing bracket.
+    fn close(&self, s: &mut AnpaState<I, S>) -> Option<()>;
+
+    /// Add a parsed element to this frame.
+    fn push(&mut self, value: Self::Value);
+
+    /// Convert this frame to a value once it has been closed.
+    fn finish(self) -> Self::Value;
+}
+
+#[cfg(feature = "alloc")]
+/// Parse a nested structure iteratively, using an explicit stack instead of recursion.
+/// This allows for parsing arbitrarily deep input without overflowing the call stack,
+/// which can happen when using recursive parsers created with [`defer_parser!`].
+///
+/// A value is either started by `open`, producing a new [`NestedFrame`] whose elements are
+/// separated by `separator` until the frame is closed, or it is parsed by `atom`.
+///
+/// ### Arguments
+/// * `open` - a parser for the start of a nested frame
+/// * `atom` - a parser for non-nested values
+/// * `separator` - a parser for the separator between elements in a frame
+/// * `max_depth` - the maximum allowed nesting depth. The parse fails if it is exceeded.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{fold_nested, NestedFrame};
+/// use anpa::number::integer;
+/// use anpa::parsers::skip;
+///
+/// // Sum all numbers in nested lists, e.g. "(1,(2,3),4)"
+/// struct Sum(u32);
+///
+/// impl<'a> NestedFrame<&'a str, ()> for Sum {
+///     type Value = u32;
+///
+///     fn close(&self, s: &mut AnpaState<&'a str, ()>) -> Option<()> {
+///         skip(')')(s)
+///     }
+///
+///     fn push(&mut self, value: u32) {
+///         self.0 += value
+///     }
+///
+///     fn finish(self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// let p = fold_nested(skip('(').map(|_| Sum(0)), integer(), skip(','), usize::MAX);
+///
+/// assert_eq!(parse(p, "(1,(2,3),4)").result, Some(10));
+/// assert_eq!(parse(p, "(1,(2,3),4").result, None);
+/// ```
+#[inline]
+pub fn fold_nested<I: SliceLike, F: NestedFrame<I, S>, O, S>(open: impl Parser<I, F, S>,
+                                                             atom: impl Parser<I, F::Value, S>,
+                                                             separator: impl Parser<I, O, S>,
+                                                             max_depth: usize,
+) -> impl Parser<I, F::Value, S> {
+    create_parser!(s, {
+        let mut stack: Vec<F> = Vec::new();
+
+        'value: loop {
+            let pos = s.input;
+            let mut value = match open(s) {
+                Some(frame) => {
+                    if stack.len() >= max_depth {
+                        return None
+                    }
+
+                    let pos = s.input;
+                    if frame.close(s).is_some() {
+                        frame.finish()
+                    } else if s.cut {
+                        return None
+                    } else {
+                        s.input = pos;
+                        stack.push(frame);
+                        stack.last_mut()?.element_prefix(s)?;
+                        continue 'value
+                    }
+                }
+                None if s.cut => return None,
+                None => {
+                    s.input = pos;
+                    atom(s)?
+                }
+            };
+
+            // A value is complete. Add it to the enclosing frames, closing them as we go.
+            loop {
+                let Some(frame) = stack.last_mut() else {
+                    return Some(value)
+                };
+
+                frame.push(value);
+
+                if separator(s).is_some() {
+                    frame.element_prefix(s)?;
+                    continue 'value
+                } else if s.cut {
+                    return None
+                }
+
+                frame.close(s)?;
+                value = stack.pop()?.finish();
+            }
+        }
+    })
+}
+
 /// Combine two parsers into a parser that returns the result of the parser
 /// that consumed the most input.
 ///
@@ -894,12 +2063,18 @@ pub fn greedy_or<I: SliceLike, S, O>(p1: impl Parser<I, O, S>,
     create_parser!(s, {
         let pos = s.input;
         let res1 = p1(s);
-        let p1_consumed = pos.slice_len() - s.input.slice_len();
+        if res1.is_none() && s.cut {
+            return None
+        }
+        let p1_consumed = consumed_len(pos, s.input);
         let p1_pos = s.input;
 
         s.input = pos;
         let res2 = p2(s);
-        let p2_consumed = pos.slice_len() - s.input.slice_len();
+        if res2.is_none() && s.cut {
+            return None
+        }
+        let p2_consumed = consumed_len(pos, s.input);
         let p1_is_some = res1.is_some();
 
         let choose_p1 = if p1_is_some == res2.is_some() {
@@ -922,7 +2097,7 @@ pub fn greedy_or<I: SliceLike, S, O>(p1: impl Parser<I, O, S>,
 
 #[cfg(test)]
 mod tests {
-    use crate::{combinators::{greedy_or, many, middle, no_separator, not_empty, times}, core::*, number::integer, parsers::{take, empty, item_while}};
+    use crate::{combinators::{greedy_or, many, middle, no_separator, not_empty, recover_with, right, succeed, times, until_parser}, core::*, number::integer, parsers::{take, empty, item_while}};
 
     use super::{fold, or, left};
 
@@ -931,10 +2106,10 @@ mod tests {
         or(left(num, take(',')), left(num, empty()))
     }
 
-    #[cfg(feature = "std")]
+    #[cfg(feature = "alloc")]
     #[test]
     fn many_nums_vec() {
-        use std::vec;
+        use alloc::vec;
         use crate::combinators::many_to_vec;
         let p = many_to_vec(num_parser(), true, no_separator());
         let res = parse(p, "1,2,3,4").result.unwrap();
@@ -1015,4 +2190,304 @@ mod tests {
         assert_eq!(res.result.unwrap(), "a");
         assert!(res.state.is_empty());
     }
+
+    #[test]
+    fn cut_test() {
+        let item = or(right(take('('), take("x)")).cut(), take("y"));
+        let p = many(item, true, no_separator());
+
+        let res = parse(p, "(x)y(x)z");
+        assert_eq!(res.result, Some("(x)y(x)"));
+        assert_eq!(res.state, "z");
+
+        // A cut failure fails the whole parse.
+        assert_eq!(parse(p, "(x)y(y)").result, None);
+        assert_eq!(parse(succeed(p), "(x)y(y)").result, None);
+
+        // ... unless recovered from.
+        let p = many(recover_with(item, take(';')), true, no_separator());
+        assert_eq!(parse(p, "(x)(y);(x)").result, Some("(x)(y);(x)"));
+    }
+
+    #[test]
+    fn labelled_test() {
+        let word = |w| take(w).labelled(w);
+        let p = right(word("let "), or(word("x"), word("y")));
+
+        // The furthest label wins.
+        let err = parse_all(p, "let z").unwrap_err();
+        assert_eq!(err.expected, Some("y"));
+        assert_eq!(err.remaining, "z");
+
+        // An outer label takes precedence.
+        let p = right(word("let "), or(word("x"), word("y")).labelled("variable"));
+        assert_eq!(parse_all(p, "let z").unwrap_err().expected, Some("variable"));
+        assert_eq!(parse_all(p, "lex").unwrap_err().expected, Some("let "));
+    }
+
+    #[test]
+    fn or_ext_test() {
+        let p = take("abc").or(take("123"));
+        assert_eq!(parse(p, "123").result, Some("123"));
+
+        let p = left(take("ab"), take('c')).or_no_partial(take("abd"));
+        assert_eq!(parse(p, "abd").result, None);
+
+        let p = integer().or_value(42u32);
+        let res = parse(p, "abc");
+        assert_eq!(res.result, Some(42));
+        assert_eq!(res.state, "abc");
+
+        let p = right(take('-'), integer::<u32, _, _, _>()).or_default();
+        assert_eq!(parse(p, "x").result, Some(0));
+        assert_eq!(parse(p, "-x").result, None);
+        assert_eq!(parse(right(take('-'), integer::<u32, _, _, _>().cut()).or_default(), "-").result, None);
+        assert_eq!(parse(super::optional_with(take("ab"), "none"), "ac").result, Some("none"));
+    }
+
+    #[test]
+    fn verify_with_span_test() {
+        let header = super::verify_with_span(middle(take('<'), item_while(|c: char| c != '>'), take('>')),
+                                             |span: &str, name| match span.len() - name.len() {
+                                                 2 if !name.is_empty() => Ok(()),
+                                                 _ => Err("header name")
+                                             });
+        assert_eq!(parse_all(header, "<a>"), Ok("a"));
+        assert_eq!(parse_all(header, "<>").unwrap_err().expected, Some("header name"));
+        assert_eq!(parse_all(header, "<a").unwrap_err().expected, None);
+
+        let p = or(header, take("<>"));
+        assert_eq!(parse_all(p, "<>"), Ok("<>"));
+    }
+
+    #[test]
+    fn substate_test() {
+        struct State { count: u32, flag: bool }
+        impl AsMut<u32> for State {
+            fn as_mut(&mut self) -> &mut u32 { &mut self.count }
+        }
+
+        let count = super::lift_to_substate(|n: &mut u32, _| *n += 1, take('a'));
+        let inner = right(take('('), take("x").labelled("x")).cut();
+        let p = or(super::with_substate(inner), super::stateless(take("(y")));
+        let mut state = State { count: 0, flag: true };
+
+        assert_eq!(parse_all_state(left(count, count), "aa", &mut state), Ok(()));
+        assert_eq!(parse_all_state(p, "(x", &mut state), Ok("x"));
+        assert_eq!(parse_all_state(p, "(y", &mut state).unwrap_err().expected, Some("x"));
+        assert_eq!(parse_all_state(super::stateless(take("x").labelled("x")), "y", &mut state).unwrap_err().expected, Some("x"));
+        assert_eq!((state.count, state.flag), (2, true));
+    }
+
+    #[test]
+    fn result_ext_test() {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        enum Tok { Let }
+
+        assert_eq!(parse(take("let").to(Tok::Let), "let").result, Some(Tok::Let));
+        assert_eq!(parse(take("let").void(), "let").result, Some(()));
+        assert_eq!(parse(take("let").opt(), "fn").result, Some(None));
+        assert_eq!(parse(take("let").opt(), "let").result, Some(Some("let")));
+    }
+
+    #[test]
+    fn until_parser_test() {
+        let p = until_parser(integer::<u32, _, _, _>());
+        let res = parse(p, "åäö 12 3");
+        assert_eq!(res.result, Some(("åäö ", 12)));
+        assert_eq!(res.state, " 3");
+
+        let res = parse(p, "åäö");
+        assert_eq!(res.result, None);
+        assert_eq!(res.state, "åäö");
+
+        assert_eq!(parse(until_parser(empty()), "ab").result, Some(("ab", "")));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn scan_test() {
+        use alloc::vec;
+        use super::{cut, scan_all, scan_for};
+        use crate::parsers::success;
+
+        let p = scan_for(integer::<u32, _, _, _>());
+        assert_eq!(parse(p, "åx12y").result, Some((3, 12)));
+        assert_eq!(parse(p, "xy").state, "xy");
+
+        // The failure of the cut parser doesn't stop the scan.
+        let p = scan_all(right(take('<'), cut(left(integer::<u32, _, _, _>(), take('>')))));
+        let res = parse(p, "<1> <2 <<3>>");
+        assert_eq!(res.result, Some(vec![(0, 1), (8, 3)]));
+        assert_eq!(res.state, "");
+
+        assert_eq!(parse(scan_all(success()), "åb").result, Some(vec![(0, ()), (2, ()), (3, ())]));
+    }
+
+    #[test]
+    fn many_count_test() {
+        use super::many_count;
+
+        assert_eq!(parse(many_count(take('a'), true, no_separator()), "aaab").result, Some(3));
+        assert_eq!(parse(many_count(take('a'), true, no_separator()), "b").result, Some(0));
+        assert_eq!(parse(many_count(take('a'), false, no_separator()), "b").result, None);
+    }
+
+    #[test]
+    fn many_progress_test() {
+        use core::ops::ControlFlow;
+
+        use super::{fold_while, many_count, separator, unguarded};
+
+        // The empty word before `1` is the last element.
+        let word = item_while(|c: char| c.is_alphabetic());
+        let res = parse(many_count(word, true, no_separator()), "ab1");
+        assert_eq!((res.result, res.state), (Some(2), "1"));
+
+        // Empty elements are fine as long as the separators consume input.
+        let res = parse(many_count(word, true, separator(take(','), false)), ",a,,1");
+        assert_eq!((res.result, res.state), (Some(4), "1"));
+        assert_eq!(parse(many_count(word, true, separator(succeed(take(',')), false)), "1").result, Some(1));
+
+        let count_to_3 = |n: &mut u32, _| {
+            *n += 1;
+            if *n == 3 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+        };
+        let nothing = item_while(|_: char| false);
+        assert_eq!(parse(fold_while(nothing, || 0, count_to_3, true, unguarded(no_separator())), "a").result, Some(3));
+        assert_eq!(parse(fold_while(nothing, || 0, count_to_3, true, no_separator()), "a").result, Some(1));
+    }
+
+    #[test]
+    fn terminated_test() {
+        use super::{separator, terminated};
+
+        let optional = many(integer::<u32, _, _, _>(), true, terminated(separator(take(','), true), take(';'), false));
+        assert_eq!(parse(optional, "1,2,;x").state, "x");
+        assert_eq!(parse(optional, "1,2x").state, "x");
+        assert_eq!(parse(optional, ";x").state, "x");
+
+        let required = fold(integer::<u32, _, _, _>(), || 0, |acc, x| *acc += x, true, terminated(no_separator(), take('.'), true));
+        assert_eq!(parse(required, "123.").result, Some(123));
+        assert_eq!(parse(required, ".").result, Some(0));
+        assert_eq!(parse(required, "123").result, None);
+    }
+
+    #[test]
+    fn seq_test() {
+        let p = seq!(_: take('('), integer(), _: take(','), integer(), _: take(')'),);
+        assert_eq!(parse(p, "(1,2)").result, Some((1u32, 2u32)));
+        assert_eq!(parse(p, "(1,2").result, None);
+
+        let p = seq!(take('a'), take('b'), take('c'));
+        assert_eq!(parse(p, "abc").result, Some(('a', 'b', 'c')));
+    }
+
+    #[test]
+    fn map_struct_test() {
+        #[derive(Debug, PartialEq)]
+        struct Point { x: u32, y: u32 }
+
+        let coordinate = left(integer(), take(','));
+        let p = map_struct!(Point { y, x, }, coordinate, integer());
+        assert_eq!(parse(p, "1,2").result, Some(Point { x: 2, y: 1 }));
+        assert_eq!(parse(p, "1,").result, None);
+    }
+
+    #[test]
+    fn with_state_value_restores_on_failure() {
+        use super::{lift_to_state, with_state_value};
+
+        let p = with_state_value(|s: &mut u32| s, 10, lift_to_state(|s: &mut u32, _| *s, take('x')));
+        let mut state = 1;
+        assert_eq!(parse_state(p, "x", &mut state).result, Some(10));
+        assert_eq!(state, 1);
+        assert_eq!(parse_state(p, "y", &mut state).result, None);
+        assert_eq!(state, 1);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn many_to_string_test() {
+        use crate::combinators::{many_to_string, separator};
+
+        let p = many_to_string(item_while(|c: char| c.is_alphabetic()), false, separator(take(' '), false));
+        assert_eq!(parse(p, "ab cd e").result.unwrap(), "abcde");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn fold_nested_deep() {
+        use alloc::string::String;
+        use super::{fold_nested, NestedFrame};
+
+        // Computes the maximum depth of nested brackets
+        struct Depth(usize);
+
+        impl<'a> NestedFrame<&'a str, ()> for Depth {
+            type Value = usize;
+
+            fn close(&self, s: &mut AnpaState<&'a str, ()>) -> Option<()> {
+                take(']').map(|_| ())(s)
+            }
+
+            fn push(&mut self, value: usize) {
+                self.0 = self.0.max(value)
+            }
+
+            fn finish(self) -> usize {
+                self.0 + 1
+            }
+        }
+
+        let n = 100_000;
+        let mut input = String::new();
+        input.extend((0..n).map(|_| '['));
+        input.push('x');
+        input.extend((0..n).map(|_| ']'));
+
+        let open = take('[').map(|_| Depth(0));
+        let atom = take('x').map(|_| 0);
+
+        let p = fold_nested(open, atom, take(','), usize::MAX);
+        let res = parse(p, input.as_str());
+        assert_eq!(res.result, Some(n));
+        assert!(res.state.is_empty());
+
+        let p = fold_nested(open, atom, take(','), n - 1);
+        assert_eq!(parse(p, input.as_str()).result, None);
+
+        let p = fold_nested(open, atom, take(','), 2);
+        assert_eq!(parse(p, "[[],[x,x],x]").result, Some(2));
+        assert_eq!(parse(p, "[[],[x,x],x").result, None);
+        assert_eq!(parse(p, "[x,]").result, None);
+    }
+
+    #[cfg(feature = "debug_invariants")]
+    #[test]
+    #[should_panic(expected = "a parser grew the input")]
+    fn debug_invariants_grown_input() {
+        use crate::combinators::count_consumed;
+
+        let p = count_consumed(create_parser!(s, {
+            s.input = "longer than the input";
+            Some(())
+        }));
+        parse(p, "input");
+    }
+
+    #[cfg(feature = "debug_invariants")]
+    #[test]
+    #[should_panic(expected = "isn't a suffix of it")]
+    fn debug_invariants_replaced_input() {
+        use crate::combinators::get_parsed;
+
+        assert_eq!(parse(right(take("in"), get_parsed(take("pu"))), "input").result, Some("pu"));
+
+        let p = get_parsed(create_parser!(s, {
+            s.input = "put";
+            Some(())
+        }));
+        parse(p, "input");
+    }
 }
\ No newline at end of file
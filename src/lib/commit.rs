@@ -0,0 +1,206 @@
+use alloc::vec::Vec;
+
+use crate::{core::Parser, findbyte::{eq, find, ByteInput}};
+
+/// A commit message following the Conventional Commits specification, as produced by
+/// [`commit_parser`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit<I> {
+    /// The type, e.g. `feat` or `fix`.
+    pub kind: I,
+    pub scope: Option<I>,
+
+    /// Whether the commit is a breaking change, i.e. if the type/scope is followed by `!`
+    /// or there is a `BREAKING CHANGE` footer.
+    pub breaking: bool,
+    pub description: I,
+    pub body: Option<I>,
+    pub footers: Vec<Footer<I>>
+}
+
+/// A footer of a commit message, e.g. `Reviewed-by: Z` or `Refs #123`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Footer<I> {
+    pub token: I,
+
+    /// The value, without the separator (`: ` or ` #`). The value may span several lines.
+    pub value: I
+}
+
+impl<'a, I: ByteInput<'a>> Footer<I> {
+    /// Check if this is a `BREAKING CHANGE` (or `BREAKING-CHANGE`) footer.
+    pub fn is_breaking(&self) -> bool {
+        matches!(self.token.as_byte_slice(), b"BREAKING CHANGE" | b"BREAKING-CHANGE")
+    }
+}
+
+impl<'a, I: ByteInput<'a>> Commit<I> {
+    /// Get the first footer with the token `token`, ignoring ASCII case.
+    pub fn footer(&self, token: &str) -> Option<&Footer<I>> {
+        self.footers.iter().find(|f| f.token.as_byte_slice().eq_ignore_ascii_case(token.as_bytes()))
+    }
+}
+
+fn trim_ascii<'a, I: ByteInput<'a>>(input: I) -> I {
+    let bytes = input.as_byte_slice();
+    let start = bytes.iter().take_while(|b| b.is_ascii_whitespace()).count();
+    let end = bytes.len() - bytes[start..].iter().rev().take_while(|b| b.is_ascii_whitespace()).count();
+    input.slice_to(end).slice_from(start)
+}
+
+/// Get the length of the line starting at `pos`, excluding the newline.
+fn line_len(bytes: &[u8], pos: usize) -> usize {
+    find(&bytes[pos..], eq(b'\n')).unwrap_or(bytes.len() - pos)
+}
+
+/// Get the length of the token and separator, if `line` starts a footer.
+fn footer_start(line: &[u8]) -> Option<(usize, usize)> {
+    let token_len = match line.starts_with(b"BREAKING CHANGE") {
+        true => 15,
+        false => line.iter().take_while(|b| b.is_ascii_alphanumeric() || **b == b'-').count()
+    };
+
+    let separator = line[token_len..].starts_with(b": ") || line[token_len..].starts_with(b" #");
+    (token_len > 0 && separator).then_some((token_len, token_len + 2))
+}
+
+/// Create a parser for a commit message following the
+/// [Conventional Commits](https://www.conventionalcommits.org) specification, e.g.
+/// `feat(parser)!: add x`, followed by an optional body and footers.
+///
+/// The footers are the last paragraph starting with a footer, e.g. `Refs: #1`, and
+/// everything after it. Any lines not starting a footer belong to the value of the
+/// preceding footer. The description, body and footer values are trimmed of ASCII
+/// whitespace.
+///
+/// ### Consuming
+/// Consumes the whole input, on successful parse. The parser fails if the first line is not
+/// a valid header, or if it is not followed by an empty line.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::commit::{commit_parser, Footer};
+///
+/// let input = "fix(lexer): handle CRLF\n\n\
+///              Windows files were rejected.\n\n\
+///              BREAKING CHANGE: `Token::Newline` now\n\
+///              includes the `\\r`.\n\
+///              Refs #42";
+///
+/// let commit = parse(commit_parser(), input).result.unwrap();
+/// assert_eq!((commit.kind, commit.scope, commit.breaking), ("fix", Some("lexer"), true));
+/// assert_eq!(commit.description, "handle CRLF");
+/// assert_eq!(commit.body, Some("Windows files were rejected."));
+/// assert_eq!(commit.footers, [
+///     Footer { token: "BREAKING CHANGE", value: "`Token::Newline` now\nincludes the `\\r`." },
+///     Footer { token: "Refs", value: "42" }
+/// ]);
+/// assert_eq!(commit.footer("refs").map(|f| f.value), Some("42"));
+/// ```
+#[inline]
+pub fn commit_parser<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Commit<I>, S> {
+    create_parser!(s, {
+        let input = s.input;
+        let bytes = input.as_byte_slice();
+        let slice = |start: usize, end: usize| input.slice_to(end).slice_from(start);
+
+        let header_len = line_len(bytes, 0);
+        let header = &bytes[..header_len];
+        let kind_len = header.iter().take_while(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_')).count();
+        let mut pos = kind_len;
+
+        let scope = match header.get(pos) {
+            Some(b'(') => {
+                let scope_len = find(&header[pos + 1..], eq(b')'))?;
+                let scope = trim_ascii(slice(pos + 1, pos + 1 + scope_len));
+                if scope.as_byte_slice().is_empty() {
+                    return None
+                }
+                pos += scope_len + 2;
+                Some(scope)
+            },
+            _ => None
+        };
+
+        let bang = header.get(pos) == Some(&b'!');
+        pos += bang as usize;
+        if kind_len == 0 || !header[pos..].starts_with(b": ") {
+            return None
+        }
+
+        let description = trim_ascii(slice(pos + 2, header_len));
+        if description.as_byte_slice().is_empty() {
+            return None
+        }
+
+        // The body and footers start after an empty line.
+        let rest_start = (header_len + 1).min(bytes.len());
+        if !bytes[rest_start..rest_start + line_len(bytes, rest_start)].iter().all(u8::is_ascii_whitespace) {
+            return None
+        }
+
+        let mut footers_start = bytes.len();
+        let mut line = rest_start;
+        let mut after_empty = true;
+        while line < bytes.len() {
+            let len = line_len(bytes, line);
+            if after_empty && footer_start(&bytes[line..line + len]).is_some() {
+                footers_start = line;
+            }
+            after_empty = bytes[line..line + len].iter().all(u8::is_ascii_whitespace);
+            line += len + 1;
+        }
+
+        let body = Some(trim_ascii(slice(rest_start, footers_start))).filter(|b| !b.as_byte_slice().is_empty());
+
+        let mut footers: Vec<Footer<I>> = Vec::new();
+        let mut value_start = 0;
+        let mut line = footers_start;
+        while line < bytes.len() {
+            let len = line_len(bytes, line);
+            if let Some((token_len, separator_end)) = footer_start(&bytes[line..line + len]) {
+                if let Some(last) = footers.last_mut() {
+                    last.value = trim_ascii(slice(value_start, line));
+                }
+                footers.push(Footer { token: slice(line, line + token_len), value: slice(line, line) });
+                value_start = line + separator_end;
+            }
+            line += len + 1;
+        }
+
+        if let Some(last) = footers.last_mut() {
+            last.value = trim_ascii(slice(value_start, bytes.len()));
+        }
+
+        s.input = input.slice_from(bytes.len());
+        let breaking = bang || footers.iter().any(Footer::is_breaking);
+        Some(Commit { kind: slice(0, kind_len), scope, breaking, description, body, footers })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{core::parse, commit::{commit_parser, Footer}};
+
+    #[test]
+    fn commits() {
+        let commit = parse(commit_parser(), "docs: a").result.unwrap();
+        assert_eq!((commit.kind, commit.scope, commit.breaking, commit.description), ("docs", None, false, "a"));
+        assert_eq!((commit.body, commit.footers.len()), (None, 0));
+
+        let commit = parse(commit_parser(), "feat( api )!:  b \n\nNote: c\n\nd\n\nAcked-by: e\n\nf\nBREAKING-CHANGE: g\n").result.unwrap();
+        assert_eq!((commit.kind, commit.scope, commit.breaking, commit.description), ("feat", Some("api"), true, "b"));
+        assert_eq!(commit.body, Some("Note: c\n\nd"));
+        assert_eq!(commit.footers, [Footer { token: "Acked-by", value: "e\n\nf" }, Footer { token: "BREAKING-CHANGE", value: "g" }]);
+        assert!(commit.footers[1].is_breaking());
+
+        let commit = parse(commit_parser(), b"x: y\n\nBREAKING CHANGE: z".as_slice()).result.unwrap();
+        assert!(commit.breaking);
+        assert_eq!(commit.footer("breaking change").map(|f| f.value), Some(&b"z"[..]));
+
+        for invalid in ["", "feat", "feat:", "feat: ", "feat:x", "(a): b", "feat(): b", "feat(a: b", "feat!(a): b", "feat: a\nb"] {
+            assert_eq!(parse(commit_parser(), invalid).result, None, "{}", invalid);
+        }
+    }
+}
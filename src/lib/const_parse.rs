@@ -0,0 +1,203 @@
+use core::str::from_utf8;
+
+use crate::findbyte::ByteSet;
+
+/// A cursor for parsing in `const` contexts, where the parsers of this crate can't be used,
+/// e.g. to validate literals at compile time with [`const_parse`](macro@crate::const_parse).
+///
+/// Each method advances the cursor on success, and otherwise marks it as failed, after
+/// which all methods leave it unchanged. A parse can therefore be written as a chain of calls,
+/// with a single check at the end. Since the cursor is `Copy`, alternatives can be tried
+/// from the same position using [`or`](ConstCursor::or) and
+/// [`optional`](ConstCursor::optional).
+///
+/// ### Example
+/// ```
+/// use anpa::const_parse::ConstCursor;
+/// use anpa::findbyte::{byteset, ByteSet};
+///
+/// const DIGITS: ByteSet = byteset(b"0123456789");
+///
+/// // A time, e.g. `12:30` or `12:30:15`.
+/// const fn time(text: &str) -> bool {
+///     let c = ConstCursor::new(text).repeat(DIGITS, 2, 2).byte(b':').repeat(DIGITS, 2, 2);
+///     c.optional(c.byte(b':').repeat(DIGITS, 2, 2)).is_done()
+/// }
+///
+/// const VALID: [bool; 4] = [time("12:30"), time("12:30:15"), time("12:3"), time("12:30:")];
+/// assert_eq!(VALID, [true, true, false, false]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstCursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    ok: bool
+}
+
+impl<'a> ConstCursor<'a> {
+    /// Create a cursor at the start of `text`.
+    pub const fn new(text: &'a str) -> Self {
+        Self::from_bytes(text.as_bytes())
+    }
+
+    /// Create a cursor at the start of `bytes`.
+    pub const fn from_bytes(bytes: &'a [u8]) -> Self {
+        ConstCursor { bytes, pos: 0, ok: true }
+    }
+
+    /// The position of the cursor, in bytes from the start of the input.
+    pub const fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Check if all steps so far have succeeded.
+    pub const fn is_ok(&self) -> bool {
+        self.ok
+    }
+
+    /// Check if all steps so far have succeeded, and all input has been consumed.
+    pub const fn is_done(&self) -> bool {
+        self.ok && self.pos == self.bytes.len()
+    }
+
+    /// Get the byte at the cursor, if any, and if the cursor has not failed.
+    pub const fn peek(&self) -> Option<u8> {
+        match self.ok && self.pos < self.bytes.len() {
+            true => Some(self.bytes[self.pos]),
+            false => None
+        }
+    }
+
+    /// Mark the cursor as failed.
+    pub const fn fail(self) -> Self {
+        ConstCursor { ok: false, ..self }
+    }
+
+    /// Advance the cursor by `n` bytes, or fail if fewer are left.
+    pub const fn advance(self, n: usize) -> Self {
+        match self.ok && n <= self.bytes.len() - self.pos {
+            true => ConstCursor { pos: self.pos + n, ..self },
+            false => self.fail()
+        }
+    }
+
+    /// Consume the byte `b`.
+    pub const fn byte(self, b: u8) -> Self {
+        match self.peek() {
+            Some(next) if next == b => self.advance(1),
+            _ => self.fail()
+        }
+    }
+
+    /// Consume `text`.
+    pub const fn literal(self, text: &str) -> Self {
+        let text = text.as_bytes();
+        let mut i = 0;
+        while i < text.len() {
+            if self.pos + i >= self.bytes.len() || self.bytes[self.pos + i] != text[i] {
+                return self.fail()
+            }
+            i += 1;
+        }
+        self.advance(text.len())
+    }
+
+    /// Consume at least `min` and at most `max` bytes in `set`. As many bytes as possible are
+    /// consumed, so the cursor doesn't fail if more than `max` bytes are in `set`.
+    pub const fn repeat(self, set: ByteSet, min: usize, max: usize) -> Self {
+        let mut n = 0;
+        while n < max && self.pos + n < self.bytes.len() && set.contains(self.bytes[self.pos + n]) {
+            n += 1;
+        }
+        match n >= min {
+            true => self.advance(n),
+            false => self.fail()
+        }
+    }
+
+    /// Consume a decimal integer, failing if there are no digits or if it doesn't fit in a
+    /// `u64`. Returns the cursor and the integer.
+    pub const fn integer(self) -> (Self, u64) {
+        let mut c = self;
+        let mut value = 0u64;
+        while let Some(b @ b'0'..=b'9') = c.peek() {
+            value = match value.checked_mul(10) {
+                Some(v) => match v.checked_add((b - b'0') as u64) {
+                    Some(v) => v,
+                    None => return (self.fail(), 0)
+                },
+                None => return (self.fail(), 0)
+            };
+            c = c.advance(1);
+        }
+        match c.pos > self.pos {
+            true => (c, value),
+            false => (self.fail(), 0)
+        }
+    }
+
+    /// Get this cursor if it has succeeded, and otherwise `other`. Use this to try
+    /// alternatives from the same cursor, e.g. `c.byte(b'a').or(c.byte(b'b'))`.
+    pub const fn or(self, other: Self) -> Self {
+        match self.ok {
+            true => self,
+            false => other
+        }
+    }
+
+    /// Get `attempt` if it has succeeded, and otherwise this cursor. Use this for optional
+    /// parts, e.g. `c.optional(c.byte(b'-'))`.
+    pub const fn optional(self, attempt: Self) -> Self {
+        attempt.or(self)
+    }
+
+    /// Get the text consumed from the position `start` to the cursor. Returns `None` if the
+    /// cursor has failed, or if the text is not valid UTF-8.
+    pub const fn text_from(&self, start: usize) -> Option<&'a str> {
+        if !self.ok || start > self.pos {
+            return None
+        }
+
+        let (consumed, _) = self.bytes.split_at(self.pos);
+        match from_utf8(consumed.split_at(start).1) {
+            Ok(text) => Some(text),
+            Err(_) => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{const_parse::ConstCursor, findbyte::{byteset, ByteSet}};
+
+    const HEX: ByteSet = byteset(b"0123456789abcdefABCDEF");
+
+    /// A hexadecimal number with an optional sign, returning the digits.
+    const fn hex(text: &str) -> Option<&str> {
+        let c = ConstCursor::new(text);
+        let c = c.optional(c.byte(b'-').or(c.byte(b'+'))).literal("0x");
+        let start = c.pos();
+        let c = c.repeat(HEX, 1, 16);
+        match c.is_done() {
+            true => c.text_from(start),
+            false => None
+        }
+    }
+
+    #[test]
+    fn cursor() {
+        const RESULTS: [Option<&str>; 6] = [hex("0xff"), hex("-0x1F"), hex("+-0x1"), hex("0x"), hex("0x12345678123456789"), hex("0xfg")];
+        assert_eq!(RESULTS, [Some("ff"), Some("1F"), None, None, None, None]);
+
+        let (c, n) = ConstCursor::new("18446744073709551615!").integer();
+        assert_eq!((n, c.peek(), c.is_done()), (u64::MAX, Some(b'!'), false));
+        assert!(!ConstCursor::new("18446744073709551616").integer().0.is_ok());
+        assert!(!ConstCursor::new("x").integer().0.is_ok());
+
+        let c = ConstCursor::from_bytes(b"\xc3\xa9!").advance(2);
+        assert_eq!((c.text_from(0), c.text_from(1), c.text_from(3)), (Some("é"), None, None));
+        assert_eq!((c.advance(1).is_done(), c.advance(2).is_ok()), (true, false));
+        assert!(!c.fail().byte(b'!').is_ok());
+        assert!(!ConstCursor::new("ab").literal("abc").is_ok());
+    }
+}
@@ -1,15 +1,64 @@
-use crate::{combinators::{bind, filter, into_type, left, map, map_if, right}, slicelike::SliceLike};
+use core::{borrow::BorrowMut, fmt::{self, Debug, Display}, iter::FusedIterator, marker::PhantomData};
 
-/// The state being passed around during parsing.
+#[cfg(feature = "alloc")]
+use crate::{boxed::BoxedParser, diagnostics::{warn_if, Diagnostics}};
+
+#[cfg(feature = "trace")]
+use crate::trace::{traced, Tracer};
+
+use crate::{combinators::{bind, cut, filter, labelled, into_type, left, map, map_if, or, or_default, or_no_partial, right, succeed}, slicelike::{consumed_input, consumed_len, SliceLike}};
+
+/// The state being passed around during parsing. Create it using [`AnpaState::new`].
 pub struct AnpaState<'a, I: SliceLike, S> {
     /// The current state of the input under parse.
     pub input: I,
 
     /// The provided user state (if any).
     pub user_state: &'a mut S,
+
+    /// Whether a parser wrapped in [`cut`](crate::combinators::cut) has failed.
+    pub(crate) cut: bool,
+
+    /// The label of the furthest failed [`labelled`](crate::combinators::labelled) parser,
+    /// together with the remaining length of the input where it started.
+    pub(crate) expected: Option<(&'static str, I::Idx)>,
+}
+
+impl<'a, I: SliceLike, S> AnpaState<'a, I, S> {
+    /// Create a new state.
+    pub fn new(input: I, user_state: &'a mut S) -> Self {
+        Self { input, user_state, cut: false, expected: None }
+    }
+
+    /// Check if a parser wrapped in [`cut`](crate::combinators::cut) has failed, in which case
+    /// no alternatives should be tried. Combinators that try alternatives after a failure
+    /// must check this.
+    pub fn is_cut(&self) -> bool {
+        self.cut
+    }
+
+    /// Record a failed expectation at the position with the remaining length `remaining`,
+    /// unless there is already one further into the input.
+    #[inline]
+    pub(crate) fn expect(&mut self, label: &'static str, remaining: I::Idx) {
+        if self.expected.map_or(true, |(_, r)| remaining <= r) {
+            self.expected = Some((label, remaining))
+        }
+    }
+}
+
+impl<I: SliceLike + Debug, S: Debug> Debug for AnpaState<'_, I, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnpaState")
+            .field("input", &self.input)
+            .field("user_state", &self.user_state)
+            .field("cut", &self.cut)
+            .finish_non_exhaustive()
+    }
 }
 
 /// The final result of a parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct AnpaResult<T, O> {
     /// The final state of the parse.
     pub state: T,
@@ -18,6 +67,194 @@ pub struct AnpaResult<T, O> {
     pub result: Option<O>
 }
 
+impl<I: SliceLike, O> AnpaResult<I, O> {
+    /// The input that was not consumed by the parse.
+    pub fn remaining(&self) -> I {
+        self.state
+    }
+
+    /// The part of `input`, which must be the input given to the parse, that was consumed
+    /// by the parse.
+    pub fn consumed(&self, input: I) -> I {
+        consumed_input(input, self.state)
+    }
+
+    /// Get the result if the parse succeeded, and otherwise the remaining input.
+    pub fn ok_or_rest(self) -> Result<O, I> {
+        self.result.ok_or(self.state)
+    }
+
+    /// Get the result if the parse succeeded and consumed all input, and otherwise the
+    /// remaining input. Use [`parse_all`] for a more detailed error.
+    ///
+    /// ### Example
+    /// ```
+    /// use anpa::core::*;
+    /// use anpa::number::integer;
+    ///
+    /// let p = integer::<u32, _, _, _>();
+    /// assert_eq!(parse(p, "123").require_empty(), Ok(123));
+    /// assert_eq!(parse(p, "123abc").require_empty(), Err("abc"));
+    /// assert_eq!(parse(p, "abc").require_empty(), Err("abc"));
+    /// ```
+    pub fn require_empty(self) -> Result<O, I> {
+        match self.result {
+            Some(result) if self.state.slice_is_empty() => Ok(result),
+            _ => Err(self.state)
+        }
+    }
+
+    /// Get the result, requiring that the parse succeeded and consumed all input.
+    ///
+    /// ### Panics
+    /// Panics if the parse failed, or if there is remaining input.
+    #[track_caller]
+    pub fn unwrap_full(self) -> O where I: Debug {
+        match self.result {
+            Some(result) if self.state.slice_is_empty() => result,
+            Some(_) => panic!("parse did not consume all input, remaining: {:?}", self.state),
+            None => panic!("parse failed, remaining: {:?}", self.state)
+        }
+    }
+}
+
+impl<I: SliceLike, S, O> AnpaResult<AnpaState<'_, I, S>, O> {
+    fn without_state(self) -> AnpaResult<I, O> {
+        AnpaResult { state: self.state.input, result: self.result }
+    }
+
+    /// The input that was not consumed by the parse.
+    pub fn remaining(&self) -> I {
+        self.state.input
+    }
+
+    /// The part of `input`, which must be the input given to the parse, that was consumed
+    /// by the parse.
+    pub fn consumed(&self, input: I) -> I {
+        consumed_input(input, self.state.input)
+    }
+
+    /// Get the result if the parse succeeded, and otherwise the remaining input.
+    pub fn ok_or_rest(self) -> Result<O, I> {
+        self.without_state().ok_or_rest()
+    }
+
+    /// Get the result if the parse succeeded and consumed all input, and otherwise the
+    /// remaining input. Use [`parse_all_state`] for a more detailed error.
+    pub fn require_empty(self) -> Result<O, I> {
+        self.without_state().require_empty()
+    }
+
+    /// Get the result, requiring that the parse succeeded and consumed all input.
+    ///
+    /// ### Panics
+    /// Panics if the parse failed, or if there is remaining input.
+    #[track_caller]
+    pub fn unwrap_full(self) -> O where I: Debug {
+        self.without_state().unwrap_full()
+    }
+}
+
+/// The kind of error in an [`AnpaError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnpaErrorKind {
+    /// The parser failed.
+    Failed,
+
+    /// The parser succeeded, but did not consume all input.
+    Incomplete
+}
+
+/// Error returned when a parse requiring all input to be consumed is unsuccessful,
+/// e.g. by [`parse_all`].
+#[derive(Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct AnpaError<I: SliceLike> {
+    /// The kind of error.
+    pub kind: AnpaErrorKind,
+
+    /// The remaining input when the parse stopped.
+    pub remaining: I,
+
+    /// The offset from the start of the input where the parse stopped.
+    ///
+    /// Note: For `&str`, the offset is in bytes.
+    pub offset: I::Idx,
+
+    /// What was expected when the parse failed, if known. See
+    /// [`labelled`](crate::combinators::labelled). In this case, `remaining` and `offset`
+    /// refer to the position of the expectation.
+    pub expected: Option<&'static str>
+}
+
+impl<I: SliceLike + Debug> Debug for AnpaError<I> where I::Idx: Debug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnpaError")
+            .field("kind", &self.kind)
+            .field("remaining", &self.remaining)
+            .field("offset", &self.offset)
+            .field("expected", &self.expected)
+            .finish()
+    }
+}
+
+impl<I: SliceLike> Display for AnpaError<I> where I::Idx: Display {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.kind, self.expected) {
+            (AnpaErrorKind::Failed, Some(expected)) => write!(f, "expected {} at offset {}", expected, self.offset),
+            (AnpaErrorKind::Failed, None) => write!(f, "parse failed at offset {}", self.offset),
+            (AnpaErrorKind::Incomplete, _) => write!(f, "unexpected input at offset {}", self.offset),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: SliceLike + Debug> std::error::Error for AnpaError<I> where I::Idx: Debug + Display {}
+
+/// An owned version of [`AnpaError`] for `&str` input, as used by
+/// [`impl_from_str`](crate::impl_from_str), where the error can't borrow the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FromStrError {
+    /// The kind of error.
+    pub kind: AnpaErrorKind,
+
+    /// The offset in bytes from the start of the input where the parse stopped.
+    pub offset: usize,
+
+    /// The first character of the remaining input when the parse stopped, if any.
+    pub found: Option<char>,
+
+    /// What was expected when the parse failed, if known. See [`AnpaError::expected`].
+    pub expected: Option<&'static str>
+}
+
+impl From<AnpaError<&str>> for FromStrError {
+    fn from(error: AnpaError<&str>) -> Self {
+        FromStrError { kind: error.kind, offset: error.offset, found: error.remaining.chars().next(),
+                       expected: error.expected }
+    }
+}
+
+impl Display for FromStrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.kind, self.expected) {
+            (AnpaErrorKind::Failed, Some(expected)) => {
+                write!(f, "expected {}, found ", expected)?;
+                match self.found {
+                    Some(c) => write!(f, "{:?}", c)?,
+                    None => write!(f, "end of input")?
+                }
+                write!(f, " at offset {}", self.offset)
+            },
+            // The other messages don't depend on the remaining input.
+            _ => Display::fmt(&AnpaError { kind: self.kind, remaining: "", offset: self.offset, expected: None }, f)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FromStrError {}
+
 /// The base trait for all parsers.
 ///
 /// If the output of a parser is the same as the input (e.g. if the result is the
@@ -33,6 +270,56 @@ create_parser_trait!(U8Parser, [u8], "Convenience alias for a parser that parses
 /// All copyable `FnOnce` functions with the correct signature are considered parsers.
 impl<I, O, S, F: FnOnce(&mut AnpaState<I, S>) -> Option<O> + Copy> Parser<I, O, S> for F {}
 
+/// Trait for types that can be parsed from the input `I`, using a user state `S`.
+///
+/// With the "derive" feature, this trait can be derived for structs and enums using
+/// `#[derive(Parse)]`. The parsers are provided using `#[anpa(...)]` attributes:
+/// * `input = <type>` - on the type: the input type. Defaults to `&'a str`.
+/// * `state = <type>` - on the type: the user state type. Defaults to `()`.
+/// * `with = <parser>` - on a field: the parser for the field. Defaults to the [`Parse`]
+///   implementation of the field type.
+/// * `before = <parser>`, `after = <parser>` - on the type, a variant or a field: parsers
+///   to apply before and after, whose results are ignored. Can be repeated.
+///
+/// Fields are parsed in order. For enums, the variants are tried in order as with
+/// [`or`](crate::combinators::or).
+///
+/// ### Example
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// use anpa::core::*;
+/// use anpa::number::integer_signed;
+/// use anpa::parsers::skip;
+///
+/// #[derive(Parse, Debug, PartialEq)]
+/// #[anpa(before = skip('('), after = skip(')'))]
+/// struct Point {
+///     #[anpa(with = integer_signed())]
+///     x: i32,
+///     #[anpa(before = skip(','), with = integer_signed())]
+///     y: i32
+/// }
+///
+/// #[derive(Parse, Debug, PartialEq)]
+/// enum Shape {
+///     #[anpa(before = skip("line "))]
+///     Line(Point, #[anpa(before = skip(' '))] Point),
+///     #[anpa(before = skip("dot "))]
+///     Dot(Point)
+/// }
+///
+/// assert_eq!(parse(Shape::parser(), "dot (1,-2)").result,
+///            Some(Shape::Dot(Point { x: 1, y: -2 })));
+/// # }
+/// ```
+pub trait Parse<I: SliceLike, S = ()>: Sized {
+    /// Get the parser for this type.
+    fn parser() -> impl Parser<I, Self, S>;
+}
+
+#[cfg(feature = "derive")]
+pub use anpa_derive::Parse;
+
 /// Convenince extension functions for all parsers.
 pub trait ParserExt<I, O, S>: Parser<I, O, S> {
     /// Transform the result of this parser.
@@ -53,9 +340,54 @@ pub trait ParserExt<I, O, S>: Parser<I, O, S> {
     /// Combine this parser with another, while ignoring the result of the latter.
     fn left<O2, P: Parser<I, O2, S>>(self, p: P) -> impl Parser<I, O, S>;
 
+    /// Try this parser, and if it fails, try `p` instead.
+    fn or<P: Parser<I, O, S>>(self, p: P) -> impl Parser<I, O, S>;
+
+    /// Try this parser, and if it fails without consuming any input, try `p` instead.
+    fn or_no_partial<P: Parser<I, O, S>>(self, p: P) -> impl Parser<I, O, S>;
+
+    /// Try this parser, and if it fails, succeed with `default` instead.
+    fn or_value(self, default: O) -> impl Parser<I, O, S> where O: Copy;
+
+    /// Succeed with `O::default()` if this parser fails without consuming any input. See
+    /// [`or_default`](crate::combinators::or_default).
+    fn or_default(self) -> impl Parser<I, O, S> where O: Default;
+
+    /// Replace the result of this parser with `value`.
+    fn to<O2: Copy>(self, value: O2) -> impl Parser<I, O2, S>;
+
+    /// Ignore the result of this parser.
+    fn void(self) -> impl Parser<I, (), S>;
+
+    /// Transform this parser to a parser that always succeeds, with the result wrapped in
+    /// an `Option`. See [`succeed`](crate::combinators::succeed).
+    fn opt(self) -> impl Parser<I, Option<O>, S>;
+
+    /// Prevent backtracking if this parser fails after consuming input. See
+    /// [`cut`](crate::combinators::cut).
+    fn cut(self) -> impl Parser<I, O, S>;
+
+    /// Name what this parser expects, for error reporting. See
+    /// [`labelled`](crate::combinators::labelled).
+    fn labelled(self, label: &'static str) -> impl Parser<I, O, S>;
+
     #[cfg(feature = "std")]
     /// Add some simple debug information to this parser.
     fn debug(self, name: &'static str) -> impl Parser<I, O, S>;
+
+    #[cfg(feature = "alloc")]
+    /// Erase the type of this parser by allocating it on the heap. See [`BoxedParser`].
+    fn boxed<'a>(self) -> BoxedParser<'a, I, O, S> where I: SliceLike, Self: 'a;
+
+    #[cfg(feature = "alloc")]
+    /// Emit a warning for the parsed input if the result matches `pred`. See [`Diagnostics`].
+    fn warn_if(self, pred: impl FnOnce(&O) -> bool + Copy, message: &'static str) -> impl Parser<I, O, S>
+        where I: SliceLike, S: AsMut<Diagnostics<I>>;
+
+    #[cfg(feature = "trace")]
+    /// Log the entry and exit of this parser using the [`Tracer`] in the user state. See
+    /// [`traced`].
+    fn trace(self, name: &'static str) -> impl Parser<I, O, S> where I: SliceLike + Debug, S: AsMut<Tracer>;
 }
 
 /// Trait for parsers with a result that can be converted into another by means of `Into`.
@@ -103,6 +435,51 @@ impl<I: SliceLike, O, S, P: Parser<I, O, S>> ParserExt<I, O ,S> for P {
         left(self, p)
     }
 
+    #[inline]
+    fn or<P2: Parser<I, O, S>>(self, p: P2) -> impl Parser<I, O, S> {
+        or(self, p)
+    }
+
+    #[inline]
+    fn or_no_partial<P2: Parser<I, O, S>>(self, p: P2) -> impl Parser<I, O, S> {
+        or_no_partial(self, p)
+    }
+
+    #[inline]
+    fn or_value(self, default: O) -> impl Parser<I, O, S> where O: Copy {
+        or(self, pure!(default))
+    }
+
+    #[inline]
+    fn or_default(self) -> impl Parser<I, O, S> where O: Default {
+        or_default(self)
+    }
+
+    #[inline]
+    fn to<O2: Copy>(self, value: O2) -> impl Parser<I, O2, S> {
+        map(self, move |_| value)
+    }
+
+    #[inline]
+    fn void(self) -> impl Parser<I, (), S> {
+        map(self, |_| ())
+    }
+
+    #[inline]
+    fn opt(self) -> impl Parser<I, Option<O>, S> {
+        succeed(self)
+    }
+
+    #[inline]
+    fn cut(self) -> impl Parser<I, O, S> {
+        cut(self)
+    }
+
+    #[inline]
+    fn labelled(self, label: &'static str) -> impl Parser<I, O, S> {
+        labelled(self, label)
+    }
+
     #[cfg(feature = "std")]
     fn debug(self, name: &'static str) -> impl Parser<I, O, S> {
         use std::println;
@@ -116,6 +493,22 @@ impl<I: SliceLike, O, S, P: Parser<I, O, S>> ParserExt<I, O ,S> for P {
             res
         })
     }
+
+    #[cfg(feature = "alloc")]
+    fn boxed<'a>(self) -> BoxedParser<'a, I, O, S> where I: SliceLike, Self: 'a {
+        BoxedParser::new(self)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn warn_if(self, pred: impl FnOnce(&O) -> bool + Copy, message: &'static str) -> impl Parser<I, O, S>
+        where S: AsMut<Diagnostics<I>> {
+        warn_if(self, pred, message)
+    }
+
+    #[cfg(feature = "trace")]
+    fn trace(self, name: &'static str) -> impl Parser<I, O, S> where I: Debug, S: AsMut<Tracer> {
+        traced(self, name)
+    }
 }
 
 /// Perform a parse with provided user state.
@@ -126,8 +519,8 @@ impl<I: SliceLike, O, S, P: Parser<I, O, S>> ParserExt<I, O ,S> for P {
 /// * `user_state` - the user state
 pub fn parse_state<I: SliceLike, O, S>(p: impl Parser<I, O, S>,
                                        input: I,
-                                       user_state: &mut S) -> AnpaResult<AnpaState<I, S>, O> {
-    let mut parser_state = AnpaState { input, user_state };
+                                       user_state: &mut S) -> AnpaResult<AnpaState<'_, I, S>, O> {
+    let mut parser_state = AnpaState::new(input, user_state);
     let result = p(&mut parser_state);
     AnpaResult { state: parser_state, result }
 }
@@ -139,7 +532,312 @@ pub fn parse_state<I: SliceLike, O, S>(p: impl Parser<I, O, S>,
 /// * `input` - the input to be parsed
 pub fn parse<I: SliceLike, O>(p: impl Parser<I, O, ()>,
                               input: I) -> AnpaResult<I, O> {
-    let mut parser_state = AnpaState { input, user_state: &mut () };
+    let mut user_state = ();
+    let mut parser_state = AnpaState::new(input, &mut user_state);
     let result = p(&mut parser_state);
     AnpaResult { state: parser_state.input, result }
-}
\ No newline at end of file
+}
+
+/// Perform a parse with provided user state, returning the result together with the
+/// number of items consumed from the start of the input. See [`parse_prefix`].
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `input` - the input to be parsed
+/// * `user_state` - the user state
+pub fn parse_prefix_state<I: SliceLike, O, S>(p: impl Parser<I, O, S>,
+                                              input: I,
+                                              user_state: &mut S) -> (Option<O>, I::Idx) {
+    let res = parse_state(p, input, user_state);
+    (res.result, consumed_len(input, res.state.input))
+}
+
+/// Perform a parse, returning the result together with the number of items consumed from the
+/// start of the input. This is useful when embedding a parser in a larger scanner, which
+/// should resume after the parsed prefix.
+///
+/// Note: For `&str`, the number of bytes consumed will be returned. On failure, the returned
+/// count is the number of items consumed before the parser failed.
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `input` - the input to be parsed
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::number::integer;
+///
+/// let input = "123 rest";
+/// let (res, len) = parse_prefix(integer::<u32, _, _, _>(), input);
+/// assert_eq!(res, Some(123));
+/// assert_eq!(&input[len..], " rest");
+/// ```
+pub fn parse_prefix<I: SliceLike, O>(p: impl Parser<I, O, ()>,
+                                     input: I) -> (Option<O>, I::Idx) {
+    parse_prefix_state(p, input, &mut ())
+}
+
+/// Perform a parse with provided user state, requiring all input to be consumed.
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `input` - the input to be parsed
+/// * `user_state` - the user state
+pub fn parse_all_state<I: SliceLike, O, S>(p: impl Parser<I, O, S>,
+                                           input: I,
+                                           user_state: &mut S) -> Result<O, AnpaError<I>> {
+    let res = parse_state(p, input, user_state);
+    let remaining = res.state.input;
+    match res.result {
+        Some(result) if remaining.slice_is_empty() => Ok(result),
+        Some(_) => Err(AnpaError { kind: AnpaErrorKind::Incomplete,
+                                   remaining,
+                                   offset: consumed_len(input, remaining),
+                                   expected: None }),
+        None => Err(failure(input, &res.state))
+    }
+}
+
+/// Create the error for a failed parse of `input`, given the final state.
+fn failure<I: SliceLike, S>(input: I, state: &AnpaState<I, S>) -> AnpaError<I> {
+    let (remaining, expected) = match state.expected {
+        Some((label, len)) => (input.slice_from(input.slice_len() - len), Some(label)),
+        None => (state.input, None)
+    };
+    AnpaError { kind: AnpaErrorKind::Failed, remaining, offset: consumed_len(input, remaining), expected }
+}
+
+/// Perform a parse, requiring all input to be consumed.
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `input` - the input to be parsed
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::number::integer;
+///
+/// assert_eq!(parse_all(integer(), "123"), Ok(123));
+///
+/// let err = parse_all(integer::<u32, _, _, _>(), "123abc").unwrap_err();
+/// assert_eq!(err.kind, AnpaErrorKind::Incomplete);
+/// assert_eq!(err.remaining, "abc");
+/// assert_eq!(err.offset, 3);
+///
+/// let err = parse_all(integer::<u32, _, _, _>(), "abc").unwrap_err();
+/// assert_eq!(err.kind, AnpaErrorKind::Failed);
+/// ```
+pub fn parse_all<I: SliceLike, O>(p: impl Parser<I, O, ()>,
+                                  input: I) -> Result<O, AnpaError<I>> {
+    parse_all_state(p, input, &mut ())
+}
+
+/// Iterator that applies a parser repeatedly to the remaining input. See [`parse_iter`].
+pub struct ParseIter<I: SliceLike, O, S, U, P> {
+    parser: P,
+    input: I,
+    remaining: I,
+    user_state: U,
+    error: Option<AnpaError<I>>,
+    done: bool,
+    _phantom: PhantomData<fn(&mut S) -> O>
+}
+
+impl<I: SliceLike, O, S, U, P> ParseIter<I, O, S, U, P> {
+    /// The input that has not been consumed by the successful parses so far.
+    pub fn remaining(&self) -> I {
+        self.remaining
+    }
+
+    /// The error of the parse that stopped the iteration, if it stopped due to a failure
+    /// rather than at the end of the input. The offset is from the start of the original input.
+    pub fn error(&self) -> Option<AnpaError<I>> {
+        self.error
+    }
+}
+
+impl<I: SliceLike, O, S, U: BorrowMut<S>, P: Parser<I, O, S>> Iterator for ParseIter<I, O, S, U, P> {
+    type Item = O;
+
+    fn next(&mut self) -> Option<O> {
+        if self.done || self.remaining.slice_is_empty() {
+            self.done = true;
+            return None
+        }
+
+        let mut state = AnpaState::new(self.remaining, self.user_state.borrow_mut());
+        match (self.parser)(&mut state) {
+            Some(res) => {
+                // Stop after a parse that consumed nothing, since it would be repeated forever.
+                self.done = state.input.slice_len() == self.remaining.slice_len();
+                self.remaining = state.input;
+                Some(res)
+            },
+            None => {
+                self.error = Some(failure(self.input, &state));
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+impl<I: SliceLike, O, S, U: BorrowMut<S>, P: Parser<I, O, S>> FusedIterator for ParseIter<I, O, S, U, P> {}
+
+/// Create an iterator that applies a parser with provided user state repeatedly to the
+/// remaining input. See [`parse_iter`].
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `input` - the input to be parsed
+/// * `user_state` - the user state
+pub fn parse_iter_state<I: SliceLike, O, S, P: Parser<I, O, S>>(p: P,
+                                                                input: I,
+                                                                user_state: &mut S
+) -> ParseIter<I, O, S, &mut S, P> {
+    ParseIter { parser: p, input, remaining: input, user_state, error: None, done: false, _phantom: PhantomData }
+}
+
+/// Create an iterator that applies a parser repeatedly to the remaining input, yielding each
+/// result, e.g. for streams of tokens or records. Unlike
+/// [`many_to_vec`](crate::combinators::many_to_vec), the results are not collected.
+///
+/// The iteration stops when the input is empty, when the parser fails, or after a result for
+/// which the parser consumed no input. Afterwards, the remaining input and the error, if the
+/// parser failed, are available from the iterator.
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `input` - the input to be parsed
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::left;
+/// use anpa::number::integer;
+/// use anpa::parsers::skip;
+///
+/// let mut records = parse_iter(left(integer::<u32, _, _, _>(), skip(';')), "1;2;3;x;4;");
+///
+/// assert_eq!(records.by_ref().sum::<u32>(), 6);
+/// assert_eq!(records.remaining(), "x;4;");
+/// assert_eq!(records.error().unwrap().offset, 6);
+/// ```
+pub fn parse_iter<I: SliceLike, O, P: Parser<I, O, ()>>(p: P, input: I) -> ParseIter<I, O, (), (), P> {
+    ParseIter { parser: p, input, remaining: input, user_state: (), error: None, done: false, _phantom: PhantomData }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{combinators::{labelled, lift_to_state, right}, core::{parse_iter, parse_iter_state, ParserExt}, parsers::{skip, success, take}};
+
+    #[test]
+    fn parse_iter_test() {
+        let mut count = 0;
+        let p = lift_to_state(|n: &mut u32, c| { *n += 1; c }, right(skip(' '), take('a')));
+        let mut iter = parse_iter_state(p, " a a", &mut count);
+        assert_eq!(iter.by_ref().count(), 2);
+        assert!(iter.error().is_none());
+        assert_eq!(iter.remaining(), "");
+        assert_eq!(count, 2);
+
+        let mut iter = parse_iter(right(skip(' '), labelled(take('a'), "a")), " a b");
+        assert_eq!(iter.next(), Some('a'));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.remaining(), " b");
+        let error = iter.error().unwrap();
+        assert_eq!((error.offset, error.expected), (3, Some("a")));
+
+        assert_eq!(parse_iter(success(), "ab").count(), 1);
+    }
+
+    #[test]
+    fn result() {
+        use crate::{core::{parse, parse_state}, number::integer};
+
+        let p = lift_to_state(|n: &mut u32, i: u32| *n += i, integer());
+        let mut sum = 0;
+        let res = parse_state(p, "12ab", &mut sum);
+        assert_eq!((res.remaining(), res.consumed("12ab")), ("ab", "12"));
+        assert_eq!(res.ok_or_rest(), Ok(()));
+        parse_state(p, "3", &mut sum).unwrap_full();
+        assert_eq!(parse_state(p, "x", &mut sum).require_empty(), Err("x"));
+        assert_eq!(sum, 15);
+
+        let res = parse(integer::<u8, _, _, _>(), "x");
+        assert_eq!((res.remaining(), res.consumed("x"), res.ok_or_rest()), ("x", "", Err("x")));
+        assert_eq!(parse(take('a'), "a").unwrap_full(), 'a');
+    }
+
+    #[test]
+    #[should_panic(expected = "remaining: \"b\"")]
+    fn unwrap_full_incomplete() {
+        crate::core::parse(take('a'), "ab").unwrap_full();
+    }
+
+    #[test]
+    fn from_str() {
+        use crate::{combinators::labelled, core::{AnpaErrorKind, FromStrError}, number::integer};
+
+        #[derive(Debug, PartialEq)]
+        struct Id(u16);
+        impl_from_str!(Id, right(skip('#'), labelled(integer(), "integer")).map(Id));
+
+        assert_eq!("#65535".parse(), Ok(Id(65535)));
+        assert_eq!("#".parse::<Id>(), Err(FromStrError { kind: AnpaErrorKind::Failed, offset: 1, found: None, expected: Some("integer") }));
+        assert_eq!("#1é".parse::<Id>().unwrap_err().found, Some('é'));
+
+        #[cfg(feature = "alloc")] {
+            use alloc::string::ToString;
+            assert_eq!("#x".parse::<Id>().unwrap_err().to_string(), "expected integer, found 'x' at offset 1");
+            assert_eq!("".parse::<Id>().unwrap_err().to_string(), "parse failed at offset 0");
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn error_display() {
+        use alloc::string::ToString;
+        use crate::{combinators::labelled, core::parse_all, parsers::item_if};
+
+        // The items of the input don't need to implement `Debug`.
+        #[derive(Clone, Copy, PartialEq)]
+        struct Token(u8);
+
+        let input = [Token(1), Token(2)];
+        let p = right(item_if(|t: &Token| t.0 == 1), labelled(item_if(|t: &Token| t.0 == 1), "one"));
+        assert_eq!(parse_all(p, input.as_slice()).err().unwrap().to_string(), "expected one at offset 1");
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derive_hygiene() {
+        use crate::{core::{parse, Parse}, number::integer, parsers::skip};
+
+        // Fields named as the variables used internally by the generated parsers.
+        #[derive(Parse, Debug, PartialEq)]
+        struct Fields {
+            #[anpa(with = integer())]
+            s: u32,
+            #[anpa(before = skip(','), with = integer())]
+            res: u32
+        }
+
+        #[derive(Parse, Debug, PartialEq)]
+        #[anpa(after = skip('.'))]
+        enum Variants {
+            Pair {
+                #[anpa(with = integer())]
+                s: u32,
+                #[anpa(before = skip(','), with = integer())]
+                res: u32
+            }
+        }
+
+        assert_eq!(parse(Fields::parser(), "1,2").result, Some(Fields { s: 1, res: 2 }));
+        assert_eq!(parse(Variants::parser(), "1,2.").result, Some(Variants::Pair { s: 1, res: 2 }));
+    }
+}
@@ -0,0 +1,266 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+
+use crate::{core::Parser, slicelike::{consumed_len, SliceLike}};
+
+/// The severity of a [`Diagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Something suspicious that doesn't prevent a successful parse.
+    Warning,
+
+    /// Something invalid, e.g. input that was skipped using
+    /// [`recover_with`](crate::combinators::recover_with).
+    Error
+}
+
+/// A diagnostic emitted during parsing, e.g. using [`emit_warning`] or
+/// [`warn_if`](crate::core::ParserExt::warn_if).
+#[derive(Clone, Copy, PartialEq)]
+pub struct Diagnostic<I: SliceLike> {
+    /// The severity of the diagnostic.
+    pub severity: Severity,
+
+    /// The message of the diagnostic.
+    pub message: &'static str,
+
+    /// The remaining input at the start of the diagnostic.
+    pub at: I,
+
+    /// The length of the input that the diagnostic concerns. May be empty.
+    pub len: I::Idx
+}
+
+impl<I: SliceLike + Debug> Debug for Diagnostic<I> where I::Idx: Debug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Diagnostic")
+            .field("severity", &self.severity)
+            .field("message", &self.message)
+            .field("at", &self.at)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl<I: SliceLike> Diagnostic<I> {
+    /// The input that the diagnostic concerns.
+    pub fn span(&self) -> I {
+        self.at.slice_to(self.len)
+    }
+
+    /// The offset of the diagnostic from the start of `input`, i.e. the input that was
+    /// originally passed to the parser.
+    ///
+    /// Note: For `&str`, the offset is in bytes.
+    pub fn offset(&self, input: I) -> I::Idx {
+        input.slice_len() - self.at.slice_len()
+    }
+}
+
+/// A list of diagnostics that can be used as, or be part of, the user state.
+///
+/// The parsers in this module require the user state to implement `AsMut<Diagnostics<I>>`,
+/// which is implemented for `Diagnostics` itself. For a custom user state, implement
+/// `AsMut` to return its diagnostics.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::diagnostics::{Diagnostics, Severity};
+/// use anpa::number::integer;
+///
+/// struct State<'a> {
+///     sum: u32,
+///     diagnostics: Diagnostics<&'a str>
+/// }
+///
+/// impl<'a> AsMut<Diagnostics<&'a str>> for State<'a> {
+///     fn as_mut(&mut self) -> &mut Diagnostics<&'a str> {
+///         &mut self.diagnostics
+///     }
+/// }
+///
+/// let p = integer::<u32, _, _, _>().warn_if(|n| *n == 0, "zero has no effect");
+///
+/// let mut state = State { sum: 0, diagnostics: Diagnostics::new() };
+/// let input = "0";
+/// assert_eq!(parse_state(p, input, &mut state).result, Some(0));
+///
+/// let warning = state.diagnostics.iter().next().unwrap();
+/// assert_eq!(warning.severity, Severity::Warning);
+/// assert_eq!(warning.span(), "0");
+/// assert_eq!(warning.offset(input), 0);
+/// ```
+#[derive(Clone, PartialEq)]
+pub struct Diagnostics<I: SliceLike> {
+    diagnostics: Vec<Diagnostic<I>>
+}
+
+impl<I: SliceLike + Debug> Debug for Diagnostics<I> where I::Idx: Debug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<I: SliceLike> Diagnostics<I> {
+    /// Create a new, empty list.
+    pub fn new() -> Self {
+        Self { diagnostics: Vec::new() }
+    }
+
+    /// Add a diagnostic.
+    pub fn push(&mut self, diagnostic: Diagnostic<I>) {
+        self.diagnostics.push(diagnostic)
+    }
+
+    /// Iterate over all diagnostics, in the order they were emitted.
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic<I>> {
+        self.diagnostics.iter()
+    }
+
+    /// Check if any diagnostic with [`Severity::Error`] has been emitted.
+    pub fn has_errors(&self) -> bool {
+        self.iter().any(|d| d.severity == Severity::Error)
+    }
+
+    /// Remove all diagnostics.
+    pub fn clear(&mut self) {
+        self.diagnostics.clear()
+    }
+
+    /// The number of diagnostics.
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    /// Check if the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
+impl<I: SliceLike> Default for Diagnostics<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: SliceLike> AsMut<Diagnostics<I>> for Diagnostics<I> {
+    fn as_mut(&mut self) -> &mut Diagnostics<I> {
+        self
+    }
+}
+
+impl<I: SliceLike> IntoIterator for Diagnostics<I> {
+    type Item = Diagnostic<I>;
+    type IntoIter = alloc::vec::IntoIter<Diagnostic<I>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.diagnostics.into_iter()
+    }
+}
+
+/// Create a parser that emits a diagnostic at the current position. This parser always
+/// succeeds.
+///
+/// ### Consuming
+/// Nothing
+///
+/// ### Arguments
+/// * `severity` - the severity of the diagnostic
+/// * `message` - the message of the diagnostic
+#[inline]
+pub fn emit<I: SliceLike, S: AsMut<Diagnostics<I>>>(severity: Severity,
+                                                    message: &'static str
+) -> impl Parser<I, (), S> {
+    create_parser!(s, {
+        let at = s.input;
+        s.user_state.as_mut().push(Diagnostic { severity, message, at, len: Default::default() });
+        Some(())
+    })
+}
+
+/// Create a parser that emits a warning at the current position. This parser always
+/// succeeds.
+///
+/// ### Consuming
+/// Nothing
+///
+/// ### Arguments
+/// * `message` - the message of the warning
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{left, or};
+/// use anpa::diagnostics::{emit_warning, Diagnostics};
+/// use anpa::parsers::{skip, take};
+///
+/// // Accept a missing semicolon, but warn about it.
+/// let statement = left(take("x"), or(skip(';'), emit_warning("missing `;`")));
+///
+/// let mut diagnostics = Diagnostics::new();
+/// assert_eq!(parse_state(statement, "x", &mut diagnostics).result, Some("x"));
+/// assert_eq!(diagnostics.iter().next().unwrap().message, "missing `;`");
+/// ```
+#[inline]
+pub fn emit_warning<I: SliceLike, S: AsMut<Diagnostics<I>>>(message: &'static str) -> impl Parser<I, (), S> {
+    emit(Severity::Warning, message)
+}
+
+/// Create a parser that emits an error at the current position. This parser always
+/// succeeds.
+///
+/// ### Consuming
+/// Nothing
+///
+/// ### Arguments
+/// * `message` - the message of the error
+#[inline]
+pub fn emit_error<I: SliceLike, S: AsMut<Diagnostics<I>>>(message: &'static str) -> impl Parser<I, (), S> {
+    emit(Severity::Error, message)
+}
+
+/// Transform a parser so that a warning is emitted for the parsed input if the result
+/// matches `pred`. See [`warn_if`](crate::core::ParserExt::warn_if).
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `pred` - the predicate for emitting the warning
+/// * `message` - the message of the warning
+#[inline]
+pub fn warn_if<I: SliceLike, O, S: AsMut<Diagnostics<I>>>(p: impl Parser<I, O, S>,
+                                                          pred: impl FnOnce(&O) -> bool + Copy,
+                                                          message: &'static str
+) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let at = s.input;
+        let res = p(s)?;
+        if pred(&res) {
+            let len = consumed_len(at, s.input);
+            s.user_state.as_mut().push(Diagnostic { severity: Severity::Warning, message, at, len });
+        }
+        Some(res)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{combinators::{left, many, no_separator, or}, core::{parse_state, ParserExt}, diagnostics::{emit_error, Diagnostics, Severity}, number::integer, parsers::skip};
+
+    #[test]
+    fn diagnostics_positions() {
+        let number = integer::<u32, _, _, _>().warn_if(|n| *n > 100, "large number");
+        let p = many(left(number, or(skip(','), emit_error("expected `,`"))), true, no_separator());
+
+        let input = "1,200,3 4";
+        let mut diagnostics = Diagnostics::new();
+        parse_state(p, input, &mut diagnostics);
+
+        assert!(diagnostics.has_errors());
+        let res: Vec<_> = diagnostics.iter().map(|d| (d.severity, d.offset(input), d.span())).collect();
+        assert_eq!(res, [(Severity::Warning, 2, "200"), (Severity::Error, 7, "")]);
+    }
+}
@@ -0,0 +1,175 @@
+use alloc::vec::Vec;
+
+use crate::{core::Parser, findbyte::ByteInput};
+
+/// A segment of text with shell-style expansions, as produced by [`expand_parser`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<I> {
+    /// Text to use as-is.
+    Literal(I),
+
+    /// A variable, i.e. `$NAME`, `${NAME}` or `${NAME:-default}`. The default value is not
+    /// parsed, and may contain further expansions.
+    Var { name: I, default: Option<I> },
+
+    /// A home directory, i.e. `~` or `~user`. The user name is empty for the current user.
+    Tilde(I)
+}
+
+fn is_name_start(byte: u8) -> bool {
+    byte.is_ascii_alphabetic() || byte == b'_'
+}
+
+fn is_name(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+fn name_len(bytes: &[u8]) -> usize {
+    match bytes.first() {
+        Some(b) if is_name_start(*b) => bytes.iter().take_while(|b| is_name(**b)).count(),
+        _ => 0
+    }
+}
+
+/// Parse the variable starting with the `$` at `pos`. Returns the segment and the position
+/// after it, or `Err` if the variable is invalid. A `$` without a name is not a variable.
+fn variable<'a, I: ByteInput<'a>>(input: I, pos: usize) -> Result<Option<(Segment<I>, usize)>, ()> {
+    let bytes = input.as_byte_slice();
+    let start = pos + 1;
+
+    if bytes.get(start) != Some(&b'{') {
+        let len = name_len(&bytes[start..]);
+        let var = Segment::Var { name: input.slice_to(start + len).slice_from(start), default: None };
+        return Ok((len > 0).then_some((var, start + len)))
+    }
+
+    // Find the closing brace, allowing nested braces in the default value.
+    let mut depth = 0;
+    let close = start + bytes[start..].iter().position(|b| {
+        depth += (*b == b'{') as usize;
+        depth -= (*b == b'}') as usize;
+        depth == 0
+    }).ok_or(())?;
+
+    let name_start = start + 1;
+    let name_end = name_start + name_len(&bytes[name_start..close]);
+    let name = input.slice_to(name_end).slice_from(name_start);
+
+    let default = match &bytes[name_end..close] {
+        _ if name_end == name_start => return Err(()),
+        [] => None,
+        [b':', b'-', ..] => Some(input.slice_to(close).slice_from(name_end + 2)),
+        _ => return Err(())
+    };
+
+    Ok(Some((Segment::Var { name, default }, close + 1)))
+}
+
+/// Get the position after the user name of the `~` at `pos`, if it's a home directory.
+fn tilde_end(bytes: &[u8], pos: usize) -> Option<usize> {
+    let start = pos + 1;
+    let len = bytes[start..].iter()
+        .take_while(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-'))
+        .count();
+
+    matches!(bytes.get(start + len), None | Some(b'/' | b':')).then_some(start + len)
+}
+
+/// Create a parser that splits text with shell-style expansions into [`Segment`]s, e.g. for
+/// resolving environment variables in configuration values. The expansions are not resolved.
+///
+/// The supported expansions are variables (`$NAME`, `${NAME}` and `${NAME:-default}`), and
+/// home directories (`~` and `~user`) at the start of the text or after a `:`, followed by
+/// a `/`, a `:` or the end of the text. A `$` or `~` that doesn't start an expansion is
+/// literal, and `\` escapes a following `$`, `~` or `\`.
+///
+/// ### Consuming
+/// Consumes the whole input, on successful parse. The parser fails if a `${...}` expansion
+/// is unterminated or invalid.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::expand::{expand_parser, Segment};
+///
+/// let res = parse(expand_parser(), "~/cache:${XDG_CACHE:-/tmp}/$USER costs \\$5");
+/// assert_eq!(res.result, Some(vec![
+///     Segment::Tilde(""),
+///     Segment::Literal("/cache:"),
+///     Segment::Var { name: "XDG_CACHE", default: Some("/tmp") },
+///     Segment::Literal("/"),
+///     Segment::Var { name: "USER", default: None },
+///     Segment::Literal(" costs "),
+///     Segment::Literal("$5"),
+/// ]));
+///
+/// assert_eq!(parse(expand_parser(), "${UNTERMINATED").result, None);
+/// ```
+#[inline]
+pub fn expand_parser<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Vec<Segment<I>>, S> {
+    create_parser!(s, {
+        let input = s.input;
+        let bytes = input.as_byte_slice();
+        let mut segments = Vec::new();
+        let mut literal_start = 0;
+        let mut i = 0;
+
+        let mut push = |segments: &mut Vec<_>, literal_end: usize, segment: Option<Segment<I>>, next: usize| {
+            if literal_end > literal_start {
+                segments.push(Segment::Literal(input.slice_to(literal_end).slice_from(literal_start)));
+            }
+            segments.extend(segment);
+            literal_start = next;
+        };
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' if matches!(bytes.get(i + 1), Some(b'$' | b'~' | b'\\')) => {
+                    push(&mut segments, i, None, i + 1);
+                    i += 2;
+                },
+                b'$' => match variable(input, i).ok()? {
+                    Some((var, end)) => {
+                        push(&mut segments, i, Some(var), end);
+                        i = end;
+                    },
+                    None => i += 1
+                },
+                b'~' if i == 0 || bytes[i - 1] == b':' => match tilde_end(bytes, i) {
+                    Some(end) => {
+                        push(&mut segments, i, Some(Segment::Tilde(input.slice_to(end).slice_from(i + 1))), end);
+                        i = end;
+                    },
+                    None => i += 1
+                },
+                _ => i += 1
+            }
+        }
+
+        push(&mut segments, bytes.len(), None, bytes.len());
+        s.input = input.slice_from(bytes.len());
+        Some(segments)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::{core::parse, expand::{expand_parser, Segment::*}};
+
+    #[test]
+    fn expand() {
+        assert_eq!(parse(expand_parser(), "").result, Some(vec![]));
+        assert_eq!(parse(expand_parser(), "$ $1 ${A:-${B:-c}}x").result,
+                   Some(vec![Literal("$ $1 "), Var { name: "A", default: Some("${B:-c}") }, Literal("x")]));
+        assert_eq!(parse(expand_parser(), "~root:a~b:~x y:\\~:\\\\$A").result,
+                   Some(vec![Tilde("root"), Literal(":a~b:~x y:"), Literal("~:"), Literal("\\"), Var { name: "A", default: None }]));
+        assert_eq!(parse(expand_parser(), b"${A}${_b2}".as_slice()).result,
+                   Some(vec![Var { name: &b"A"[..], default: None }, Var { name: b"_b2", default: None }]));
+
+        for invalid in ["${}", "${1}", "${A-b}", "${A:-{}"] {
+            assert_eq!(parse(expand_parser(), invalid).result, None);
+        }
+    }
+}
@@ -0,0 +1,1244 @@
+use core::{convert::TryInto, ops::{BitAnd, BitOr, Not as NotOp}};
+
+use crate::{core::Parser, slicelike::SliceLike};
+
+const LOW_BITS: u64 = 0x7f7f_7f7f_7f7f_7f7f;
+const HIGH_BITS: u64 = 0x8080_8080_8080_8080;
+
+/// Repeat `b` in all bytes of a word.
+#[inline(always)]
+const fn splat(b: u8) -> u64 {
+    b as u64 * 0x0101_0101_0101_0101
+}
+
+/// Get a word with the high bit set for each byte in `word` that is zero.
+#[inline(always)]
+fn zero_bytes(word: u64) -> u64 {
+    // Exact zero byte detection. Unlike the common `(x - 0x01..) & !x & 0x80..`,
+    // no borrows can propagate between bytes, so there are no false positives.
+    !(((word & LOW_BITS) + LOW_BITS) | word | LOW_BITS)
+}
+
+/// Get a word with the high bit set for each byte in `word` that is less than `n`.
+#[inline(always)]
+fn less_than(word: u64, n: u16) -> u64 {
+    if n <= 128 {
+        // The high bit of the sum is set iff the low 7 bits are >= n. The high bit of the
+        // byte itself must be cleared for the byte to be less than n.
+        !(((word & LOW_BITS) + splat((128 - n) as u8)) | word) & HIGH_BITS
+    } else {
+        let low_less = !((word & LOW_BITS) + splat((256 - n) as u8));
+        (!word | low_less) & HIGH_BITS
+    }
+}
+
+/// Trait for finding bytes matching some criteria, using SWAR (SIMD within a register) to
+/// check eight bytes at a time.
+///
+/// Finders can be combined using `|` and `&`, and negated using `!`.
+pub trait ByteFinder: Copy {
+    /// Get a word where the high bit of each byte is set if the corresponding byte in `word`
+    /// matches, and all other bits are cleared.
+    fn find_in_word(self, word: u64) -> u64;
+
+    /// Check if a single byte matches.
+    fn matches(self, byte: u8) -> bool;
+}
+
+/// Finder for a single byte value. See [`eq`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Eq(u8);
+
+/// Finder for an inclusive range of byte values. See [`in_range`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InRange(u8, u8);
+
+/// Finder for bytes with certain bits set to a value. See [`mask`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mask(u8, u8);
+
+/// Finder for a set of bytes. See [`byteset`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteSet([u64; 4]);
+
+/// Finder matching bytes matched by any of two finders. Created using `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Or<A, B>(A, B);
+
+/// Finder matching bytes matched by both of two finders. Created using `&`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct And<A, B>(A, B);
+
+/// Finder matching bytes not matched by a finder. Created using `!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Not<A>(A);
+
+impl ByteFinder for Eq {
+    #[inline(always)]
+    fn find_in_word(self, word: u64) -> u64 {
+        zero_bytes(word ^ splat(self.0))
+    }
+
+    #[inline(always)]
+    fn matches(self, byte: u8) -> bool {
+        byte == self.0
+    }
+}
+
+impl ByteFinder for InRange {
+    #[inline(always)]
+    fn find_in_word(self, word: u64) -> u64 {
+        !less_than(word, self.0 as u16) & less_than(word, self.1 as u16 + 1)
+    }
+
+    #[inline(always)]
+    fn matches(self, byte: u8) -> bool {
+        (self.0..=self.1).contains(&byte)
+    }
+}
+
+impl ByteFinder for Mask {
+    #[inline(always)]
+    fn find_in_word(self, word: u64) -> u64 {
+        zero_bytes((word & splat(self.0)) ^ splat(self.1))
+    }
+
+    #[inline(always)]
+    fn matches(self, byte: u8) -> bool {
+        byte & self.0 == self.1
+    }
+}
+
+impl ByteFinder for ByteSet {
+    #[inline(always)]
+    fn find_in_word(self, word: u64) -> u64 {
+        // No arithmetic trick is available for arbitrary sets, so look up each byte in the
+        // bitmap. This still benefits from loading and checking a whole word at a time.
+        let mut res = 0;
+        for i in 0..8 {
+            res |= (self.matches((word >> (i * 8)) as u8) as u64) << (i * 8 + 7);
+        }
+        res
+    }
+
+    #[inline(always)]
+    fn matches(self, byte: u8) -> bool {
+        self.contains(byte)
+    }
+}
+
+impl ByteSet {
+    /// Check if `byte` is in the set. Unlike [`ByteFinder::matches`], this can be used in
+    /// `const` contexts.
+    #[inline(always)]
+    pub const fn contains(&self, byte: u8) -> bool {
+        self.0[(byte >> 6) as usize] & (1 << (byte & 63)) != 0
+    }
+}
+
+impl<A: ByteFinder, B: ByteFinder> ByteFinder for Or<A, B> {
+    #[inline(always)]
+    fn find_in_word(self, word: u64) -> u64 {
+        self.0.find_in_word(word) | self.1.find_in_word(word)
+    }
+
+    #[inline(always)]
+    fn matches(self, byte: u8) -> bool {
+        self.0.matches(byte) || self.1.matches(byte)
+    }
+}
+
+impl<A: ByteFinder, B: ByteFinder> ByteFinder for And<A, B> {
+    #[inline(always)]
+    fn find_in_word(self, word: u64) -> u64 {
+        self.0.find_in_word(word) & self.1.find_in_word(word)
+    }
+
+    #[inline(always)]
+    fn matches(self, byte: u8) -> bool {
+        self.0.matches(byte) && self.1.matches(byte)
+    }
+}
+
+impl<A: ByteFinder> ByteFinder for Not<A> {
+    #[inline(always)]
+    fn find_in_word(self, word: u64) -> u64 {
+        !self.0.find_in_word(word) & HIGH_BITS
+    }
+
+    #[inline(always)]
+    fn matches(self, byte: u8) -> bool {
+        !self.0.matches(byte)
+    }
+}
+
+macro_rules! impl_ops {
+    ($t:ident $(<$($g:ident),*>)?) => {
+        impl<$($($g: Copy,)*)? Rhs: Copy> BitOr<Rhs> for $t $(<$($g),*>)? {
+            type Output = Or<Self, Rhs>;
+
+            #[inline(always)]
+            fn bitor(self, rhs: Rhs) -> Self::Output {
+                Or(self, rhs)
+            }
+        }
+
+        impl<$($($g: Copy,)*)? Rhs: Copy> BitAnd<Rhs> for $t $(<$($g),*>)? {
+            type Output = And<Self, Rhs>;
+
+            #[inline(always)]
+            fn bitand(self, rhs: Rhs) -> Self::Output {
+                And(self, rhs)
+            }
+        }
+
+        impl $(<$($g: Copy),*>)? NotOp for $t $(<$($g),*>)? {
+            type Output = Not<Self>;
+
+            #[inline(always)]
+            fn not(self) -> Self::Output {
+                Not(self)
+            }
+        }
+    };
+}
+
+impl_ops!(Eq);
+impl_ops!(InRange);
+impl_ops!(Mask);
+impl_ops!(ByteSet);
+impl_ops!(Or<A, B>);
+impl_ops!(And<A, B>);
+impl_ops!(Not<A>);
+impl_ops!(ItemEq<T>);
+impl_ops!(ItemInRange<T>);
+
+/// Get a finder matching the byte `b`.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::findbyte::{eq, find_byte};
+///
+/// let p = find_byte(eq(b',') | eq(b';'));
+/// assert_eq!(parse(p, "abc;def").result, Some("abc"));
+/// ```
+#[inline]
+pub const fn eq(b: u8) -> Eq {
+    Eq(b)
+}
+
+/// Get a finder matching bytes in the inclusive range `lo..=hi`.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::findbyte::{find_byte, in_range};
+///
+/// // Find the first non-ASCII byte.
+/// let p = find_byte(in_range(0x80, 0xff));
+/// assert_eq!(parse(p, "abcåäö".as_bytes()).result, Some("abc".as_bytes()));
+/// ```
+#[inline]
+pub const fn in_range(lo: u8, hi: u8) -> InRange {
+    InRange(lo, hi)
+}
+
+/// Get a finder matching bytes where the bits selected by `m` are equal to `value`,
+/// i.e. `byte & m == value`.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::findbyte::{find_byte, mask};
+///
+/// // Find the first byte with the high bit set, except UTF-8 continuation bytes.
+/// let p = find_byte(mask(0x80, 0x80) & !mask(0xc0, 0x80));
+/// assert_eq!(parse(p, "abcåäö".as_bytes()).result, Some("abc".as_bytes()));
+/// ```
+#[inline]
+pub const fn mask(m: u8, value: u8) -> Mask {
+    Mask(m, value)
+}
+
+/// Get a finder matching any of the provided bytes. Use this for sets that can't be expressed
+/// by a few other finders, since each byte is checked individually.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::findbyte::{byteset, find_byte};
+///
+/// const BRACKETS: anpa::findbyte::ByteSet = byteset(b"(){}[]");
+///
+/// let p = find_byte(BRACKETS);
+/// assert_eq!(parse(p, "f(x)").result, Some("f"));
+/// ```
+#[inline]
+pub const fn byteset(bytes: &[u8]) -> ByteSet {
+    let mut set = [0; 4];
+    let mut i = 0;
+    while i < bytes.len() {
+        set[(bytes[i] >> 6) as usize] |= 1 << (bytes[i] & 63);
+        i += 1;
+    }
+    ByteSet(set)
+}
+
+/// Get a finder matching ASCII digits.
+#[inline]
+pub const fn digit() -> InRange {
+    in_range(b'0', b'9')
+}
+
+/// Get a finder matching ASCII letters.
+#[inline]
+pub const fn alpha() -> Or<InRange, InRange> {
+    Or(in_range(b'a', b'z'), in_range(b'A', b'Z'))
+}
+
+/// Get a finder matching ASCII letters and digits.
+#[inline]
+pub const fn alphanumeric() -> Or<Or<InRange, InRange>, InRange> {
+    Or(alpha(), digit())
+}
+
+/// Get a finder matching ASCII whitespace, as defined by [`u8::is_ascii_whitespace`].
+#[inline]
+pub const fn whitespace() -> Or<Or<InRange, InRange>, Eq> {
+    Or(Or(in_range(b'\t', b'\n'), in_range(b'\x0C', b'\r')), Eq(b' '))
+}
+
+/// Get a finder matching characters commonly allowed in identifiers, i.e. ASCII letters,
+/// digits and `_`.
+#[inline]
+pub const fn ident() -> Or<Or<Or<InRange, InRange>, InRange>, Eq> {
+    Or(alphanumeric(), Eq(b'_'))
+}
+
+/// Load the (possibly unaligned) word starting at `pos`.
+#[inline(always)]
+fn load_word(bytes: &[u8], pos: usize) -> u64 {
+    u64::from_le_bytes(bytes[pos..pos + 8].try_into().unwrap())
+}
+
+/// Get the position of the first byte in `bytes` matched by `finder`.
+///
+/// The first word is loaded unaligned, and the search then continues from the next aligned
+/// word, so that the main loop reads whole words directly instead of copying each chunk.
+/// The last, partial, word is checked by loading the final eight bytes, ignoring the bytes
+/// already checked.
+#[inline]
+pub(crate) fn get_byte_pos(bytes: &[u8], finder: impl ByteFinder) -> Option<usize> {
+    if bytes.len() < 8 {
+        return bytes.iter().position(|b| finder.matches(*b))
+    }
+
+    let found = finder.find_in_word(load_word(bytes, 0));
+    if found != 0 {
+        return Some((found.trailing_zeros() / 8) as usize)
+    }
+
+    // Continue from the next aligned word, rechecking up to seven bytes of the first one.
+    let mut pos = match bytes.as_ptr().align_offset(8) {
+        0 => 8,
+        offset => offset.min(8)
+    };
+
+    // SAFETY: any bit pattern is a valid `u64`.
+    let (head, words, _) = unsafe { bytes[pos..].align_to::<u64>() };
+    if let Some(i) = head.iter().position(|b| finder.matches(*b)) {
+        return Some(pos + i)
+    }
+    pos += head.len();
+
+    for word in words {
+        let found = finder.find_in_word(u64::from_le(*word));
+        if found != 0 {
+            return Some(pos + (found.trailing_zeros() / 8) as usize)
+        }
+        pos += 8;
+    }
+
+    if pos == bytes.len() {
+        return None
+    }
+
+    // The final word overlaps the bytes already checked, which are masked out.
+    let last = bytes.len() - 8;
+    let found = finder.find_in_word(load_word(bytes, last)) & (!0 << ((pos - last) * 8));
+    (found != 0).then(|| last + (found.trailing_zeros() / 8) as usize)
+}
+
+/// Get the position of the first byte in `haystack` matched by `finder`. This is the same
+/// search as used by the parsers in this module, for use outside of parsers.
+///
+/// ### Example
+/// ```
+/// use anpa::findbyte::{eq, find, whitespace};
+///
+/// assert_eq!(find(b"key = value", whitespace() | eq(b'=')), Some(3));
+/// assert_eq!(find(b"key", whitespace()), None);
+/// ```
+#[inline]
+pub fn find(haystack: &[u8], finder: impl ByteFinder) -> Option<usize> {
+    get_byte_pos(haystack, finder)
+}
+
+/// Get an iterator over the positions of all bytes in `haystack` matched by `finder`.
+///
+/// ### Example
+/// ```
+/// use anpa::findbyte::{eq, find_iter};
+///
+/// let positions: Vec<_> = find_iter(b"a,b,,c", eq(b',')).collect();
+/// assert_eq!(positions, [1, 3, 4]);
+/// ```
+#[inline]
+pub fn find_iter<F: ByteFinder>(haystack: &[u8], finder: F) -> FindIter<'_, F> {
+    FindIter { haystack, finder, offset: 0 }
+}
+
+/// Iterator over the positions of matching bytes. See [`find_iter`].
+#[derive(Debug, Clone)]
+pub struct FindIter<'a, F> {
+    haystack: &'a [u8],
+    finder: F,
+    offset: usize
+}
+
+impl<F: ByteFinder> Iterator for FindIter<'_, F> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        let pos = self.offset + get_byte_pos(&self.haystack[self.offset..], self.finder)?;
+        self.offset = pos + 1;
+        Some(pos)
+    }
+}
+
+/// Get the position of the first occurrence of the byte pair `first`, `second` in `bytes`.
+#[inline]
+pub(crate) fn get_pair_pos(bytes: &[u8], first: u8, second: u8) -> Option<usize> {
+    let mut i = 0;
+
+    // Compare the first byte against one word, and the second against the same word
+    // shifted by one byte.
+    while i + 9 <= bytes.len() {
+        let word1 = u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+        let word2 = u64::from_le_bytes(bytes[i + 1..i + 9].try_into().unwrap());
+        let found = Eq(first).find_in_word(word1) & Eq(second).find_in_word(word2);
+        if found != 0 {
+            return Some(i + (found.trailing_zeros() / 8) as usize)
+        }
+        i += 8;
+    }
+
+    bytes[i..].windows(2)
+        .position(|w| w == [first, second])
+        .map(|pos| i + pos)
+}
+
+/// Get the position of the first occurrence of `needle` in `bytes`.
+#[inline]
+pub(crate) fn get_seq_pos(bytes: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle {
+        [] => Some(0),
+        [b] => get_byte_pos(bytes, Eq(*b)),
+        [first, second, rest @ ..] => {
+            let mut offset = 0;
+            loop {
+                let pos = offset + get_pair_pos(&bytes[offset..], *first, *second)?;
+                if bytes[pos + 2..].starts_with(rest) {
+                    return Some(pos)
+                }
+                offset = pos + 1;
+            }
+        }
+    }
+}
+
+/// Trait for input that can be scanned as bytes, i.e. `&[u8]` and `&str`.
+pub trait ByteInput<'a>: SliceLike<Idx = usize> {
+    /// Get the input as bytes.
+    fn as_byte_slice(self) -> &'a [u8];
+
+    /// Check if the input can be split at byte position `pos`.
+    fn is_split_point(self, pos: usize) -> bool;
+}
+
+impl<'a> ByteInput<'a> for &'a [u8] {
+    #[inline(always)]
+    fn as_byte_slice(self) -> &'a [u8] {
+        self
+    }
+
+    #[inline(always)]
+    fn is_split_point(self, _pos: usize) -> bool {
+        true
+    }
+}
+
+impl<'a> ByteInput<'a> for &'a str {
+    #[inline(always)]
+    fn as_byte_slice(self) -> &'a [u8] {
+        self.as_bytes()
+    }
+
+    #[inline(always)]
+    fn is_split_point(self, pos: usize) -> bool {
+        self.is_char_boundary(pos)
+    }
+}
+
+/// Get the position of the first match of `finder` in `input` where the input can be split.
+#[inline]
+pub(crate) fn find_split_point<'a>(input: impl ByteInput<'a>, finder: impl ByteFinder) -> Option<usize> {
+    let bytes = input.as_byte_slice();
+    let mut offset = 0;
+
+    loop {
+        let pos = offset + get_byte_pos(&bytes[offset..], finder)?;
+        if input.is_split_point(pos) {
+            return Some(pos)
+        }
+        offset = pos + 1;
+    }
+}
+
+/// Create a parser that parses until a byte matched by `finder`, using a fast search that
+/// checks eight bytes at a time.
+///
+/// For `&str`, matches that are not at a character boundary are ignored, so finders should
+/// typically only match ASCII characters.
+///
+/// ### Consuming
+/// Consumes all items before the matching byte, but not the byte itself.
+///
+/// ### Arguments
+/// * `finder` - the finder for the byte to search for
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::findbyte::{eq, find_byte, whitespace};
+///
+/// let p = find_byte(whitespace() | eq(b'='));
+///
+/// let res = parse(p, "key=value");
+/// assert_eq!(res.result, Some("key"));
+/// assert_eq!(res.state, "=value");
+/// ```
+#[inline]
+pub fn find_byte<'a, I: ByteInput<'a>, S>(finder: impl ByteFinder) -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        let res;
+        (res, s.input) = s.input.slice_split_at(find_split_point(s.input, finder)?);
+        Some(res)
+    })
+}
+
+/// Create a parser that parses until a byte matched by `finder`, using a fast search that
+/// checks eight bytes at a time. This is a faster version of [`until`](crate::parsers::until)
+/// for byte needles.
+///
+/// For `&str`, matches that are not at a character boundary are ignored, so finders should
+/// typically only match ASCII characters.
+///
+/// ### Consuming
+/// Consumes all items before the matching byte, and the byte itself. For `&str`, the whole
+/// character starting with the matching byte is consumed.
+///
+/// ### Arguments
+/// * `finder` - the finder for the byte to search for
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::findbyte::{eq, until_byte};
+///
+/// let p = until_byte(eq(b';'));
+///
+/// let res = parse(p, "let x = 2;rest");
+/// assert_eq!(res.result, Some("let x = 2"));
+/// assert_eq!(res.state, "rest");
+/// ```
+#[inline]
+pub fn until_byte<'a, I: ByteInput<'a>, S>(finder: impl ByteFinder) -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        let (res, rest) = s.input.slice_split_at(find_split_point(s.input, finder)?);
+        (_, s.input) = rest.slice_first_if(|_| true)?;
+        Some(res)
+    })
+}
+
+/// Create a parser that parses bytes as long as they are matched by `class`, using a fast search
+/// that checks eight bytes at a time. This is a faster version of
+/// [`item_while`](crate::parsers::item_while) for predicates on bytes.
+///
+/// Prebuilt classes are available, e.g. [`digit`], [`alphanumeric`], [`whitespace`] and
+/// [`ident`], and classes can be combined using `|`.
+///
+/// For `&str`, the parser stops at the last character boundary if `class` matches part of a
+/// multi-byte character.
+///
+/// This parser never fails.
+///
+/// ### Arguments
+/// * `class` - the finder for the bytes to parse
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::findbyte::{eq, ident, take_while_byteclass};
+///
+/// let p = take_while_byteclass(ident() | eq(b'-'));
+///
+/// let res = parse(p, "foo-bar_2 = 1");
+/// assert_eq!(res.result, Some("foo-bar_2"));
+/// assert_eq!(res.state, " = 1");
+/// ```
+#[inline]
+pub fn take_while_byteclass<'a, I: ByteInput<'a>, S>(class: impl ByteFinder) -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice();
+        let mut pos = get_byte_pos(bytes, Not(class)).unwrap_or(bytes.len());
+        while !s.input.is_split_point(pos) {
+            pos -= 1;
+        }
+
+        let res;
+        (res, s.input) = s.input.slice_split_at(pos);
+        Some(res)
+    })
+}
+
+/// Create a parser that parses until the byte pair `first`, `second`, e.g. `\r\n` or `*/`,
+/// using a fast search that checks eight positions at a time.
+///
+/// For `&str`, matches that are not at a character boundary are ignored.
+///
+/// ### Consuming
+/// Consumes all items before the matching pair, but not the pair itself.
+///
+/// ### Arguments
+/// * `first` - the first byte of the pair
+/// * `second` - the second byte of the pair
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::findbyte::find_pair;
+///
+/// let p = find_pair(b'*', b'/');
+///
+/// let res = parse(p, "comment */ code");
+/// assert_eq!(res.result, Some("comment "));
+/// assert_eq!(res.state, "*/ code");
+/// ```
+#[inline]
+pub fn find_pair<'a, I: ByteInput<'a>, S>(first: u8, second: u8) -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice();
+        let mut offset = 0;
+
+        let pos = loop {
+            let pos = offset + get_pair_pos(&bytes[offset..], first, second)?;
+            if s.input.is_split_point(pos) && s.input.is_split_point(pos + 2) {
+                break pos
+            }
+            offset = pos + 1;
+        };
+
+        let res;
+        (res, s.input) = s.input.slice_split_at(pos);
+        Some(res)
+    })
+}
+
+/// Create a parser that parses until `needle`, using a fast search for its first two bytes.
+/// This is a faster version of [`until`](crate::parsers::until) for short byte sequences,
+/// e.g. delimiters in protocols.
+///
+/// For `&str`, matches that are not at a character boundary are ignored.
+///
+/// ### Consuming
+/// Consumes all items before the matching needle, and the needle itself.
+///
+/// ### Arguments
+/// * `needle` - the sequence to search for
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::findbyte::until_seq_fast;
+///
+/// let p = until_seq_fast(b"\r\n");
+///
+/// let res = parse(p, "GET / HTTP/1.1\r\nHost: x".as_bytes());
+/// assert_eq!(res.result, Some("GET / HTTP/1.1".as_bytes()));
+/// assert_eq!(res.state, "Host: x".as_bytes());
+/// ```
+#[inline]
+pub fn until_seq_fast<'a, 'b, I: ByteInput<'a>, S>(needle: &'b [u8]) -> impl Parser<I, I, S> + 'b {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice();
+        let mut offset = 0;
+
+        let pos = loop {
+            let pos = offset + get_seq_pos(&bytes[offset..], needle)?;
+            if s.input.is_split_point(pos) && s.input.is_split_point(pos + needle.len()) {
+                break pos
+            }
+            offset = pos + 1;
+        };
+
+        let res = s.input.slice_to(pos);
+        s.input = s.input.slice_from(pos + needle.len());
+        Some(res)
+    })
+}
+
+/// Create a parser that parses until `p` succeeds, as
+/// [`until_parser`](crate::combinators::until_parser), but only tries `p` at the positions
+/// where the next byte is matched by `first`. A fast search that checks eight bytes at a time
+/// is used to jump between these positions.
+///
+/// `first` must match the first byte of all input that `p` can succeed on. For `&str`,
+/// matches that are not at a character boundary are ignored.
+///
+/// ### Consuming
+/// Consumes all items before the terminator, and the input consumed by `p`. Nothing is
+/// consumed on failure.
+///
+/// ### Arguments
+/// * `first` - the finder for the first byte of the terminator
+/// * `p` - the parser for the terminator
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::right;
+/// use anpa::findbyte::{eq, until_parser_from};
+/// use anpa::parsers::{line, skip};
+///
+/// // Parse until a line starting with `---`.
+/// let p = until_parser_from(eq(b'\n'), right(skip("\n---"), line()));
+///
+/// let res = parse(p, "a\nb\n--- end\nc");
+/// assert_eq!(res.result, Some(("a\nb", " end")));
+/// assert_eq!(res.state, "c");
+/// ```
+#[inline]
+pub fn until_parser_from<'a, I: ByteInput<'a>, O, S>(first: impl ByteFinder,
+                                                     p: impl Parser<I, O, S>
+) -> impl Parser<I, (I, O), S> {
+    create_parser!(s, {
+        let start = s.input;
+        let bytes = start.as_byte_slice();
+        let mut offset = 0;
+
+        while let Some(pos) = get_byte_pos(&bytes[offset..], first) {
+            let pos = offset + pos;
+            offset = pos + 1;
+            if !start.is_split_point(pos) {
+                continue
+            }
+
+            s.input = start.slice_from(pos);
+            if let Some(res) = p(s) {
+                return Some((start.slice_to(pos), res))
+            } else if s.cut {
+                return None
+            }
+        }
+
+        s.input = start;
+        None
+    })
+}
+
+/// Create a parser that parses a block between the balanced delimiters `open` and `close`,
+/// e.g. `{ ... }`, including any nested blocks, and returns the content of the block. This
+/// makes it possible to extract a block without a grammar for its content, e.g. to parse it
+/// lazily later.
+///
+/// If `quote` is provided, delimiters in strings enclosed in `quote` are ignored. A `\`
+/// in a string escapes the following byte.
+///
+/// `open` and `close` must be different, and for `&str` they should be ASCII characters.
+///
+/// ### Consuming
+/// Consumes the block, including the delimiters, on successful parse. The parser fails if
+/// the input doesn't start with `open`, or if the block is not closed.
+///
+/// ### Arguments
+/// * `open` - the opening delimiter
+/// * `close` - the closing delimiter
+/// * `quote` - the delimiter of strings, if any
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::findbyte::between_balanced;
+///
+/// let block = between_balanced(b'{', b'}', Some(b'"'));
+///
+/// let res = parse(block, r#"{ a { b } "}" } rest"#);
+/// assert_eq!(res.result, Some(r#" a { b } "}" "#));
+/// assert_eq!(res.state, " rest");
+///
+/// assert_eq!(parse(block, "{ a { b }").result, None);
+/// ```
+#[inline]
+pub fn between_balanced<'a, I: ByteInput<'a>, S>(open: u8,
+                                                  close: u8,
+                                                  quote: Option<u8>
+) -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice();
+        if bytes.first() != Some(&open) {
+            return None
+        }
+
+        // Without strings, search for `open` twice rather than adding a branch to the search.
+        let delimiters = Eq(open) | Eq(close) | Eq(quote.unwrap_or(open));
+        let mut depth = 1;
+        let mut pos = 1;
+
+        loop {
+            pos += get_byte_pos(&bytes[pos..], delimiters)?;
+            let b = bytes[pos];
+            pos += 1;
+
+            if Some(b) == quote {
+                // Skip the string, including escaped bytes.
+                loop {
+                    pos += get_byte_pos(bytes.get(pos..)?, Eq(b) | Eq(b'\\'))?;
+                    if bytes[pos] == b {
+                        pos += 1;
+                        break
+                    }
+                    pos += 2;
+                }
+            } else if b == open {
+                depth += 1;
+            } else {
+                depth -= 1;
+                if depth == 0 {
+                    break
+                }
+            }
+        }
+
+        if !s.input.is_split_point(1) || !s.input.is_split_point(pos - 1) {
+            return None
+        }
+
+        let res = s.input.slice_from(1).slice_to(pos - 2);
+        s.input = s.input.slice_from(pos);
+        Some(res)
+    })
+}
+
+/// The unit of work for a SWAR search of items, i.e. the item type of the haystack. A `u64`
+/// holds `64 / BITS` items, which are checked at once.
+pub trait Work: Copy + PartialOrd + Into<u64> {
+    /// The number of bits of an item.
+    const BITS: u32;
+
+    /// Load `64 / BITS` items into a word, with the first item in the lowest bits.
+    #[inline(always)]
+    fn load(items: &[Self]) -> u64 {
+        items.iter().rev().fold(0, |word, item| word << Self::BITS | (*item).into())
+    }
+}
+
+impl Work for u8 {
+    const BITS: u32 = 8;
+
+    #[inline(always)]
+    fn load(items: &[Self]) -> u64 {
+        u64::from_le_bytes(items.try_into().unwrap())
+    }
+}
+
+impl Work for u16 {
+    const BITS: u32 = 16;
+}
+
+impl Work for u32 {
+    const BITS: u32 = 32;
+}
+
+/// Repeat `n` in all items of a word.
+#[inline(always)]
+const fn splat_item<T: Work>(n: u64) -> u64 {
+    n * (u64::MAX / (u64::MAX >> (64 - T::BITS)))
+}
+
+/// Get a word with the high bit of each item set.
+#[inline(always)]
+const fn high_item_bits<T: Work>() -> u64 {
+    splat_item::<T>(1 << (T::BITS - 1))
+}
+
+/// Get a word with the high bit set for each item in `word` that is less than `n`. This is
+/// [`less_than`] for items of any size.
+#[inline(always)]
+fn item_less_than<T: Work>(word: u64, n: u64) -> u64 {
+    let high = high_item_bits::<T>();
+    let low = !high;
+    if n <= 1 << (T::BITS - 1) {
+        !(((word & low) + splat_item::<T>((1 << (T::BITS - 1)) - n)) | word) & high
+    } else {
+        let low_less = !((word & low) + splat_item::<T>((1 << T::BITS) - n));
+        (!word | low_less) & high
+    }
+}
+
+/// Trait for finding items of type `T` matching some criteria, using SWAR to check several
+/// items at a time. This is the counterpart of [`ByteFinder`] for `u16` and `u32` items.
+///
+/// Finders can be combined using `|` and `&`, and negated using `!`.
+pub trait ItemFinder<T: Work>: Copy {
+    /// Get a word where the high bit of each item is set if the corresponding item in `word`
+    /// matches, and all other bits are cleared.
+    fn find_in_word(self, word: u64) -> u64;
+
+    /// Check if a single item matches.
+    fn matches(self, item: T) -> bool;
+}
+
+/// Finder for a single item value. See [`item_eq`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemEq<T>(T);
+
+/// Finder for an inclusive range of items. See [`item_in_range`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemInRange<T>(T, T);
+
+impl<T: Work> ItemFinder<T> for ItemEq<T> {
+    #[inline(always)]
+    fn find_in_word(self, word: u64) -> u64 {
+        // Exact zero detection, as for bytes.
+        let word = word ^ splat_item::<T>(self.0.into());
+        let low = !high_item_bits::<T>();
+        !(((word & low) + low) | word | low)
+    }
+
+    #[inline(always)]
+    fn matches(self, item: T) -> bool {
+        item == self.0
+    }
+}
+
+impl<T: Work> ItemFinder<T> for ItemInRange<T> {
+    #[inline(always)]
+    fn find_in_word(self, word: u64) -> u64 {
+        !item_less_than::<T>(word, self.0.into()) & item_less_than::<T>(word, self.1.into() + 1)
+    }
+
+    #[inline(always)]
+    fn matches(self, item: T) -> bool {
+        self.0 <= item && item <= self.1
+    }
+}
+
+impl<T: Work, A: ItemFinder<T>, B: ItemFinder<T>> ItemFinder<T> for Or<A, B> {
+    #[inline(always)]
+    fn find_in_word(self, word: u64) -> u64 {
+        self.0.find_in_word(word) | self.1.find_in_word(word)
+    }
+
+    #[inline(always)]
+    fn matches(self, item: T) -> bool {
+        self.0.matches(item) || self.1.matches(item)
+    }
+}
+
+impl<T: Work, A: ItemFinder<T>, B: ItemFinder<T>> ItemFinder<T> for And<A, B> {
+    #[inline(always)]
+    fn find_in_word(self, word: u64) -> u64 {
+        self.0.find_in_word(word) & self.1.find_in_word(word)
+    }
+
+    #[inline(always)]
+    fn matches(self, item: T) -> bool {
+        self.0.matches(item) && self.1.matches(item)
+    }
+}
+
+impl<T: Work, A: ItemFinder<T>> ItemFinder<T> for Not<A> {
+    #[inline(always)]
+    fn find_in_word(self, word: u64) -> u64 {
+        !self.0.find_in_word(word) & high_item_bits::<T>()
+    }
+
+    #[inline(always)]
+    fn matches(self, item: T) -> bool {
+        !self.0.matches(item)
+    }
+}
+
+/// Get a finder matching the item `item`, e.g. a UTF-16 code unit.
+#[inline]
+pub const fn item_eq<T: Work>(item: T) -> ItemEq<T> {
+    ItemEq(item)
+}
+
+/// Get a finder matching items in the inclusive range `lo..=hi`.
+#[inline]
+pub const fn item_in_range<T: Work>(lo: T, hi: T) -> ItemInRange<T> {
+    ItemInRange(lo, hi)
+}
+
+/// Get the position of the first item in `items` matched by `finder`.
+#[inline]
+pub(crate) fn get_item_pos<T: Work>(items: &[T], finder: impl ItemFinder<T>) -> Option<usize> {
+    let lanes = (64 / T::BITS) as usize;
+    let mut chunks = items.chunks_exact(lanes);
+
+    for (i, chunk) in (&mut chunks).enumerate() {
+        let found = finder.find_in_word(T::load(chunk));
+        if found != 0 {
+            return Some(i * lanes + (found.trailing_zeros() / T::BITS) as usize)
+        }
+    }
+
+    let remainder = chunks.remainder();
+    remainder.iter()
+        .position(|item| finder.matches(*item))
+        .map(|pos| items.len() - remainder.len() + pos)
+}
+
+/// Create a parser that parses until an item matched by `finder`, using a fast search that
+/// checks several items at a time, e.g. four UTF-16 code units. This is the counterpart of
+/// [`find_byte`] for slices of `u16` and `u32`.
+///
+/// ### Consuming
+/// Consumes all items before the matching item, but not the item itself.
+///
+/// ### Arguments
+/// * `finder` - the finder for the item to search for
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::findbyte::{find_item, item_eq, item_in_range};
+///
+/// let utf16: Vec<u16> = "key=välue".encode_utf16().collect();
+///
+/// let res = parse(find_item(item_eq(b'=' as u16)), utf16.as_slice());
+/// assert_eq!(res.result.map(<[u16]>::len), Some(3));
+///
+/// // Find the first non-ASCII code unit.
+/// let res = parse(find_item(item_in_range(0x80, 0xffff)), utf16.as_slice());
+/// assert_eq!(res.result.map(<[u16]>::len), Some(5));
+/// ```
+#[inline]
+pub fn find_item<'a, T: Work, S>(finder: impl ItemFinder<T>) -> impl Parser<&'a [T], &'a [T], S> {
+    create_parser!(s, {
+        let res;
+        (res, s.input) = s.input.split_at(get_item_pos(s.input, finder)?);
+        Some(res)
+    })
+}
+
+/// Create a parser that parses until an item matched by `finder`, using a fast search that
+/// checks several items at a time. This is the counterpart of [`until_byte`] for slices of
+/// `u16` and `u32`.
+///
+/// ### Consuming
+/// Consumes all items before the matching item, and the item itself.
+///
+/// ### Arguments
+/// * `finder` - the finder for the item to search for
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::findbyte::{item_eq, until_item};
+///
+/// let samples: &[u32] = &[10, 20, u32::MAX, 30];
+///
+/// let res = parse(until_item(item_eq(u32::MAX)), samples);
+/// assert_eq!(res.result, Some([10, 20].as_slice()));
+/// assert_eq!(res.state, &[30]);
+/// ```
+#[inline]
+pub fn until_item<'a, T: Work, S>(finder: impl ItemFinder<T>) -> impl Parser<&'a [T], &'a [T], S> {
+    create_parser!(s, {
+        let pos = get_item_pos(s.input, finder)?;
+        let res = &s.input[..pos];
+        s.input = &s.input[pos + 1..];
+        Some(res)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{core::parse, findbyte::*};
+
+    fn scalar_pos(bytes: &[u8], finder: impl ByteFinder) -> Option<usize> {
+        bytes.iter().position(|b| finder.matches(*b))
+    }
+
+    #[test]
+    fn swar_matches_scalar() {
+        let bytes: [u8; 256] = core::array::from_fn(|i| i as u8);
+
+        for start in 0..256 {
+            let haystack = &bytes[start..];
+            assert_eq!(get_byte_pos(haystack, digit()), scalar_pos(haystack, digit()));
+            assert_eq!(get_byte_pos(haystack, whitespace()), scalar_pos(haystack, whitespace()));
+            assert_eq!(get_byte_pos(haystack, eq(0x80) | eq(0xff)), scalar_pos(haystack, eq(0x80) | eq(0xff)));
+            assert_eq!(get_byte_pos(haystack, in_range(0x90, 0xfe)), scalar_pos(haystack, in_range(0x90, 0xfe)));
+            assert_eq!(get_byte_pos(haystack, !ident()), scalar_pos(haystack, !ident()));
+            assert_eq!(get_byte_pos(haystack, byteset(b"\x00a\x80\xff")), scalar_pos(haystack, byteset(b"\x00a\x80\xff")));
+            assert_eq!(get_byte_pos(haystack, in_range(0, 0xff)), scalar_pos(haystack, in_range(0, 0xff)));
+            assert_eq!(get_byte_pos(haystack, mask(0xc0, 0x80)), scalar_pos(haystack, mask(0xc0, 0x80)));
+            assert_eq!(get_byte_pos(haystack, alpha() & !mask(0x20, 0x20)), scalar_pos(haystack, alpha() & !mask(0x20, 0x20)));
+        }
+    }
+
+    #[test]
+    fn swar_alignment() {
+        let mut bytes = [b'a'; 64];
+        for needle in 0..64 {
+            bytes[needle] = b'x';
+            for start in 0..24 {
+                for end in start..64 {
+                    let haystack = &bytes[start..end];
+                    assert_eq!(get_byte_pos(haystack, eq(b'x')), scalar_pos(haystack, eq(b'x')), "{} {} {}", needle, start, end);
+                }
+            }
+            bytes[needle] = b'a';
+        }
+    }
+
+    #[test]
+    fn swar_items_match_scalar() {
+        fn check<T: Work + core::fmt::Debug>(items: &[T], finder: impl ItemFinder<T>) {
+            for start in 0..items.len() {
+                let haystack = &items[start..];
+                let expected = haystack.iter().position(|item| finder.matches(*item));
+                assert_eq!(get_item_pos(haystack, finder), expected, "{:?}", haystack);
+            }
+        }
+
+        let u16s: [u16; 40] = core::array::from_fn(|i| match i % 10 {
+            0 => 0,
+            1 => 0x7fff,
+            2 => 0x8000,
+            3 => 0xffff,
+            n => n as u16 * 0x1111
+        });
+        check(&u16s, item_eq(0u16));
+        check(&u16s, item_eq(0xffffu16) | item_eq(0x8000));
+        check(&u16s, item_in_range(0x8000u16, 0xfffe));
+        check(&u16s, item_in_range(0u16, 0xffff) & !item_eq(0));
+        check(&u16s, !item_in_range(0x1000u16, 0xffff));
+
+        let u32s: [u32; 20] = core::array::from_fn(|i| match i % 5 {
+            0 => 0,
+            1 => u32::MAX,
+            2 => 0x8000_0000,
+            n => n as u32 * 0x1111_1111
+        });
+        check(&u32s, item_eq(u32::MAX));
+        check(&u32s, item_in_range(1u32, 0x8000_0000));
+        check(&u32s, !item_eq(0u32));
+
+        let bytes: [u8; 256] = core::array::from_fn(|i| i as u8);
+        check(&bytes, item_in_range(0x7fu8, 0x81));
+    }
+
+    #[test]
+    fn item_parsers() {
+        let items: &[u16] = &[1, 2, 0xd800, 3];
+        let res = parse(find_item(item_in_range(0xd800, 0xdfff)), items);
+        assert_eq!(res.result, Some([1, 2].as_slice()));
+        assert_eq!(res.state, &[0xd800, 3]);
+
+        let res = parse(until_item(item_eq(2u16)), items);
+        assert_eq!(res.result, Some([1].as_slice()));
+        assert_eq!(res.state, &[0xd800, 3]);
+        assert_eq!(parse(until_item(item_eq(4u16)), items).result, None);
+    }
+
+    #[test]
+    fn seq_search() {
+        let bytes: [u8; 64] = core::array::from_fn(|i| (i % 5) as u8);
+
+        for start in 0..64 {
+            let haystack = &bytes[start..];
+            for needle in [&[3, 4][..], &[4, 0], &[4, 4], &[2, 3, 4, 0], &[1], &[]] {
+                let expected = (0..=haystack.len()).find(|i| haystack[*i..].starts_with(needle));
+                assert_eq!(get_seq_pos(haystack, needle), expected);
+            }
+        }
+
+        let res = parse(until_seq_fast(b"--"), "a-b--c");
+        assert_eq!(res.result, Some("a-b"));
+        assert_eq!(res.state, "c");
+        assert_eq!(parse(find_pair(0xa5, b'b'), "aåb").result, None);
+    }
+
+    #[test]
+    fn until_parser_from_test() {
+        use crate::{combinators::{cut, right, until_parser}, parsers::{skip, take}};
+
+        let terminator = right(skip("--"), take('>'));
+        for input in ["", "a", "-->", "a->b-->c", "å--å-->", "-- >-->", "--"] {
+            let expected = parse(until_parser(terminator), input);
+            let res = parse(until_parser_from(eq(b'-'), terminator), input);
+            assert_eq!(res.result, expected.result, "{}", input);
+            assert_eq!(res.state, expected.state, "{}", input);
+        }
+
+        // Matches within a character are ignored.
+        let res = parse(until_parser_from(eq(0xa5), take("")), "aå");
+        assert_eq!(res.result, None);
+        assert_eq!(res.state, "aå");
+
+        let res = parse(until_parser_from(eq(b'-'), cut(right(skip("--"), take('>')))), "a-- >-->");
+        assert_eq!(res.result, None);
+    }
+
+    #[test]
+    fn str_boundaries() {
+        let res = parse(take_while_byteclass(in_range(b'a', b'z') | eq(0xc3)), "aåb");
+        assert_eq!(res.result, Some("a"));
+
+        assert_eq!(parse(find_byte(eq(0xa5) | eq(b'b')), "aåb").result, Some("aå"));
+        assert_eq!(parse(until_byte(eq(b'x')), "aåb").result, None);
+
+        let res = parse(until_byte(eq(0xc3)), "aåb");
+        assert_eq!(res.result, Some("a"));
+        assert_eq!(res.state, "b");
+        assert_eq!(parse(take_while_byteclass(digit()), "").result, Some(""));
+    }
+
+    #[test]
+    fn balanced() {
+        let parens = between_balanced(b'(', b')', None);
+        let res = parse(parens, "(a(b)(c(d)))e");
+        assert_eq!(res.result, Some("a(b)(c(d))"));
+        assert_eq!(res.state, "e");
+        assert_eq!(parse(parens, "()").result, Some(""));
+        assert_eq!(parse(parens, "(\")\")").result, Some("\""));
+
+        let strings = between_balanced(b'[', b']', Some(b'\''));
+        let res = parse(strings, r"[x, '\]\'', [']'], 'å']]".as_bytes());
+        assert_eq!(res.result, Some(r"x, '\]\'', [']'], 'å'".as_bytes()));
+        assert_eq!(res.state, b"]");
+
+        for input in ["", "x()", "(", "(()", "(')", "('\\')"] {
+            let res = parse(between_balanced::<_, ()>(b'(', b')', Some(b'\'')), input);
+            assert_eq!(res.result, None);
+            assert_eq!(res.state, input);
+        }
+    }
+}
@@ -0,0 +1,463 @@
+use alloc::vec::Vec;
+
+use crate::{core::Parser, findbyte::ByteInput};
+
+/// The value of a parameter, as produced by [`value`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value<I> {
+    /// A token, e.g. `3600`.
+    Token(I),
+
+    /// The content of a quoted string, without the quotes. Quoted pairs, e.g. `\"`, are
+    /// not unescaped, see [`Value::unescaped`].
+    Quoted(I)
+}
+
+impl<'a, I: ByteInput<'a>> Value<I> {
+    /// Get the value as bytes, with any quoted pairs unescaped.
+    pub fn unescaped(self) -> Vec<u8> {
+        match self {
+            Value::Token(token) => token.as_byte_slice().to_vec(),
+            Value::Quoted(content) => {
+                let mut escaped = false;
+                content.as_byte_slice().iter().filter(|b| {
+                    escaped = !escaped && **b == b'\\';
+                    !escaped
+                }).copied().collect()
+            }
+        }
+    }
+}
+
+/// A parameter, e.g. `charset=utf-8` or `no-cache`, as produced by [`param`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param<I> {
+    pub name: I,
+    pub value: Option<Value<I>>
+}
+
+/// An element of a list with parameters, e.g. `text/html;q=0.9`, as produced by [`elements`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Element<I> {
+    pub value: I,
+    pub params: Vec<Param<I>>
+}
+
+/// A `Set-Cookie` header value, as produced by [`set_cookie`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetCookie<I> {
+    pub name: I,
+    pub value: I,
+
+    /// The attributes, e.g. `Path=/` or `Secure`.
+    pub attributes: Vec<(I, Option<I>)>
+}
+
+fn eq_ignore_case<'a>(input: impl ByteInput<'a>, name: &str) -> bool {
+    input.as_byte_slice().eq_ignore_ascii_case(name.as_bytes())
+}
+
+impl<'a, I: ByteInput<'a>> Element<I> {
+    /// Get the first parameter named `name`, ignoring ASCII case.
+    pub fn param(&self, name: &str) -> Option<&Param<I>> {
+        self.params.iter().find(|p| eq_ignore_case(p.name, name))
+    }
+
+    /// Get the quality value (the `q` parameter) in thousandths, e.g. 500 for `q=0.5`.
+    /// Returns 1000 if there is no `q` parameter, or `None` if it is invalid.
+    pub fn quality(&self) -> Option<u16> {
+        let Some(param) = self.param("q") else { return Some(1000) };
+        let Some(Value::Token(q)) = param.value else { return None };
+
+        let (int, frac) = match q.as_byte_slice() {
+            [int @ (b'0' | b'1')] => (int, &[][..]),
+            [int @ (b'0' | b'1'), b'.', frac @ ..] if frac.len() <= 3 => (int, frac),
+            _ => return None
+        };
+
+        let mut res = (int - b'0') as u16 * 1000;
+        for (digit, scale) in frac.iter().zip([100, 10, 1]) {
+            res += digit.is_ascii_digit().then_some((digit - b'0') as u16 * scale)?;
+        }
+        (res <= 1000).then_some(res)
+    }
+}
+
+impl<'a, I: ByteInput<'a>> SetCookie<I> {
+    /// Get the first attribute named `name`, ignoring ASCII case. Returns the value of the
+    /// attribute, which is `None` for attributes without a value, e.g. `Secure`.
+    pub fn attribute(&self, name: &str) -> Option<Option<I>> {
+        self.attributes.iter().find(|(n, _)| eq_ignore_case(*n, name)).map(|(_, v)| *v)
+    }
+}
+
+fn is_tchar(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+fn is_ows(byte: &u8) -> bool {
+    *byte == b' ' || *byte == b'\t'
+}
+
+/// Skip optional whitespace, i.e. spaces and tabs.
+fn ows<'a, I: ByteInput<'a>, S>() -> impl Parser<I, (), S> {
+    create_parser!(s, {
+        let len = s.input.as_byte_slice().iter().take_while(|b| is_ows(b)).count();
+        s.input = s.input.slice_from(len);
+        Some(())
+    })
+}
+
+/// Parse a delimiter byte.
+fn delimiter<'a, I: ByteInput<'a>, S>(byte: u8) -> impl Parser<I, (), S> {
+    create_parser!(s, {
+        if s.input.as_byte_slice().first() != Some(&byte) {
+            return None
+        }
+        s.input = s.input.slice_from(1);
+        Some(())
+    })
+}
+
+/// Take the bytes before `end` or the end of input, without surrounding whitespace.
+fn trimmed_until<'a, I: ByteInput<'a>, S>(end: u8) -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice();
+        let len = bytes.iter().position(|b| *b == end).unwrap_or(bytes.len());
+        let start = bytes[..len].iter().take_while(|b| is_ows(b)).count();
+        let trimmed_len = len - bytes[start..len].iter().rev().take_while(|b| is_ows(b)).count();
+
+        let res = s.input.slice_to(trimmed_len).slice_from(start);
+        s.input = s.input.slice_from(len);
+        Some(res)
+    })
+}
+
+/// Create a parser for a token as specified by RFC 7230, e.g. `max-age`.
+///
+/// ### Consuming
+/// Consumes the token, on successful parse.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::http::token;
+///
+/// let res = parse(token(), "no-cache, private");
+/// assert_eq!(res.result, Some("no-cache"));
+/// assert_eq!(res.state, ", private");
+/// ```
+#[inline]
+pub fn token<'a, I: ByteInput<'a>, S>() -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        let len = s.input.as_byte_slice().iter().take_while(|b| is_tchar(**b)).count();
+        if len == 0 {
+            return None
+        }
+        let res = s.input.slice_to(len);
+        s.input = s.input.slice_from(len);
+        Some(res)
+    })
+}
+
+/// Create a parser for a quoted string as specified by RFC 7230, e.g. `"a \"b\""`. Returns
+/// the content of the string without the quotes, and with any quoted pairs left as-is.
+///
+/// ### Consuming
+/// Consumes the quoted string, on successful parse. The parser fails if the string is not
+/// terminated.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::http::quoted_string;
+///
+/// let res = parse(quoted_string(), r#""a \"b\"";"#);
+/// assert_eq!(res.result, Some(r#"a \"b\""#));
+/// assert_eq!(res.state, ";");
+/// ```
+#[inline]
+pub fn quoted_string<'a, I: ByteInput<'a>, S>() -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice();
+        if bytes.first() != Some(&b'"') {
+            return None
+        }
+
+        let mut i = 1;
+        loop {
+            match bytes.get(i)? {
+                b'"' => break,
+                b'\\' => i += 2,
+                _ => i += 1
+            }
+        }
+
+        let res = s.input.slice_to(i).slice_from(1);
+        s.input = s.input.slice_from(i + 1);
+        Some(res)
+    })
+}
+
+/// Create a parser for a parameter value, i.e. a token or a quoted string.
+///
+/// ### Consuming
+/// Consumes the value, on successful parse.
+#[inline]
+pub fn value<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Value<I>, S> {
+    create_parser!(s, {
+        match token()(s) {
+            Some(token) => Some(Value::Token(token)),
+            None => quoted_string()(s).map(Value::Quoted)
+        }
+    })
+}
+
+/// Create a parser for a parameter, i.e. a token optionally followed by `=` and a
+/// [`value`], e.g. `max-age=3600` or `no-store`.
+///
+/// ### Consuming
+/// Consumes the parameter, on successful parse. If the value is invalid, only the name is
+/// consumed.
+#[inline]
+pub fn param<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Param<I>, S> {
+    create_parser!(s, {
+        let name = token()(s)?;
+        let before_value = s.input;
+        let value = delimiter(b'=')(s).and_then(|_| value()(s));
+        if value.is_none() {
+            s.input = before_value;
+        }
+        Some(Param { name, value })
+    })
+}
+
+/// Parse a comma-separated list, as specified by RFC 7230, where empty elements are allowed.
+fn list<'a, I: ByteInput<'a>, O, S>(element: impl Parser<I, O, S>) -> impl Parser<I, Vec<O>, S> {
+    create_parser!(s, {
+        let mut elements = Vec::new();
+        loop {
+            ows()(s);
+            if delimiter(b',')(s).is_some() {
+                continue
+            }
+
+            let Some(element) = element(s) else { return Some(elements) };
+            elements.push(element);
+
+            let before_separator = s.input;
+            ows()(s);
+            if delimiter(b',')(s).is_none() {
+                s.input = before_separator;
+                return Some(elements)
+            }
+        }
+    })
+}
+
+/// Create a parser for a comma-separated list of parameters, e.g. the value of a
+/// `Cache-Control` header.
+///
+/// ### Consuming
+/// Consumes the list, on successful parse. The parser always succeeds.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::http::{directives, Param, Value};
+///
+/// let res = parse(directives(), r#"no-cache, max-age=60, private="Set-Cookie""#);
+/// assert_eq!(res.result, Some(vec![
+///     Param { name: "no-cache", value: None },
+///     Param { name: "max-age", value: Some(Value::Token("60")) },
+///     Param { name: "private", value: Some(Value::Quoted("Set-Cookie")) },
+/// ]));
+/// ```
+#[inline]
+pub fn directives<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Vec<Param<I>>, S> {
+    list(param())
+}
+
+/// Create a parser for a value with semicolon-separated parameters, e.g. the value of a
+/// `Content-Type` header. The value may be a media type, i.e. contain a `/`.
+///
+/// ### Consuming
+/// Consumes the element, on successful parse.
+#[inline]
+pub fn element<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Element<I>, S> {
+    create_parser!(s, {
+        let len = s.input.as_byte_slice().iter().take_while(|b| is_tchar(**b) || **b == b'/').count();
+        if len == 0 {
+            return None
+        }
+        let value = s.input.slice_to(len);
+        s.input = s.input.slice_from(len);
+
+        let mut params = Vec::new();
+        loop {
+            let before_param = s.input;
+            ows()(s);
+            let Some(param) = delimiter(b';')(s).and_then(|_| { ows()(s); param()(s) }) else {
+                s.input = before_param;
+                return Some(Element { value, params })
+            };
+            params.push(param);
+        }
+    })
+}
+
+/// Create a parser for a comma-separated list of values with parameters, e.g. the value of
+/// an `Accept` header. See [`element`].
+///
+/// ### Consuming
+/// Consumes the list, on successful parse. The parser always succeeds.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::http::elements;
+///
+/// let res = parse(elements(), "text/html, application/json;q=0.9;charset=\"utf-8\", */*;q=0");
+/// let accept = res.result.unwrap();
+///
+/// assert_eq!(accept.iter().map(|e| e.value).collect::<Vec<_>>(), ["text/html", "application/json", "*/*"]);
+/// assert_eq!(accept.iter().map(|e| e.quality()).collect::<Vec<_>>(), [Some(1000), Some(900), Some(0)]);
+/// assert_eq!(accept[1].param("Charset").unwrap().value.unwrap().unescaped(), b"utf-8");
+/// ```
+#[inline]
+pub fn elements<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Vec<Element<I>>, S> {
+    list(element())
+}
+
+/// Parse a cookie pair, i.e. `name=value`. Surrounding quotes are removed from the value.
+fn cookie_pair<'a, I: ByteInput<'a>, S>() -> impl Parser<I, (I, I), S> {
+    create_parser!(s, {
+        ows()(s);
+        let name = token()(s)?;
+        delimiter(b'=')(s)?;
+        let value = trimmed_until(b';')(s)?;
+        let bytes = value.as_byte_slice();
+        let value = match bytes {
+            [b'"', .., b'"'] => value.slice_to(bytes.len() - 1).slice_from(1),
+            _ => value
+        };
+        Some((name, value))
+    })
+}
+
+/// Create a parser for the cookies of a `Cookie` header, e.g. `id=a3fWa; theme=dark`.
+///
+/// ### Consuming
+/// Consumes the cookies, on successful parse. The parser fails if a cookie is invalid.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::http::cookies;
+///
+/// let res = parse(cookies(), "id=a3fWa; theme=\"dark\"");
+/// assert_eq!(res.result, Some(vec![("id", "a3fWa"), ("theme", "dark")]));
+/// ```
+#[inline]
+pub fn cookies<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Vec<(I, I)>, S> {
+    create_parser!(s, {
+        let mut cookies = Vec::new();
+        loop {
+            cookies.push(cookie_pair()(s)?);
+            if delimiter(b';')(s).is_none() {
+                return Some(cookies)
+            }
+        }
+    })
+}
+
+/// Create a parser for the value of a `Set-Cookie` header, as specified by RFC 6265. The
+/// values of the attributes are not parsed, since e.g. `Expires` contains a date.
+///
+/// ### Consuming
+/// Consumes the whole input, on successful parse. The parser fails if the cookie is
+/// invalid.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::http::set_cookie;
+///
+/// let res = parse(set_cookie(), "id=a3fWa; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Secure");
+/// let cookie = res.result.unwrap();
+///
+/// assert_eq!((cookie.name, cookie.value), ("id", "a3fWa"));
+/// assert_eq!(cookie.attribute("expires"), Some(Some("Wed, 21 Oct 2015 07:28:00 GMT")));
+/// assert_eq!(cookie.attribute("Secure"), Some(None));
+/// assert_eq!(cookie.attribute("HttpOnly"), None);
+/// ```
+#[inline]
+pub fn set_cookie<'a, I: ByteInput<'a>, S>() -> impl Parser<I, SetCookie<I>, S> {
+    create_parser!(s, {
+        let (name, value) = cookie_pair()(s)?;
+        let mut attributes = Vec::new();
+
+        while delimiter(b';')(s).is_some() {
+            let attribute = trimmed_until(b';')(s)?;
+            let bytes = attribute.as_byte_slice();
+            match bytes.iter().position(|b| *b == b'=') {
+                None if bytes.is_empty() => {},
+                None => attributes.push((attribute, None)),
+                Some(pos) => {
+                    let name = attribute.slice_to(pos);
+                    let value = attribute.slice_from(pos + 1);
+                    let trimmed_name = bytes[..pos].iter().rev().take_while(|b| is_ows(b)).count();
+                    let trimmed_value = bytes[pos + 1..].iter().take_while(|b| is_ows(b)).count();
+                    attributes.push((name.slice_to(pos - trimmed_name), Some(value.slice_from(trimmed_value))));
+                }
+            }
+        }
+
+        Some(SetCookie { name, value, attributes })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::{core::parse, http::{cookies, directives, elements, param, quoted_string, set_cookie, Param, Value}};
+
+    #[test]
+    fn params() {
+        assert_eq!(parse(quoted_string(), r#""\\""#).result, Some(r#"\\"#));
+        assert_eq!(parse(quoted_string(), r#""\""#).result, None);
+        assert_eq!(Value::Quoted(r#"a\"b\\"#).unescaped(), br#"a"b\"#);
+
+        let res = parse(param(), "a=,");
+        assert_eq!(res.result, Some(Param { name: "a", value: None }));
+        assert_eq!(res.state, "=,");
+
+        let res = parse(directives(), b" , a ,, b=\"x\" ;".as_slice());
+        assert_eq!(res.result, Some(vec![Param { name: &b"a"[..], value: None },
+                                         Param { name: b"b", value: Some(Value::Quoted(b"x")) }]));
+        assert_eq!(res.state, b" ;");
+    }
+
+    #[test]
+    fn lists() {
+        let res = parse(elements(), "a/b ; x=1 ;y, c;q=1.000;q=0, d;q=0.1234, e;q=1.1, f;q=\"1\"");
+        let list = res.result.unwrap();
+        assert_eq!(res.state, "");
+        assert_eq!(list[0].params, [Param { name: "x", value: Some(Value::Token("1")) },
+                                        Param { name: "y", value: None }]);
+        assert_eq!(list.iter().map(|e| e.quality()).collect::<vec::Vec<_>>(),
+                   [Some(1000), Some(1000), None, None, None]);
+
+        let res = parse(elements(), "a; ;b");
+        assert_eq!(res.result.unwrap()[0].params, []);
+        assert_eq!(res.state, "; ;b");
+
+        assert_eq!(parse(cookies(), "a=b;c=").result, Some(vec![("a", "b"), ("c", "")]));
+        assert_eq!(parse(cookies(), "a=b; c").result, None);
+
+        let cookie = parse(set_cookie(), "a=\"b\";; Path = / ; Max-Age=0=0;").result.unwrap();
+        assert_eq!(cookie.value, "b");
+        assert_eq!(cookie.attributes, [("Path", Some("/")), ("Max-Age", Some("0=0"))]);
+    }
+}
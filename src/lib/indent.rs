@@ -0,0 +1,141 @@
+use core::cmp::Ordering;
+
+use crate::{charlike::CharLike, combinators::{attempt, count_consumed, peek, with_state_value}, core::{Parser, ParserExt}, parsers::item_while, slicelike::SliceLike};
+
+/// Create a parser that parses the indentation at the current position, i.e. any number of
+/// spaces and tabs, and returns its width. Each space or tab counts as one column.
+///
+/// This parser should be used at the start of a line, and it never fails.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::indent::indentation;
+///
+/// let res = parse(indentation(), "    x");
+/// assert_eq!(res.result, Some(4));
+/// assert_eq!(res.state, "x");
+/// ```
+#[inline]
+pub fn indentation<A: CharLike, I: SliceLike<RefItem = A, Idx = usize>, S>() -> impl Parser<I, usize, S> {
+    count_consumed(item_while(|c: A| matches!(c.as_char(), ' ' | '\t'))).map(|(n, _)| n)
+}
+
+/// Create a parser that parses the indentation at the current position, and succeeds only if
+/// its width compares to `level` as `cmp`. Returns the width of the indentation.
+///
+/// Nothing is consumed on failure, so that the indentation can be parsed again, e.g. by an
+/// enclosing block.
+///
+/// ### Arguments
+/// * `cmp` - the required ordering of the indentation compared to `level`
+/// * `level` - the reference indentation level
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::indent::indent_guard;
+/// use std::cmp::Ordering;
+///
+/// let p = indent_guard(Ordering::Greater, 2);
+/// assert_eq!(parse(p, "    x").result, Some(4));
+/// assert_eq!(parse(p, "  x").state, "  x");
+/// ```
+#[inline]
+pub fn indent_guard<A: CharLike, I: SliceLike<RefItem = A, Idx = usize>, S>(cmp: Ordering,
+                                                                           level: usize
+) -> impl Parser<I, usize, S> {
+    attempt(indentation().filter(move |n: &usize| n.cmp(&level) == cmp))
+}
+
+/// Create a parser that parses `p` if it is preceded by an indentation equal to the current
+/// indentation level, as given by `field` in the user state.
+///
+/// ### Arguments
+/// * `field` - a function selecting the current indentation level in the user state.
+/// * `p` - the parser
+///
+/// ### Example
+/// See [`indented_block`]
+#[inline]
+pub fn same_indent<A: CharLike, I: SliceLike<RefItem = A, Idx = usize>, O, S>(
+    field: impl FnOnce(&mut S) -> &mut usize + Copy,
+    p: impl Parser<I, O, S>
+) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let level = *field(s.user_state);
+        indent_guard(Ordering::Equal, level)(s)?;
+        p(s)
+    })
+}
+
+/// Create a parser for an indented block. The block must be indented further than the current
+/// indentation level, as given by `field` in the user state. The indentation of the first line
+/// in the block is used as the current level while parsing `p`, after which the previous level
+/// is restored.
+///
+/// The indentation of the first line is not consumed, so `p` will typically consist of lines
+/// parsed using [`same_indent`].
+///
+/// ### Arguments
+/// * `field` - a function selecting the current indentation level in the user state.
+/// * `p` - the parser for the contents of the block.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{fold, no_separator, not_empty, right};
+/// use anpa::indent::{indented_block, same_indent};
+/// use anpa::parsers::{item_while, skip};
+/// use anpa::defer_parser;
+///
+/// // Count the number of nodes in an indented tree
+/// fn node<'a>() -> impl StrParser<'a, usize, usize> {
+///     let name = not_empty(item_while(|c: char| c.is_alphanumeric()));
+///     let line = right(name, skip('\n'));
+///     let children = indented_block(|level| level,
+///                                   fold(defer_parser!(same_indent(|level| level, node())),
+///                                        || 0, |n, c| *n += c, true, no_separator()));
+///     right(line, children.or_value(0)).map(|children| children + 1)
+/// }
+///
+/// let input = "a\n  b\n    c\n  d\ne\n";
+///
+/// assert_eq!(parse_state(node(), input, &mut 0).result, Some(4));
+/// ```
+#[inline]
+pub fn indented_block<A: CharLike, I: SliceLike<RefItem = A, Idx = usize>, O, S>(
+    field: impl FnOnce(&mut S) -> &mut usize + Copy,
+    p: impl Parser<I, O, S>
+) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let level = *field(s.user_state);
+        let new_level = peek(indent_guard(Ordering::Greater, level))(s)?;
+        with_state_value(field, new_level, p)(s)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cmp::Ordering;
+
+    use crate::{core::{parse, parse_state}, indent::{indent_guard, indented_block, same_indent}, parsers::take};
+
+    #[test]
+    fn guard() {
+        let p = indent_guard(Ordering::Less, 2);
+        assert_eq!(parse(p, " x").result, Some(1));
+        assert_eq!(parse(p, "x").result, Some(0));
+        assert_eq!(parse(p, "\t\tx").result, None);
+    }
+
+    #[test]
+    fn block() {
+        let p = indented_block(|l: &mut usize| l, same_indent(|l| l, take("x")));
+
+        let mut level = 2;
+        assert_eq!(parse_state(p, "    x", &mut level).result, Some("x"));
+        assert_eq!(level, 2);
+        assert_eq!(parse_state(p, "  x", &mut level).result, None);
+    }
+}
@@ -0,0 +1,155 @@
+use alloc::{collections::BTreeMap, rc::Rc, vec::Vec};
+
+use crate::{core::Parser, slicelike::SliceLike};
+
+/// A cheap identifier of a string interned by an [`Interner`]. Symbols from the same
+/// interner are equal if and only if their strings are equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// The index of the symbol, i.e. the number of strings interned before it.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A string interner that can be used as, or be part of, the user state, storing each
+/// distinct string once and identifying it by a [`Symbol`].
+///
+/// The parsers in this module require the user state to implement `AsMut<Interner>`,
+/// which is implemented for `Interner` itself. For a custom user state, implement `AsMut`
+/// to return its interner.
+///
+/// Symbols are `Copy` and `Ord`, so they can also be used as the keys of a
+/// [`SymbolTable`](crate::symbols::SymbolTable).
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{many_to_vec, separator};
+/// use anpa::interner::{intern, Interner};
+/// use anpa::parsers::{item_while, skip};
+///
+/// let word = intern(item_while(|c: char| c.is_alphabetic()));
+/// let p = many_to_vec(word, true, separator(skip(' '), false));
+///
+/// let mut interner = Interner::new();
+/// let words = parse_state(p, "to be or not to be", &mut interner).result.unwrap();
+///
+/// assert_eq!(interner.len(), 4);
+/// assert_eq!(words[0], words[4]);
+/// assert_eq!(interner.resolve(words[1]), "be");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    symbols: BTreeMap<Rc<str>, Symbol>,
+    strings: Vec<Rc<str>>
+}
+
+impl Interner {
+    /// Create a new, empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the symbol of `text`, interning it if it hasn't been interned before.
+    ///
+    /// ### Panics
+    /// Panics if more than `u32::MAX` strings are interned.
+    pub fn intern(&mut self, text: &str) -> Symbol {
+        if let Some(symbol) = self.get(text) {
+            return symbol
+        }
+
+        assert!(self.strings.len() <= u32::MAX as usize, "too many interned strings");
+        let symbol = Symbol(self.strings.len() as u32);
+        let text: Rc<str> = text.into();
+        self.strings.push(text.clone());
+        self.symbols.insert(text, symbol);
+        symbol
+    }
+
+    /// Get the symbol of `text`, if it has been interned.
+    pub fn get(&self, text: &str) -> Option<Symbol> {
+        self.symbols.get(text).copied()
+    }
+
+    /// Get the string of `symbol`.
+    ///
+    /// ### Panics
+    /// Panics if `symbol` is not from this interner.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.index()]
+    }
+
+    /// The number of interned strings.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Check if no strings have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Iterate over all interned strings with their symbols, in the order they were interned.
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> {
+        self.strings.iter().enumerate().map(|(i, s)| (Symbol(i as u32), &**s))
+    }
+}
+
+impl AsMut<Interner> for Interner {
+    fn as_mut(&mut self) -> &mut Interner {
+        self
+    }
+}
+
+/// Transform a parser with a string result to a parser that interns the result in the
+/// [`Interner`] of the user state, and returns its symbol instead.
+///
+/// ### Arguments
+/// * `p` - the parser
+#[inline]
+pub fn intern<I: SliceLike, O: AsRef<str>, S: AsMut<Interner>>(p: impl Parser<I, O, S>) -> impl Parser<I, Symbol, S> {
+    create_parser!(s, {
+        let res = p(s)?;
+        Some(s.user_state.as_mut().intern(res.as_ref()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec::Vec};
+
+    use crate::{combinators::{many_to_vec, right, separator}, core::{parse_all_state, parse_state, ParserExt}, interner::{intern, Interner, Symbol}, parsers::{item_while, skip}, symbols::{define, resolve, SymbolTable}};
+
+    #[test]
+    fn interner() {
+        let mut interner = Interner::new();
+        let a = interner.intern("a");
+        assert_eq!((interner.intern(&String::from("a")), interner.intern("b").index()), (a, 1));
+        assert_eq!((interner.get("a"), interner.get("c")), (Some(a), None));
+        assert!(interner.iter().eq([(a, "a"), (Symbol(1), "b")]));
+        assert!(!interner.is_empty());
+
+        struct State {
+            interner: Interner,
+            symbols: SymbolTable<Symbol, usize>
+        }
+        impl AsMut<Interner> for State {
+            fn as_mut(&mut self) -> &mut Interner { &mut self.interner }
+        }
+        impl AsMut<SymbolTable<Symbol, usize>> for State {
+            fn as_mut(&mut self) -> &mut SymbolTable<Symbol, usize> { &mut self.symbols }
+        }
+
+        let name = intern(item_while(|c: char| c.is_alphabetic()));
+        let p = right(define(name.map(|n| (n, n.index()))), many_to_vec(right(skip(' '), resolve(name)), true, separator(skip(','), false)));
+        let mut state = State { interner, symbols: SymbolTable::new() };
+        let res: Vec<usize> = parse_state(p, "c c, c", &mut state).result.unwrap();
+        assert_eq!(res, [2, 2]);
+        assert_eq!(state.interner.len(), 3);
+        assert_eq!(parse_all_state(resolve(name), "b", &mut state).unwrap_err().expected, Some("defined name"));
+    }
+}
@@ -0,0 +1,185 @@
+#[cfg(feature = "nom")]
+use nom::error::{ErrorKind, ParseError};
+
+#[cfg(feature = "winnow")]
+use winnow::{error::{ErrMode, ParserError}, stream::Stream};
+
+use crate::{core::{AnpaState, Parser}, slicelike::SliceLike};
+
+/// Apply `p` to `input`, returning the remaining input, the result, and whether the parse
+/// was cut.
+#[inline]
+fn apply<I: SliceLike, O>(p: impl Parser<I, O>, input: I) -> (I, Option<O>, bool) {
+    let mut user_state = ();
+    let mut state = AnpaState::new(input, &mut user_state);
+    let res = p(&mut state);
+    (state.input, res, state.is_cut())
+}
+
+#[cfg(feature = "nom")]
+/// Convert a parser to a `nom` parser, e.g. to use it within a `nom` grammar.
+///
+/// If `p` fails, a `nom::Err::Error` of kind `ErrorKind::Fail` at the start of the input is
+/// returned, or a `nom::Err::Failure` if `p` was [`cut`](crate::combinators::cut).
+///
+/// ### Arguments
+/// * `p` - the parser
+///
+/// ### Example
+/// ```
+/// use anpa::interop::to_nom;
+/// use anpa::number::integer;
+/// use nom::{bytes::complete::tag, sequence::preceded, IResult, Parser};
+///
+/// fn version(input: &str) -> IResult<&str, u32> {
+///     preceded(tag("v"), to_nom(integer())).parse(input)
+/// }
+///
+/// assert_eq!(version("v12 rest"), Ok((" rest", 12)));
+/// assert!(version("vx").is_err());
+/// ```
+#[inline]
+pub fn to_nom<I: SliceLike, O, E: ParseError<I>>(p: impl Parser<I, O>) -> impl FnMut(I) -> nom::IResult<I, O, E> + Copy {
+    move |input| match apply(p, input) {
+        (rest, Some(res), _) => Ok((rest, res)),
+        (_, None, false) => Err(nom::Err::Error(E::from_error_kind(input, ErrorKind::Fail))),
+        (_, None, true) => Err(nom::Err::Failure(E::from_error_kind(input, ErrorKind::Fail)))
+    }
+}
+
+#[cfg(feature = "nom")]
+/// Convert a `nom` parser to a parser, e.g. to reuse parts of an existing `nom` grammar.
+///
+/// The `nom` parser is run as a complete parser, i.e. `nom::Err::Incomplete` is never
+/// expected. A `nom::Err::Failure` is treated as if the parser was
+/// [`cut`](crate::combinators::cut).
+///
+/// ### Arguments
+/// * `p` - the `nom` parser. Must be `Copy`, e.g. a function or a closure.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::right;
+/// use anpa::interop::from_nom;
+/// use anpa::parsers::skip;
+///
+/// let hex = from_nom(nom::character::complete::hex_digit1::<&str, nom::error::Error<&str>>);
+/// let p = right(skip("0x"), hex);
+///
+/// assert_eq!(parse(p, "0xff!").result, Some("ff"));
+/// assert_eq!(parse(p, "0xg").result, None);
+/// ```
+#[inline]
+pub fn from_nom<I: SliceLike, O, S>(p: impl nom::Parser<I, Output = O> + Copy) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let mut p = p;
+        match p.parse_complete(s.input) {
+            Ok((rest, res)) => {
+                s.input = rest;
+                Some(res)
+            },
+            Err(nom::Err::Failure(_)) => {
+                s.cut = true;
+                None
+            },
+            Err(_) => None
+        }
+    })
+}
+
+#[cfg(feature = "winnow")]
+/// Convert a parser to a `winnow` parser, e.g. to use it within a `winnow` grammar.
+///
+/// If `p` fails, an `ErrMode::Backtrack` at the start of the input is returned, or an
+/// `ErrMode::Cut` if `p` was [`cut`](crate::combinators::cut).
+///
+/// ### Arguments
+/// * `p` - the parser
+///
+/// ### Example
+/// ```
+/// use anpa::interop::to_winnow;
+/// use anpa::number::integer;
+/// use winnow::{combinator::preceded, ModalResult, Parser};
+///
+/// fn version(input: &mut &str) -> ModalResult<u32> {
+///     preceded("v", to_winnow(integer())).parse_next(input)
+/// }
+///
+/// assert_eq!(version.parse_peek("v12 rest"), Ok((" rest", 12)));
+/// assert!(version.parse_peek("vx").is_err());
+/// ```
+#[inline]
+pub fn to_winnow<I: SliceLike + Stream, O, E: ParserError<I>>(p: impl Parser<I, O>) -> impl FnMut(&mut I) -> winnow::ModalResult<O, E> + Copy {
+    move |input| match apply(p, *input) {
+        (rest, Some(res), _) => {
+            *input = rest;
+            Ok(res)
+        },
+        (_, None, false) => Err(ErrMode::Backtrack(E::from_input(input))),
+        (_, None, true) => Err(ErrMode::Cut(E::from_input(input)))
+    }
+}
+
+#[cfg(feature = "winnow")]
+/// Convert a `winnow` parser to a parser, e.g. to reuse parts of an existing `winnow`
+/// grammar.
+///
+/// An error that isn't a backtrack, e.g. `ErrMode::Cut`, is treated as if the parser was
+/// [`cut`](crate::combinators::cut).
+///
+/// ### Arguments
+/// * `p` - the `winnow` parser. Must be `Copy`, e.g. a function or a closure.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::right;
+/// use anpa::interop::from_winnow;
+/// use anpa::parsers::skip;
+///
+/// let hex = from_winnow(winnow::ascii::hex_digit1::<&str, winnow::error::ContextError>);
+/// let p = right(skip("0x"), hex);
+///
+/// assert_eq!(parse(p, "0xff!").result, Some("ff"));
+/// assert_eq!(parse(p, "0xg").result, None);
+/// ```
+#[inline]
+pub fn from_winnow<I: SliceLike + Stream, O, E: ParserError<I>, S>(p: impl winnow::Parser<I, O, E> + Copy) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let mut p = p;
+        let mut input = s.input;
+        let res = p.parse_next(&mut input);
+        s.input = input;
+        match res {
+            Ok(res) => Some(res),
+            Err(e) => {
+                s.cut |= !e.is_backtrack();
+                None
+            }
+        }
+    })
+}
+
+#[cfg(all(test, feature = "nom", feature = "winnow"))]
+mod tests {
+    use crate::{combinators::{cut, or, right}, core::{parse, ParserExt}, interop::{from_nom, from_winnow, to_nom, to_winnow}, number::integer, parsers::skip};
+
+    #[test]
+    fn round_trip() {
+        let number = right(skip('#'), cut(integer::<u32, _, _, _>()).map(|n| n * 2));
+        let nom = to_nom::<_, _, nom::error::Error<&str>>(number);
+        let winnow = to_winnow::<_, _, winnow::error::ContextError>(number);
+
+        let p = or(from_nom(nom), from_winnow(winnow).map(|n| n + 1));
+        assert_eq!(parse(p, "#21").result, Some(42));
+        assert_eq!(parse(from_winnow(winnow), "#21").result, Some(42));
+        assert_eq!(parse(p, "21").result, None);
+
+        let cut_number = right(skip('#'), right(skip('-'), integer::<u32, _, _, _>()).cut());
+        assert_eq!(parse(or(from_nom(to_nom::<_, _, nom::error::Error<&str>>(cut_number)), skip("#-x").to(0)), "#-x").result, None);
+        assert_eq!(parse(or(from_winnow(to_winnow::<_, _, winnow::error::ContextError>(cut_number)), skip("#-x").to(0)), "#-x").result, None);
+        assert_eq!(parse(or(from_nom(to_nom::<_, _, nom::error::Error<&str>>(cut_number)), skip("#x").to(0)), "#x").result, Some(0));
+    }
+}
@@ -1,54 +1,397 @@
-use std::{collections::BTreeMap, vec::Vec};
+#[cfg(feature = "serde")]
+use alloc::string::String;
+use alloc::{collections::BTreeMap, vec::Vec};
 
-use crate::{combinators::*, core::{ParserExt, ParserInto, StrParser}, number::float, parsers::*, whitespace::AsciiWhitespace};
+use crate::{combinators::*, core::{parse, AnpaState, ParserExt, ParserInto, StrParser}, findbyte::{between_balanced, eq, find}, literal::{string_literal, StringSyntax}, number::convert_number, whitespace::AsciiWhitespace};
+#[cfg(feature = "serde")]
+use crate::literal::unescape;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum JsonValue<StringType> {
     Null,
     Bool(bool),
     Str(StringType),
     Num(f64),
     Dic(BTreeMap<StringType, JsonValue<StringType>>),
-    Arr(Vec<JsonValue<StringType>>)
+    Arr(Vec<JsonValue<StringType>>),
+
+    /// An unparsed object, including the braces, as produced by [`lazy_value_parser`].
+    RawObject(StringType),
+
+    /// An unparsed array, including the brackets, as produced by [`lazy_value_parser`].
+    RawArray(StringType)
+}
+
+/// Drop nested objects and arrays iteratively, so that dropping a deeply nested value, e.g.
+/// as parsed by [`value_parser`], doesn't overflow the stack.
+impl<StringType> Drop for JsonValue<StringType> {
+    fn drop(&mut self) {
+        fn take_children<T>(value: &mut JsonValue<T>, stack: &mut Vec<JsonValue<T>>) {
+            match value {
+                JsonValue::Dic(map) => stack.extend(core::mem::take(map).into_values()),
+                JsonValue::Arr(vec) => stack.append(vec),
+                _ => {}
+            }
+        }
+
+        let mut stack = Vec::new();
+        take_children(self, &mut stack);
+        while let Some(mut value) = stack.pop() {
+            take_children(&mut value, &mut stack);
+        }
+    }
+}
+
+impl<'a> JsonValue<&'a str> {
+    /// Parse a [`RawObject`](JsonValue::RawObject) or [`RawArray`](JsonValue::RawArray)
+    /// using [`lazy_value_parser`], i.e. with its nested objects and arrays left unparsed.
+    ///
+    /// Returns `None` if the value is not raw, or if it is invalid.
+    ///
+    /// ### Example
+    /// ```
+    /// use anpa::core::parse;
+    /// use anpa::json::{lazy_value_parser, JsonValue};
+    ///
+    /// let res = parse(lazy_value_parser(), r#"{"a": {"b": [1, 2]}, "c": [3]}"#).result;
+    /// let Some(JsonValue::Dic(map)) = &res else { panic!() };
+    /// assert!(matches!(map["c"], JsonValue::RawArray("[3]")));
+    ///
+    /// let Some(JsonValue::Dic(a)) = &map["a"].parse_value() else { panic!() };
+    /// assert!(matches!(a["b"], JsonValue::RawArray("[1, 2]")));
+    /// ```
+    pub fn parse_value(&self) -> Option<JsonValue<&'a str>> {
+        match self {
+            JsonValue::RawObject(raw) | JsonValue::RawArray(raw) => {
+                let res = parse(lazy_value_parser(), *raw);
+                res.state.is_empty().then_some(res.result?)
+            }
+            _ => None
+        }
+    }
+}
+
+/// Serialize the value, with the strings unescaped. Raw objects and arrays are parsed using
+/// [`value_parser`], so the serialization fails if they are invalid.
+#[cfg(feature = "serde")]
+impl<T: AsRef<str>> serde::Serialize for JsonValue<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            JsonValue::Null => serializer.serialize_unit(),
+            JsonValue::Bool(b) => serializer.serialize_bool(*b),
+            JsonValue::Str(s) => Escaped(s.as_ref()).serialize(serializer),
+            JsonValue::Num(n) => serializer.serialize_f64(*n),
+            JsonValue::Dic(map) => serializer.collect_map(map.iter().map(|(k, v)| (Escaped(k.as_ref()), v))),
+            JsonValue::Arr(vec) => serializer.collect_seq(vec),
+            JsonValue::RawObject(raw) | JsonValue::RawArray(raw) => {
+                let res = parse(value_parser::<&str>(), raw.as_ref());
+                match res.result {
+                    Some(value) if res.state.is_empty() => value.serialize(serializer),
+                    _ => Err(serde::ser::Error::custom("invalid raw JSON value"))
+                }
+            }
+        }
+    }
+}
+
+/// The escaped content of a JSON string, serialized unescaped.
+#[cfg(feature = "serde")]
+struct Escaped<'a>(&'a str);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Escaped<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !self.0.contains('\\') {
+            return serializer.serialize_str(self.0)
+        }
+        let unescaped = unescape(self.0.as_bytes(), &StringSyntax::JSON)
+            .ok_or_else(|| serde::ser::Error::custom("invalid escape in JSON string"))?;
+        serializer.serialize_str(&unescaped)
+    }
 }
 
-fn eat<'a, O>(p: impl StrParser<'a, O>) -> impl StrParser<'a, O> {
+/// Escape `text` as the content of a JSON string, i.e. the inverse of [`unescape`].
+#[cfg(feature = "serde")]
+fn escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            '\u{8}' => res.push_str("\\b"),
+            '\u{c}' => res.push_str("\\f"),
+            c if c < ' ' => {
+                let hex = |n: u32| char::from_digit(n, 16).unwrap();
+                res.extend(['\\', 'u', '0', '0', hex(c as u32 >> 4), hex(c as u32 & 0xf)]);
+            }
+            c => res.push(c)
+        }
+    }
+    res
+}
+
+/// Deserialize a value from any self-describing format, e.g. to convert it to JSON. The
+/// strings are escaped, so that they are stored the same way as when parsed by
+/// [`value_parser`]. Integers are converted to `f64`.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for JsonValue<String> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct ValueVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for ValueVisitor {
+    type Value = JsonValue<String>;
+
+    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(JsonValue::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(JsonValue::Null)
+    }
+
+    fn visit_some<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        serde::Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(JsonValue::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(JsonValue::Num(v as f64))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(JsonValue::Num(v as f64))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(JsonValue::Num(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(JsonValue::Str(escape(v)))
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut vec = Vec::new();
+        while let Some(value) = seq.next_element()? {
+            vec.push(value);
+        }
+        Ok(JsonValue::Arr(vec))
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut dic = BTreeMap::new();
+        while let Some((key, value)) = map.next_entry::<String, _>()? {
+            dic.insert(escape(&key), value);
+        }
+        Ok(JsonValue::Dic(dic))
+    }
+}
+
+fn eat<'a, O, S>(p: impl StrParser<'a, O, S>) -> impl StrParser<'a, O, S> {
     // For unknown reasons, this gives much better performance than `skip_ascii_whitespace()`.
     // Possibly a random optimization quirk, since it ideally shouldn't happen.
     right(skip!(AsciiWhitespace()), p)
 }
 
-fn string_parser<'a, T: From<&'a str>>() -> impl StrParser<'a, T> {
-    let unicode = right(skip!('u'), times(4, item_if(|c: char| c.is_ascii_hexdigit())));
-    let escaped = right(skip!('\\'), or_diff(unicode, item_if(|c: char| "\"\\/bfnrt".contains(c))));
-    let valid_char = item_if(|c: char| c != '"' && c != '\\' && !c.is_control());
-    let not_end = or_diff(valid_char, escaped);
-    middle(skip!('"'), many(not_end, true, no_separator()), skip!('"')).into_type()
+fn string_parser<'a, T: From<&'a str>, S>() -> impl StrParser<'a, T, S> {
+    string_literal(StringSyntax::JSON).into_type()
 }
 
-fn json_string_parser<'a, T: From<&'a str>>() -> impl StrParser<'a, JsonValue<T>> {
+fn json_string_parser<'a, T: From<&'a str>, S>() -> impl StrParser<'a, JsonValue<T>, S> {
     string_parser().map(JsonValue::Str)
 }
 
-fn number_parser<'a, T>() -> impl StrParser<'a, JsonValue<T>> {
-    float().map(JsonValue::Num)
+/// Get the length of the number at the start of `bytes`, on the form
+/// `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`. Returns `None` if there is no such number.
+fn number_len(bytes: &[u8]) -> Option<usize> {
+    let digits = |pos: usize| pos + bytes[pos..].iter().take_while(|b| b.is_ascii_digit()).count();
+
+    let mut pos = (bytes.first() == Some(&b'-')) as usize;
+    pos = match bytes.get(pos)? {
+        b'0' => pos + 1,
+        b'1'..=b'9' => digits(pos),
+        _ => return None
+    };
+    if bytes.get(pos) == Some(&b'.') {
+        match digits(pos + 1) {
+            end if end == pos + 1 => return None,
+            end => pos = end
+        }
+    }
+    if let Some(b'e' | b'E') = bytes.get(pos) {
+        let start = pos + 1 + matches!(bytes.get(pos + 1), Some(b'+' | b'-')) as usize;
+        match digits(start) {
+            end if end == start => return None,
+            end => pos = end
+        }
+    }
+    Some(pos)
+}
+
+fn number_parser<'a, T, S>(reject_overflow: bool) -> impl StrParser<'a, JsonValue<T>, S> {
+    create_parser!(s, {
+        let len = number_len(s.input.as_bytes())?;
+        let n: f64 = convert_number(&s.input.as_bytes()[..len])?;
+        if reject_overflow && !n.is_finite() {
+            return None
+        }
+        s.input = &s.input[len..];
+        Some(JsonValue::Num(n))
+    })
 }
 
-fn bool_parser<'a, T>() -> impl StrParser<'a, JsonValue<T>> {
+fn bool_parser<'a, T, S>() -> impl StrParser<'a, JsonValue<T>, S> {
     or(skip!("true").map(|_| JsonValue::Bool(true)), skip!("false").map(|_| JsonValue::Bool(false)))
 }
 
-fn null_parser<'a, T>() -> impl StrParser<'a, JsonValue<T>> {
+fn null_parser<'a, T, S>() -> impl StrParser<'a, JsonValue<T>, S> {
     skip!("null").map(|_| JsonValue::Null)
 }
 
+/// A partially parsed JSON object or array.
+enum Frame<T> {
+    Dic(BTreeMap<T, JsonValue<T>>, Option<T>),
+    Arr(Vec<JsonValue<T>>)
+}
+
+impl<'a, T: From<&'a str> + Ord, S> NestedFrame<&'a str, S> for Frame<T> {
+    type Value = JsonValue<T>;
+
+    fn element_prefix(&mut self, s: &mut AnpaState<&'a str, S>) -> Option<()> {
+        if let Frame::Dic(_, key) = self {
+            *key = Some(left(eat(string_parser()), eat(skip!(':')))(s)?);
+        }
+        Some(())
+    }
+
+    fn close(&self, s: &mut AnpaState<&'a str, S>) -> Option<()> {
+        match self {
+            Frame::Dic(..) => eat(skip!('}'))(s),
+            Frame::Arr(_) => eat(skip!(']'))(s)
+        }
+    }
+
+    fn push(&mut self, value: JsonValue<T>) {
+        match self {
+            Frame::Dic(map, key) => {
+                if let Some(key) = key.take() {
+                    map.insert(key, value);
+                }
+            }
+            Frame::Arr(vec) => vec.push(value)
+        }
+    }
+
+    fn finish(self) -> JsonValue<T> {
+        match self {
+            Frame::Dic(map, _) => JsonValue::Dic(map),
+            Frame::Arr(vec) => JsonValue::Arr(vec)
+        }
+    }
+}
+
+fn atom_parser<'a, T: From<&'a str>, S>(reject_overflow: bool) -> impl StrParser<'a, JsonValue<T>, S> {
+    eat(dispatch!('"' => json_string_parser(),
+                  '-' | '0'..='9' => number_parser(reject_overflow),
+                  't' | 'f' => bool_parser(),
+                  'n' => null_parser()))
+}
+
+fn value_parser_internal<'a, T: From<&'a str> + Ord, S>(limits: JsonLimits) -> impl StrParser<'a, JsonValue<T>, S> {
+    // Objects and arrays are parsed iteratively to support arbitrarily deep nesting.
+    let open = eat(or(skip!('{').map(|_| Frame::Dic(BTreeMap::new(), None)),
+                      skip!('[').map(|_| Frame::Arr(Vec::new()))));
+    let value = fold_nested(open, atom_parser(limits.reject_overflow), eat(skip!(',')), limits.max_depth);
+    create_parser!(s, {
+        if s.input.len() > limits.max_input_len {
+            return None
+        }
+        value(s)
+    })
+}
+
 /// Get a JSON parser that parses any JSON value. The type used for strings will be inferred
 /// from the context via `From<&str>`. For examples, see `object_parser`.
 pub fn value_parser<'a, T: From<&'a str> + Ord>() -> impl StrParser<'a, JsonValue<T>> {
-    defer_parser! {
-        eat(or!(json_string_parser(), number_parser(), object_parser(),
-                array_parser(), bool_parser(), null_parser()))
-    }
+    value_parser_internal(JsonLimits::NONE)
+}
+
+/// Get a JSON parser that parses any JSON value, failing if objects and arrays are nested
+/// deeper than `max_depth`. Use this parser to limit memory usage for untrusted input.
+///
+/// ### Example
+/// ```
+/// use anpa::core::parse;
+/// use anpa::json;
+///
+/// let p = json::value_parser_with_max_depth::<&str>(2);
+///
+/// assert!(parse(p, "[[1]]").result.is_some());
+/// assert!(parse(p, "[[[1]]]").result.is_none());
+/// ```
+pub fn value_parser_with_max_depth<'a, T: From<&'a str> + Ord>(max_depth: usize) -> impl StrParser<'a, JsonValue<T>> {
+    value_parser_internal(JsonLimits { max_depth, ..JsonLimits::NONE })
+}
+
+/// Limits for parsing untrusted JSON, as used by [`value_parser_with_limits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonLimits {
+    /// The maximum length of the remaining input, in bytes. Longer input is rejected before
+    /// parsing.
+    pub max_input_len: usize,
+
+    /// The maximum nesting depth of objects and arrays.
+    pub max_depth: usize,
+
+    /// Whether numbers too large for an `f64`, e.g. `1e999`, are rejected rather than parsed
+    /// as infinity.
+    pub reject_overflow: bool
+}
+
+impl JsonLimits {
+    /// No limits, as used by [`value_parser`].
+    pub const NONE: JsonLimits = JsonLimits { max_input_len: usize::MAX, max_depth: usize::MAX, reject_overflow: false };
+
+    /// Limits suitable for untrusted input: at most 16 MiB of input and 128 levels of
+    /// nesting, and no overflowing numbers.
+    pub const UNTRUSTED: JsonLimits = JsonLimits { max_input_len: 16 << 20, max_depth: 128, reject_overflow: true };
+}
+
+/// Get a JSON parser that parses any JSON value, failing if the input exceeds `limits`.
+/// Use this parser, e.g. with [`JsonLimits::UNTRUSTED`], to limit the memory usage and
+/// reject degenerate values for untrusted input.
+///
+/// ### Example
+/// ```
+/// use anpa::core::parse;
+/// use anpa::json::{value_parser_with_limits, JsonLimits};
+///
+/// let p = value_parser_with_limits::<&str>(JsonLimits::UNTRUSTED);
+/// assert!(parse(p, "[1e308]").result.is_some());
+/// assert!(parse(p, "[1e309]").result.is_none());
+///
+/// let p = value_parser_with_limits::<&str>(JsonLimits { max_input_len: 8, ..JsonLimits::UNTRUSTED });
+/// assert!(parse(p, "[1, 2]").result.is_some());
+/// assert!(parse(p, "[1, 2, 3]").result.is_none());
+/// ```
+pub fn value_parser_with_limits<'a, T: From<&'a str> + Ord>(limits: JsonLimits) -> impl StrParser<'a, JsonValue<T>> {
+    value_parser_internal(limits)
 }
 
 /// Get a JSON parser that parses a JSON object. The type used for strings will be inferred
@@ -65,20 +408,234 @@ pub fn value_parser<'a, T: From<&'a str> + Ord>() -> impl StrParser<'a, JsonValu
 /// // let p3 = json::object_parser::<MyString>();
 /// ```
 pub fn object_parser<'a, T: From<&'a str> + Ord>() -> impl StrParser<'a, JsonValue<T>> {
-    let pair_parser = tuplify!(
-        left(eat(string_parser()), eat(skip!(':'))),
-        value_parser());
-    middle(
-        skip!('{'),
-        many_to_map_ordered(pair_parser, true, separator(eat(skip!(',')), false)),
-        eat(skip!('}'))).map(JsonValue::Dic)
+    right(peek(skip!('{')), value_parser())
 }
 
 /// Get a JSON parser that parses a JSON array. The type used for strings will be inferred
 /// from the context via `From<&str>`. For examples, see `object_parser`.
 pub fn array_parser<'a, T: From<&'a str> + Ord>() -> impl StrParser<'a, JsonValue<T>> {
-    middle(
-        skip!('['),
-        many_to_vec(value_parser(), true, separator(eat(skip!(',')), false)),
-        eat(skip!(']'))).map(JsonValue::Arr)
-}
\ No newline at end of file
+    right(peek(skip!('[')), value_parser())
+}
+
+/// Get a JSON parser that parses any JSON value, but leaves the objects and arrays nested
+/// in it unparsed, as [`JsonValue::RawObject`] and [`JsonValue::RawArray`]. These can be
+/// parsed on demand using [`JsonValue::parse_value`].
+///
+/// This avoids building the whole document when only parts of it are needed, e.g. when
+/// looking for a single key in a large document. The raw values are only checked for
+/// balanced delimiters, so any other errors in them are found when they are parsed.
+///
+/// ### Example
+/// ```
+/// use anpa::core::parse;
+/// use anpa::json::{lazy_value_parser, JsonValue};
+///
+/// let input = r#"{"id": 7, "items": [{"x": 1}, {"x": 2}]}"#;
+/// let Some(JsonValue::Dic(map)) = &parse(lazy_value_parser(), input).result else { panic!() };
+///
+/// assert!(matches!(map["id"], JsonValue::Num(n) if n == 7.0));
+/// assert!(matches!(map["items"], JsonValue::RawArray(r#"[{"x": 1}, {"x": 2}]"#)));
+/// ```
+pub fn lazy_value_parser<'a, T: From<&'a str> + Ord>() -> impl StrParser<'a, JsonValue<T>> {
+    let raw = eat(dispatch!('{' => get_parsed(between_balanced(b'{', b'}', Some(b'"')))
+                                .map(|raw: &str| JsonValue::RawObject(raw.into())),
+                            '[' => get_parsed(between_balanced(b'[', b']', Some(b'"')))
+                                .map(|raw: &str| JsonValue::RawArray(raw.into()))));
+    let value = or(atom_parser(false), raw);
+    let separator = || separator(eat(skip!(',')), false);
+
+    let member = tuplify!(eat(string_parser()), right(eat(skip!(':')), value));
+    let object = middle(skip!('{'), many_to_map_ordered(member, true, separator()), eat(skip!('}')))
+        .map(JsonValue::Dic);
+    let array = middle(skip!('['), many_to_vec(value, true, separator()), eat(skip!(']')))
+        .map(JsonValue::Arr);
+
+    or(eat(or(object, array)), atom_parser(false))
+}
+
+/// Skip any lines that are empty or only contain whitespace.
+fn skip_blank_lines(input: &str) -> &str {
+    let trimmed = input.trim_ascii_start();
+    if trimmed.is_empty() {
+        return trimmed
+    }
+
+    let skipped = &input[..input.len() - trimmed.len()];
+    skipped.rfind('\n').map_or(input, |i| &input[i + 1..])
+}
+
+/// Get a parser for NDJSON (JSON Lines), i.e. one JSON value per line, that parses a single
+/// line. Use e.g. [`parse_iter`](crate::core::parse_iter) to get an iterator over the values,
+/// or [`many_to_vec`] to collect them. Blank lines are skipped.
+///
+/// If `skip_invalid` is `true`, lines that are not a valid JSON value are skipped. Otherwise,
+/// the parser fails at the start of an invalid line.
+///
+/// ### Example
+/// ```
+/// use anpa::core::parse_iter;
+/// use anpa::json::{ndjson_parser, JsonValue};
+///
+/// let input = "{\"id\": 1}\n\n[2]\nnot json\n3\n";
+///
+/// let mut values = parse_iter(ndjson_parser::<&str>(false), input);
+/// assert!(matches!(values.next(), Some(JsonValue::Dic(_))));
+/// assert!(matches!(values.next(), Some(JsonValue::Arr(_))));
+/// assert!(values.next().is_none());
+/// assert_eq!(values.error().unwrap().offset, 15);
+///
+/// assert_eq!(parse_iter(ndjson_parser::<&str>(true), input).count(), 3);
+/// ```
+pub fn ndjson_parser<'a, T: From<&'a str> + Ord>(skip_invalid: bool) -> impl StrParser<'a, JsonValue<T>> {
+    create_parser!(s, {
+        s.input = skip_blank_lines(s.input);
+
+        while !s.input.is_empty() {
+            // Parse each line separately, so that a value can't continue on the next line.
+            let end = find(s.input.as_bytes(), eq(b'\n')).unwrap_or(s.input.len());
+            let (line, rest) = s.input.split_at(end);
+            let next = skip_blank_lines(rest.strip_prefix('\n').unwrap_or(rest));
+
+            let res = parse(value_parser(), line);
+            if res.result.is_some() && res.state.trim_ascii().is_empty() {
+                s.input = next;
+                return res.result
+            } else if !skip_invalid {
+                return None
+            }
+            s.input = next;
+        }
+
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec::Vec};
+
+    use crate::{combinators::{lift_to_state, many_to_vec, no_separator, separator, stateless}, core::{parse, parse_iter, parse_state}, parsers::skip, json::{array_parser, lazy_value_parser, ndjson_parser, object_parser, value_parser, value_parser_with_limits, JsonLimits, JsonValue}};
+
+    #[test]
+    fn with_user_state() {
+        let value = lift_to_state(|n: &mut usize, v| { *n += 1; v }, stateless(value_parser::<&str>()));
+        let mut count = 0;
+        let res = parse_state(many_to_vec(value, true, separator(skip(','), false)), "1,[2],{}", &mut count).result;
+        assert_eq!((res.map(|v| v.len()), count), (Some(3), 3));
+    }
+
+    #[test]
+    fn object() {
+        let res = parse(object_parser::<&str>(), r#"{ "a" : [1, true, null], "b": {"c": "d"}, "e": {} }"#);
+        assert!(res.state.is_empty());
+        let Some(JsonValue::Dic(map)) = &res.result else { panic!() };
+        assert!(matches!(&map["a"], JsonValue::Arr(a) if matches!(a[..],
+            [JsonValue::Num(n), JsonValue::Bool(true), JsonValue::Null] if n == 1.0)));
+        assert!(matches!(&map["b"], JsonValue::Dic(m) if matches!(m["c"], JsonValue::Str("d"))));
+        assert!(matches!(&map["e"], JsonValue::Dic(m) if m.is_empty()));
+
+        assert!(parse(object_parser::<&str>(), "[]").result.is_none());
+        assert!(parse(object_parser::<&str>(), r#"{"a": 1,}"#).result.is_none());
+        assert!(parse(object_parser::<&str>(), r#"{"a" 1}"#).result.is_none());
+    }
+
+    #[test]
+    fn array() {
+        let res = parse(array_parser::<&str>(), "[ [], [[]] , \"x\" ]").result;
+        assert!(matches!(&res, Some(JsonValue::Arr(a)) if a.len() == 3));
+
+        assert!(parse(array_parser::<&str>(), "{}").result.is_none());
+        assert!(parse(array_parser::<&str>(), "[1,]").result.is_none());
+        assert!(parse(array_parser::<&str>(), "[1").result.is_none());
+    }
+
+    #[test]
+    fn numbers() {
+        let res = parse(array_parser::<&str>(), "[0, -0.5, 12e3, 1.5E-2, -1e+2]").result;
+        let Some(JsonValue::Arr(a)) = &res else { panic!() };
+        let nums: Vec<_> = a.iter().map(|v| match v { JsonValue::Num(n) => *n, _ => panic!() }).collect();
+        assert_eq!(nums, [0.0, -0.5, 12e3, 1.5e-2, -1e2]);
+
+        for input in ["[1.]", "[-.5]", "[.5]", "[1.e5]", "[01]", "[-01]", "[+1]", "[1e]", "[1e+]", "[-]", "[1.5.2]"] {
+            assert!(parse(array_parser::<&str>(), input).result.is_none(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn lazy() {
+        let input = r#" { "a" : { "b" : "}" } , "c": [1, [2]], "d": null } "#;
+        let Some(JsonValue::Dic(map)) = &parse(lazy_value_parser(), input).result else { panic!() };
+        assert!(matches!(map["a"], JsonValue::RawObject(r#"{ "b" : "}" }"#)));
+        assert!(matches!(map["d"], JsonValue::Null));
+
+        let Some(JsonValue::Arr(c)) = &map["c"].parse_value() else { panic!() };
+        assert!(matches!(c[..], [JsonValue::Num(_), JsonValue::RawArray("[2]")]));
+        assert!(c[0].parse_value().is_none());
+
+        assert!(matches!(&parse(lazy_value_parser::<&str>(), "[]").result, Some(JsonValue::Arr(a)) if a.is_empty()));
+        assert!(parse(lazy_value_parser::<&str>(), r#"{"a": [1}"#).result.is_none());
+        assert!(JsonValue::RawArray("[1,]").parse_value().is_none());
+    }
+
+    #[test]
+    fn ndjson() {
+        let input = "  \r\n1\r\n [\n2]\n\"x\" \n\t\n";
+        let values: Vec<JsonValue<&str>> = parse_iter(ndjson_parser(true), input).collect();
+        assert!(matches!(values[..], [JsonValue::Num(_), JsonValue::Str("x")]));
+
+        let mut iter = parse_iter(ndjson_parser::<&str>(false), input);
+        assert_eq!(iter.by_ref().count(), 1);
+        assert_eq!(iter.remaining(), " [\n2]\n\"x\" \n\t\n");
+
+        let res = parse(many_to_vec(ndjson_parser::<&str>(true), true, no_separator()), "1\nx\n");
+        assert_eq!(res.result.map(|v| v.len()), Some(1));
+        assert_eq!(res.state, "");
+    }
+
+    #[test]
+    fn deep_nesting() {
+        let n = 100_000;
+        let mut input = String::new();
+        input.extend((0..n).map(|_| '['));
+        input.extend((0..n).map(|_| ']'));
+
+        assert!(parse(value_parser_with_limits::<&str>(JsonLimits::UNTRUSTED), input.as_str()).result.is_none());
+        assert!(parse(value_parser_with_limits::<&str>(JsonLimits::UNTRUSTED), &input[n - 128..n + 128]).result.is_some());
+
+        let res = parse(value_parser::<&str>(), input.as_str());
+        assert!(res.state.is_empty());
+        assert!(matches!(res.result, Some(JsonValue::Arr(_))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        use alloc::{collections::BTreeMap, vec};
+        use serde_test::{assert_de_tokens, assert_ser_tokens, assert_ser_tokens_error, Token};
+
+        let value = parse(value_parser::<&str>(), r#"{"a\"": [1.5, true, null], "b": "é\n🦀"}"#).result.unwrap();
+        let tokens = [
+            Token::Map { len: Some(2) },
+            Token::Str("a\""), Token::Seq { len: Some(3) }, Token::F64(1.5), Token::Bool(true), Token::Unit, Token::SeqEnd,
+            Token::Str("b"), Token::Str("é\n🦀"),
+            Token::MapEnd
+        ];
+        assert_ser_tokens(&value, &tokens);
+
+        let lazy = parse(lazy_value_parser::<&str>(), r#"[{"a\"": [1.5, true, null], "b": "é\n🦀"}]"#).result.unwrap();
+        assert_ser_tokens(&lazy, &[&[Token::Seq { len: Some(1) }][..], &tokens, &[Token::SeqEnd]].concat());
+        assert_ser_tokens_error(&JsonValue::RawArray("[1,]"), &[], "invalid raw JSON value");
+        assert_ser_tokens_error(&JsonValue::Str("\\x"), &[], "invalid escape in JSON string");
+
+        let expected = JsonValue::Dic(BTreeMap::from([
+            (String::from("\\\"q\\\""), JsonValue::Arr(vec![JsonValue::Num(-1.0), JsonValue::Null, JsonValue::Null])),
+            (String::from("s"), JsonValue::Str(String::from("\\t\\u001f\\\\")))
+        ]));
+        assert_de_tokens(&expected, &[
+            Token::Map { len: None },
+            Token::Str("\"q\""), Token::Seq { len: None }, Token::I8(-1), Token::None, Token::Unit, Token::SeqEnd,
+            Token::BorrowedStr("s"), Token::String("\t\u{1f}\\"),
+            Token::MapEnd
+        ]);
+    }
+}
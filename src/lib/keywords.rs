@@ -0,0 +1,176 @@
+use crate::{core::Parser, findbyte::ByteInput};
+
+/// A node in a [`Trie`], using a first child/next sibling representation.
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    byte: u8,
+    first_child: u16,
+    next_sibling: u16,
+
+    /// The index of the key ending at this node, plus one, or zero if no key ends here.
+    key: u16
+}
+
+const NONE: u16 = 0;
+
+impl Node {
+    const EMPTY: Node = Node { byte: 0, first_child: NONE, next_sibling: NONE, key: NONE };
+}
+
+/// A trie of keywords that can be constructed at compile time, for matching the longest of a
+/// set of keywords in a single pass over the input. Normally used through [`keywords!`].
+///
+/// The number of nodes `N` must be at least the value of [`node_count`] for the keys.
+#[derive(Debug, Clone, Copy)]
+pub struct Trie<const N: usize> {
+    nodes: [Node; N],
+
+    /// The children of the root, indexed by byte, since the root usually has the most children.
+    root: [u16; 256]
+}
+
+/// Get the number of nodes needed for a [`Trie`] of `keys`.
+pub const fn node_count(keys: &[&str]) -> usize {
+    let mut count = 1;
+    let mut i = 0;
+    while i < keys.len() {
+        count += keys[i].len();
+        i += 1;
+    }
+    count
+}
+
+impl<const N: usize> Trie<N> {
+    /// Create a trie of `keys`. If a key occurs several times, the first occurrence is used.
+    ///
+    /// Panics if `N` is too small, or if there are more than 65535 nodes or keys.
+    pub const fn new(keys: &[&str]) -> Self {
+        assert!(N >= node_count(keys) && N <= u16::MAX as usize && keys.len() < u16::MAX as usize,
+                "invalid number of nodes or keys");
+
+        let mut nodes = [Node::EMPTY; N];
+        let mut len = 1;
+
+        let mut k = 0;
+        while k < keys.len() {
+            let key = keys[k].as_bytes();
+            let mut node = 0;
+
+            let mut i = 0;
+            while i < key.len() {
+                // Find the child for the byte, or add it.
+                let mut child = nodes[node].first_child;
+                while child != NONE && nodes[child as usize].byte != key[i] {
+                    child = nodes[child as usize].next_sibling;
+                }
+
+                if child == NONE {
+                    child = len as u16;
+                    nodes[len] = Node { byte: key[i], first_child: NONE, next_sibling: nodes[node].first_child, key: NONE };
+                    nodes[node].first_child = child;
+                    len += 1;
+                }
+
+                node = child as usize;
+                i += 1;
+            }
+
+            if nodes[node].key == NONE {
+                nodes[node].key = k as u16 + 1;
+            }
+            k += 1;
+        }
+
+        let mut root = [NONE; 256];
+        let mut child = nodes[0].first_child;
+        while child != NONE {
+            root[nodes[child as usize].byte as usize] = child;
+            child = nodes[child as usize].next_sibling;
+        }
+
+        Self { nodes, root }
+    }
+
+    /// Find the longest key that is a prefix of `bytes`. Returns the index of the key and
+    /// its length.
+    #[inline]
+    pub fn longest_match(&self, bytes: &[u8]) -> Option<(usize, usize)> {
+        let mut found = None;
+        let mut node = 0;
+
+        for (i, b) in bytes.iter().enumerate() {
+            let key = self.nodes[node].key;
+            if key != NONE {
+                found = Some((key as usize - 1, i));
+            }
+
+            let child = if i == 0 {
+                self.root[*b as usize]
+            } else {
+                let mut child = self.nodes[node].first_child;
+                while child != NONE && self.nodes[child as usize].byte != *b {
+                    child = self.nodes[child as usize].next_sibling;
+                }
+                child
+            };
+
+            if child == NONE {
+                return found
+            }
+            node = child as usize;
+        }
+
+        let key = self.nodes[node].key;
+        if key != NONE {
+            found = Some((key as usize - 1, bytes.len()));
+        }
+        found
+    }
+}
+
+/// Create a parser that matches the longest key in `trie`, and returns the value with the
+/// same index as the key. Normally used through [`keywords!`].
+///
+/// ### Consuming
+/// The matched key, on successful parse.
+///
+/// ### Arguments
+/// * `trie` - the trie of keys
+/// * `values` - the values of the keys
+#[inline]
+pub fn keywords<'a, 't, I: ByteInput<'a>, V: Copy + 't, const N: usize, const K: usize, S>(
+    trie: &'t Trie<N>,
+    values: [V; K]
+) -> impl Parser<I, V, S> + 't {
+    create_parser!(s, {
+        let (key, len) = trie.longest_match(s.input.as_byte_slice())?;
+        s.input = s.input.slice_from(len);
+        values.get(key).copied()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{core::parse, keywords::{node_count, Trie}};
+
+    #[test]
+    fn trie() {
+        const KEYS: &[&str] = &["in", "int", "if", "i", "in", ""];
+        const TRIE: Trie<{ node_count(KEYS) }> = Trie::new(KEYS);
+
+        assert_eq!(TRIE.longest_match(b"integer"), Some((1, 3)));
+        assert_eq!(TRIE.longest_match(b"inner"), Some((0, 2)));
+        assert_eq!(TRIE.longest_match(b"ix"), Some((3, 1)));
+        assert_eq!(TRIE.longest_match(b"x"), Some((5, 0)));
+
+        const NO_EMPTY: Trie<3> = Trie::new(&["ab"]);
+        assert_eq!(NO_EMPTY.longest_match(b"a"), None);
+        assert_eq!(NO_EMPTY.longest_match(b""), None);
+
+        let p = keywords!("<" => 1, "<=" => 2, "<<=" => 3);
+        let res = parse(p, "<<x");
+        assert_eq!(res.result, Some(1));
+        assert_eq!(res.state, "<x");
+        assert_eq!(parse(keywords!("<" => 1, "<<=" => 3), b"<<=".as_slice()).result, Some(3));
+    }
+}
@@ -0,0 +1,145 @@
+#[cfg(feature = "alloc")]
+use {alloc::vec::Vec, crate::slicelike::{consumed_len, SliceLike}};
+
+use crate::{core::Parser, parsers::item_if};
+
+/// A token produced by a lexer, consisting of its kind along with the input it was parsed from.
+///
+/// A sequence of tokens can be parsed like any other input, since `&[T]` implements
+/// [`SliceLike`](crate::slicelike::SliceLike). See [`tokenize`] for an example of a complete two-phase pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<K, I> {
+    /// The kind of the token, e.g. an enum with one variant per type of token.
+    pub kind: K,
+
+    /// The input that this token was parsed from.
+    pub text: I,
+
+    /// The offset of the token from the start of the input.
+    ///
+    /// Note: For `&str`, the offset is in bytes.
+    pub offset: usize
+}
+
+#[cfg(feature = "alloc")]
+/// Create a parser that splits the input into a `Vec` of [`Token`], where each token is parsed
+/// by `token` and any input matched by `trivia` (e.g. whitespace and comments) between
+/// tokens is ignored.
+///
+/// The parser fails if any input remains that can't be parsed as a token.
+///
+/// ### Arguments
+/// * `token` - a parser returning the kind of the token
+/// * `trivia` - a parser for input to be ignored between tokens
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::not_empty;
+/// use anpa::lexer::{token, token_text, tokenize};
+/// use anpa::parsers::{item_while, skip};
+/// use anpa::whitespace::skip_ascii_whitespace;
+/// use anpa::{map, or};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Kind { Let, Ident, Eq, Num }
+///
+/// // Phase 1: Split the text into tokens
+/// let lexer = tokenize(or!(skip("let").to(Kind::Let),
+///                          skip('=').to(Kind::Eq),
+///                          not_empty(item_while(|c: char| c.is_ascii_digit())).to(Kind::Num),
+///                          not_empty(item_while(|c: char| c.is_alphabetic())).to(Kind::Ident)),
+///                      skip_ascii_whitespace());
+///
+/// let tokens = parse(lexer, "let x = 42").result.unwrap();
+/// assert_eq!(tokens.len(), 4);
+///
+/// // Phase 2: Parse the tokens
+/// let binding = map!(|name, value: &str| (name, value.parse::<u32>().unwrap()),
+///                    token(Kind::Let).right(token_text(Kind::Ident)),
+///                    token(Kind::Eq).right(token_text(Kind::Num)));
+///
+/// assert_eq!(parse(binding, tokens.as_slice()).result, Some(("x", 42)));
+/// ```
+#[inline]
+pub fn tokenize<I: SliceLike<Idx = usize>, K, O, S>(token: impl Parser<I, K, S>,
+                                                    trivia: impl Parser<I, O, S>
+) -> impl Parser<I, Vec<Token<K, I>>, S> {
+    create_parser!(s, {
+        let start = s.input.slice_len();
+        let mut tokens = Vec::new();
+
+        loop {
+            if trivia(s).is_none() && s.is_cut() {
+                return None
+            }
+
+            if s.input.slice_is_empty() {
+                return Some(tokens)
+            }
+
+            let before = s.input;
+            let kind = token(s)?;
+            let len = consumed_len(before, s.input);
+
+            // A token must consume input, or we would loop forever.
+            if len == 0 {
+                return None
+            }
+
+            tokens.push(Token { kind, text: before.slice_to(len), offset: start - before.slice_len() });
+        }
+    })
+}
+
+/// Create a parser that parses a single token of the provided kind.
+///
+/// ### Arguments
+/// * `kind` - the kind of token to parse
+#[inline]
+pub fn token<'a, K: PartialEq + Copy + 'a, T: 'a, S>(kind: K) -> impl Parser<&'a [Token<K, T>], &'a Token<K, T>, S> {
+    item_if(move |t: &Token<K, T>| t.kind == kind)
+}
+
+/// Create a parser that parses a single token of the provided kind, and returns its text.
+///
+/// ### Arguments
+/// * `kind` - the kind of token to parse
+#[inline]
+pub fn token_text<'a, K: PartialEq + Copy + 'a, T: Copy + 'a, S>(kind: K) -> impl Parser<&'a [Token<K, T>], T, S> {
+    create_parser!(s, token(kind)(s).map(|t| t.text))
+}
+
+/// Create a parser that parses a single token whose kind matches the provided predicate.
+///
+/// ### Arguments
+/// * `pred` - the predicate
+#[inline]
+pub fn token_if<'a, K: Copy + 'a, T: 'a, S>(pred: impl FnOnce(K) -> bool + Copy) -> impl Parser<&'a [Token<K, T>], &'a Token<K, T>, S> {
+    item_if(move |t: &Token<K, T>| pred(t.kind))
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{combinators::not_empty, core::{parse, ParserExt}, lexer::{token_if, tokenize, Token}, parsers::{item_while, skip}, whitespace::skip_ascii_whitespace};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Kind { Word, Comma }
+
+    #[test]
+    fn tokens() {
+        let word = not_empty(item_while(|c: char| c.is_alphabetic())).to(Kind::Word);
+        let lexer = tokenize(or!(word, skip(',').to(Kind::Comma)), skip_ascii_whitespace());
+
+        let res = parse(lexer, " ab , c ").result.unwrap();
+        assert_eq!(res, [Token { kind: Kind::Word, text: "ab", offset: 1 },
+                         Token { kind: Kind::Comma, text: ",", offset: 4 },
+                         Token { kind: Kind::Word, text: "c", offset: 6 }]);
+
+        assert!(parse(lexer, "ab ; c").result.is_none());
+
+        let words = parse(token_if(|k| k == Kind::Word), res.as_slice());
+        assert_eq!(words.result, Some(&res[0]));
+        assert_eq!(words.state.len(), 2);
+    }
+}
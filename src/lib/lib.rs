@@ -3,6 +3,13 @@
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+// The derive macros refer to `::anpa`, so allow them to be tested within the crate.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as anpa;
+
 #[macro_use]
 pub mod macros;
 pub mod parsers;
@@ -12,11 +19,92 @@ pub mod core;
 pub mod combinators;
 pub mod slicelike;
 pub mod prefix;
+pub mod suffix;
 pub mod needle;
+pub mod findbyte;
 pub mod whitespace;
+pub mod lexer;
+pub mod indent;
+pub mod pattern;
+pub mod keywords;
+pub mod units;
+pub mod literal;
+pub mod const_parse;
+
+#[cfg(feature = "alloc")]
+pub mod boxed;
+
+#[cfg(feature = "alloc")]
+pub mod diagnostics;
+
+#[cfg(feature = "alloc")]
+pub mod symbols;
+
+#[cfg(feature = "alloc")]
+pub mod interner;
+
+#[cfg(feature = "std")]
+pub mod memo;
+
+#[cfg(feature = "std")]
+pub mod read;
+
+#[cfg(feature = "std")]
+pub mod parallel;
+
+#[cfg(feature = "trace")]
+pub mod trace;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "arena")]
+pub mod arena;
+
+#[cfg(feature = "regex")]
+pub mod regex;
+
+#[cfg(any(feature = "nom", feature = "winnow"))]
+pub mod interop;
 
 #[cfg(feature = "json")]
 pub mod json;
 
+#[cfg(feature = "toml")]
+pub mod toml;
+
+#[cfg(feature = "xml")]
+pub mod xml;
+
+#[cfg(feature = "markdown")]
+pub mod markdown;
+
 #[cfg(feature = "semver")]
-pub mod semver;
\ No newline at end of file
+pub mod semver;
+
+#[cfg(feature = "commit")]
+pub mod commit;
+
+#[cfg(feature = "resp")]
+pub mod resp;
+
+#[cfg(feature = "mime")]
+pub mod mime;
+
+#[cfg(feature = "expand")]
+pub mod expand;
+
+#[cfg(feature = "template")]
+pub mod template;
+
+#[cfg(feature = "http")]
+pub mod http;
+
+#[cfg(feature = "color")]
+pub mod color;
+
+#[cfg(feature = "numeral")]
+pub mod numeral;
+
+#[cfg(feature = "capi")]
+pub mod capi;
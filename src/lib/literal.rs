@@ -0,0 +1,542 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::str::from_utf8;
+
+use crate::{core::Parser, findbyte::{eq, find, in_range, ByteInput}};
+
+/// The syntax of number literals, as used by [`number_literal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberSyntax<'b> {
+    /// The digit separator, if any, e.g. `_` in Rust or `'` in C23.
+    pub separator: Option<u8>,
+
+    /// Whether a leading `0` makes an integer octal, as in C, rather than a `0o` prefix, as in
+    /// Rust.
+    pub leading_zero_octal: bool,
+
+    /// The suffixes allowed after integers, e.g. `u32`.
+    pub integer_suffixes: &'b [&'b str],
+
+    /// The suffixes allowed after floats, e.g. `f64`. A decimal integer followed by one of
+    /// these is a float.
+    pub float_suffixes: &'b [&'b str],
+
+    /// Whether suffixes are matched ignoring ASCII case, as in C.
+    pub suffix_ignore_case: bool
+}
+
+impl NumberSyntax<'static> {
+    /// Rust number literals, e.g. `0xff_u8` or `1e3f32`.
+    pub const RUST: NumberSyntax<'static> = NumberSyntax {
+        separator: Some(b'_'),
+        leading_zero_octal: false,
+        integer_suffixes: &["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"],
+        float_suffixes: &["f32", "f64"],
+        suffix_ignore_case: false
+    };
+
+    /// C number literals, e.g. `0755`, `10ULL` or `1.5f`, with `'` separators as in C23.
+    pub const C: NumberSyntax<'static> = NumberSyntax {
+        separator: Some(b'\''),
+        leading_zero_octal: true,
+        integer_suffixes: &["u", "l", "ul", "lu", "ll", "ull", "llu"],
+        float_suffixes: &["f", "l"],
+        suffix_ignore_case: true
+    };
+}
+
+/// A number literal, as produced by [`number_literal`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberLiteral<I> {
+    /// An integer, with its radix (2, 8, 10 or 16) and optional suffix.
+    Integer { value: u128, radix: u32, suffix: Option<I> },
+
+    /// A float, with its optional suffix.
+    Float { value: f64, suffix: Option<I> }
+}
+
+fn is_ident_byte(byte: Option<&u8>) -> bool {
+    byte.is_some_and(|b| b.is_ascii_alphanumeric() || *b == b'_')
+}
+
+/// Get the length of the digits of `radix` starting at `pos`, including separators after the
+/// first digit.
+fn digits_len(bytes: &[u8], pos: usize, radix: u32, separator: Option<u8>) -> usize {
+    match bytes.get(pos) {
+        Some(b) if (*b as char).is_digit(radix) => 1 + bytes[pos + 1..].iter()
+            .take_while(|b| (**b as char).is_digit(radix) || Some(**b) == separator).count(),
+        _ => 0
+    }
+}
+
+/// Get the length of the longest suffix in `suffixes` starting at `pos`, which must not be
+/// followed by an identifier byte.
+fn suffix_len(bytes: &[u8], pos: usize, suffixes: &[&str], ignore_case: bool) -> Option<usize> {
+    suffixes.iter().map(|s| s.as_bytes()).filter(|s| {
+        let Some(candidate) = bytes.get(pos..pos + s.len()) else { return false };
+        let matches = if ignore_case { candidate.eq_ignore_ascii_case(s) } else { candidate == *s };
+        matches && !is_ident_byte(bytes.get(pos + s.len()))
+    }).map(<[u8]>::len).max()
+}
+
+/// Create a parser for a number literal of a programming language, e.g. `0x1F`, `1_000u64`
+/// or `2.5e-3f32`. Integers may have a `0x`, `0o` (or a leading `0`, depending on `syntax`)
+/// or `0b` prefix, and decimal floats may have a fraction and an exponent. A sign is not
+/// part of the literal.
+///
+/// Hexadecimal floats, and floats longer than 64 bytes (excluding separators and suffix),
+/// are not supported.
+///
+/// ### Consuming
+/// Consumes the literal, on successful parse. The parser fails if the literal is followed
+/// by an identifier character, e.g. an invalid suffix, or if an integer doesn't fit in a
+/// `u128`.
+///
+/// ### Arguments
+/// * `syntax` - the syntax of the literals, e.g. [`NumberSyntax::RUST`]
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::literal::{number_literal, NumberLiteral, NumberSyntax};
+///
+/// let rust = number_literal(NumberSyntax::RUST);
+/// assert_eq!(parse(rust, "0xff_u8").result, Some(NumberLiteral::Integer { value: 255, radix: 16, suffix: Some("u8") }));
+/// assert_eq!(parse(rust, "1_000.5e1").result, Some(NumberLiteral::Float { value: 10005.0, suffix: None }));
+/// assert_eq!(parse(rust, "1f32").result, Some(NumberLiteral::Float { value: 1.0, suffix: Some("f32") }));
+///
+/// let c = number_literal(NumberSyntax::C);
+/// assert_eq!(parse(c, "0755UL").result, Some(NumberLiteral::Integer { value: 0o755, radix: 8, suffix: Some("UL") }));
+/// assert_eq!(parse(c, "08").result, None);
+/// ```
+#[inline]
+pub fn number_literal<'a, 'b, I: ByteInput<'a>, S>(syntax: NumberSyntax<'b>) -> impl Parser<I, NumberLiteral<I>, S> + 'b {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice();
+        let (radix, start) = match bytes {
+            [b'0', b'x' | b'X', ..] => (16, 2),
+            [b'0', b'b' | b'B', ..] => (2, 2),
+            [b'0', b'o' | b'O', ..] if !syntax.leading_zero_octal => (8, 2),
+            _ => (10, 0)
+        };
+
+        let int_end = start + digits_len(bytes, start, radix, syntax.separator);
+        if int_end == start {
+            return None
+        }
+
+        let mut end = int_end;
+        if radix == 10 {
+            match bytes.get(end..end + 2) {
+                Some([b'.', b'0'..=b'9']) => end += 1 + digits_len(bytes, end + 1, 10, syntax.separator),
+                // A trailing dot, unless it's e.g. a range or a method call.
+                _ if bytes.get(end) == Some(&b'.') && !is_ident_byte(bytes.get(end + 1)) && bytes.get(end + 1) != Some(&b'.') => end += 1,
+                _ => {}
+            }
+
+            let exp_start = end + 1 + matches!(bytes.get(end + 1), Some(b'+' | b'-')) as usize;
+            if matches!(bytes.get(end), Some(b'e' | b'E')) && digits_len(bytes, exp_start, 10, syntax.separator) > 0 {
+                end = exp_start + digits_len(bytes, exp_start, 10, syntax.separator);
+            }
+        }
+
+        let float_suffix = (radix == 10).then(|| suffix_len(bytes, end, syntax.float_suffixes, syntax.suffix_ignore_case)).flatten();
+        let int_suffix = (end == int_end).then(|| suffix_len(bytes, end, syntax.integer_suffixes, syntax.suffix_ignore_case)).flatten();
+        let (is_float, suffix_len) = match (int_suffix, float_suffix) {
+            (Some(i), f) if i >= f.unwrap_or(0) => (false, i),
+            (_, Some(f)) => (true, f),
+            _ => (end > int_end, 0)
+        };
+
+        if is_ident_byte(bytes.get(end + suffix_len)) {
+            return None
+        }
+
+        let suffix = (suffix_len > 0).then(|| s.input.slice_to(end + suffix_len).slice_from(end));
+        let number = bytes[start..end].iter().filter(|b| Some(**b) != syntax.separator);
+
+        let literal = if is_float {
+            let mut buf = [0; 64];
+            let mut len = 0;
+            for b in number {
+                *buf.get_mut(len)? = *b;
+                len += 1;
+            }
+            // The buffer only contains ASCII, so it's always valid UTF-8.
+            let value = from_utf8(&buf[..len]).ok()?.parse().ok()?;
+            NumberLiteral::Float { value, suffix }
+        } else {
+            let radix = if syntax.leading_zero_octal && radix == 10 && bytes[0] == b'0' && int_end > 1 { 8 } else { radix };
+            let mut value = 0u128;
+            for b in number {
+                let digit = (*b as char).to_digit(radix)?;
+                value = value.checked_mul(radix as u128)?.checked_add(digit as u128)?;
+            }
+            NumberLiteral::Integer { value, radix, suffix }
+        };
+
+        s.input = s.input.slice_from(end + suffix_len);
+        Some(literal)
+    })
+}
+
+/// The syntax of string literals, as used by [`string_literal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StringSyntax<'b> {
+    /// The quotes that may delimit a string, e.g. `"` and `'`. A string is closed by the
+    /// quote that opened it.
+    pub quotes: &'b [u8],
+
+    /// The quotes of strings that may contain unescaped control characters, e.g. newlines.
+    pub multiline_quotes: &'b [u8],
+
+    /// The byte starting an escape sequence, if any, e.g. `\`.
+    pub escape: Option<u8>,
+
+    /// The single byte escapes and the characters they denote, e.g. `(b'n', '\n')`.
+    pub escapes: &'b [(u8, char)],
+
+    /// Whether `\xHH` escapes are allowed.
+    pub hex_escapes: bool,
+
+    /// Whether `\uHHHH` escapes are allowed. When unescaping, surrogate pairs are combined
+    /// as in JSON.
+    pub unicode_escapes: bool,
+
+    /// Whether `\u{H...}` escapes are allowed.
+    pub braced_unicode_escapes: bool,
+
+    /// The prefix of raw strings, if any, e.g. `r` for Rust's `r"..."` and `r#"..."#`. Raw
+    /// strings have no escapes, and may contain control characters.
+    pub raw_prefix: Option<u8>
+}
+
+impl StringSyntax<'static> {
+    /// JSON strings.
+    pub const JSON: StringSyntax<'static> = StringSyntax {
+        quotes: b"\"",
+        multiline_quotes: b"",
+        escape: Some(b'\\'),
+        escapes: &[(b'"', '"'), (b'\\', '\\'), (b'/', '/'), (b'b', '\x08'), (b'f', '\x0c'),
+                   (b'n', '\n'), (b'r', '\r'), (b't', '\t')],
+        hex_escapes: false,
+        unicode_escapes: true,
+        braced_unicode_escapes: false,
+        raw_prefix: None
+    };
+
+    /// Rust strings, including raw strings.
+    pub const RUST: StringSyntax<'static> = StringSyntax {
+        quotes: b"\"",
+        multiline_quotes: b"\"",
+        escape: Some(b'\\'),
+        escapes: &[(b'"', '"'), (b'\'', '\''), (b'\\', '\\'), (b'0', '\0'), (b'n', '\n'),
+                   (b'r', '\r'), (b't', '\t')],
+        hex_escapes: true,
+        unicode_escapes: false,
+        braced_unicode_escapes: true,
+        raw_prefix: Some(b'r')
+    };
+
+    /// JavaScript strings, including template literals without substitutions.
+    pub const JAVASCRIPT: StringSyntax<'static> = StringSyntax {
+        quotes: b"\"'`",
+        multiline_quotes: b"`",
+        escape: Some(b'\\'),
+        escapes: &[(b'"', '"'), (b'\'', '\''), (b'`', '`'), (b'\\', '\\'), (b'0', '\0'),
+                   (b'b', '\x08'), (b'f', '\x0c'), (b'n', '\n'), (b'r', '\r'), (b't', '\t'),
+                   (b'v', '\x0b'), (b'$', '$')],
+        hex_escapes: true,
+        unicode_escapes: true,
+        braced_unicode_escapes: true,
+        raw_prefix: None
+    };
+}
+
+/// The syntax of the escapes of [`char_literal`].
+const CHAR_SYNTAX: StringSyntax<'static> = StringSyntax {
+    unicode_escapes: true,
+    ..StringSyntax::RUST
+};
+
+/// Parse an escape sequence, i.e. the bytes after the escape byte. Returns the code point,
+/// which is a UTF-16 code unit (possibly a surrogate) for `\uHHHH` escapes, and the length
+/// of the escape sequence.
+fn escape(bytes: &[u8], syntax: &StringSyntax) -> Option<(u32, usize)> {
+    let hex = |digits: &[u8]| {
+        digits.iter().try_fold(0u32, |acc, d| Some(acc * 16 + (*d as char).to_digit(16)?))
+    };
+
+    if let Some((_, c)) = syntax.escapes.iter().find(|(b, _)| Some(b) == bytes.first()) {
+        return Some((*c as u32, 1))
+    }
+
+    match bytes {
+        [b'x', digits @ ..] if syntax.hex_escapes => Some((hex(digits.get(..2)?)?, 3)),
+        [b'u', b'{', rest @ ..] if syntax.braced_unicode_escapes => {
+            let len = rest.iter().take(7).position(|b| *b == b'}').filter(|len| *len > 0)?;
+            let c = char::from_u32(hex(&rest[..len])?)?;
+            Some((c as u32, len + 3))
+        },
+        [b'u', digits @ ..] if syntax.unicode_escapes => Some((hex(digits.get(..4)?)?, 5)),
+        _ => None
+    }
+}
+
+/// Find the string literal at the start of `bytes`. Returns the range of the content, the
+/// end of the literal and whether it's a raw string.
+fn string_span(bytes: &[u8], syntax: &StringSyntax) -> Option<(usize, usize, usize, bool)> {
+    match (bytes.first(), syntax.raw_prefix) {
+        (Some(first), Some(prefix)) if *first == prefix => {
+            let hashes = bytes[1..].iter().take_while(|b| **b == b'#').count();
+            let quote = *bytes.get(1 + hashes).filter(|q| syntax.quotes.contains(q))?;
+            let start = 2 + hashes;
+            let mut pos = start;
+            loop {
+                pos += find(&bytes[pos..], eq(quote))?;
+                if bytes[pos + 1..].iter().take(hashes).filter(|b| **b == b'#').count() == hashes {
+                    return Some((start, pos, pos + 1 + hashes, true))
+                }
+                pos += 1;
+            }
+        },
+        _ => {
+            let quote = *bytes.first().filter(|q| syntax.quotes.contains(q))?;
+            let multiline = syntax.multiline_quotes.contains(&quote);
+            let escape_byte = syntax.escape.unwrap_or(quote);
+            let mut pos = 1;
+            loop {
+                pos += find(&bytes[pos..], eq(quote) | eq(escape_byte) | in_range(0, 0x1f))?;
+                match bytes[pos] {
+                    b if b == quote => return Some((1, pos, pos + 1, false)),
+                    b if b == escape_byte => pos += 1 + escape(&bytes[pos + 1..], syntax)?.1,
+                    _ if multiline => pos += 1,
+                    _ => return None
+                }
+            }
+        }
+    }
+}
+
+/// Create a parser for a string literal with the syntax `syntax`, e.g. `"a\n"` for
+/// [`StringSyntax::JSON`]. The result is the content of the literal, without the quotes and
+/// with the escapes validated but not unescaped. Use [`string_literal_unescaped`] to get
+/// the unescaped content.
+///
+/// ### Consuming
+/// Consumes the literal, on successful parse. The parser fails if the literal is not
+/// closed, on an invalid escape, or on an unescaped control character outside of raw and
+/// multiline strings.
+///
+/// ### Arguments
+/// * `syntax` - the syntax of the literals, e.g. [`StringSyntax::RUST`]
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::literal::{string_literal, StringSyntax};
+///
+/// assert_eq!(parse(string_literal(StringSyntax::JSON), r#""a\"b" c"#).result, Some(r#"a\"b"#));
+/// assert_eq!(parse(string_literal(StringSyntax::RUST), r###"r#"a"b"#"###).result, Some(r#"a"b"#));
+/// assert_eq!(parse(string_literal(StringSyntax::JAVASCRIPT), "'a\\q'").result, None);
+/// ```
+#[inline]
+pub fn string_literal<'a, 'b, I: ByteInput<'a>, S>(syntax: StringSyntax<'b>) -> impl Parser<I, I, S> + 'b {
+    create_parser!(s, {
+        let (start, end, len, _) = string_span(s.input.as_byte_slice(), &syntax)?;
+        let content = s.input.slice_to(end).slice_from(start);
+        s.input = s.input.slice_from(len);
+        Some(content)
+    })
+}
+
+#[cfg(feature = "alloc")]
+/// Unescape the content of a string literal with the syntax `syntax`. Returns `None` on an
+/// invalid escape, or if the result is not valid UTF-8.
+pub(crate) fn unescape(content: &[u8], syntax: &StringSyntax) -> Option<String> {
+    let mut res = String::with_capacity(content.len());
+    let mut pos = 0;
+    while pos < content.len() {
+        let next = match syntax.escape {
+            Some(escape_byte) => pos + find(&content[pos..], eq(escape_byte)).unwrap_or(content.len() - pos),
+            None => content.len()
+        };
+        res.push_str(from_utf8(&content[pos..next]).ok()?);
+        if next == content.len() {
+            break
+        }
+
+        let (mut code, escape_len) = escape(&content[next + 1..], syntax)?;
+        pos = next + 1 + escape_len;
+        if (0xd800..0xdc00).contains(&code) && content.get(pos) == syntax.escape.as_ref() {
+            let (low, low_len) = escape(&content[pos + 1..], syntax).filter(|(low, _)| (0xdc00..0xe000).contains(low))?;
+            code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+            pos += 1 + low_len;
+        }
+        res.push(char::from_u32(code)?);
+    }
+    Some(res)
+}
+
+#[cfg(feature = "alloc")]
+/// Create a parser for a string literal with the syntax `syntax`, returning the unescaped
+/// content. See [`string_literal`].
+///
+/// ### Consuming
+/// Consumes the literal, on successful parse. The parser fails in the same cases as
+/// [`string_literal`], and if the content is not valid UTF-8 after unescaping, e.g. on an
+/// unpaired surrogate.
+///
+/// ### Arguments
+/// * `syntax` - the syntax of the literals, e.g. [`StringSyntax::RUST`]
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::literal::{string_literal_unescaped, StringSyntax};
+///
+/// let json = string_literal_unescaped(StringSyntax::JSON);
+/// assert_eq!(parse(json, r#""a\tb 🦀""#).result.as_deref(), Some("a\tb 🦀"));
+/// assert_eq!(parse(json, r#""\ud83e""#).result, None);
+/// ```
+#[inline]
+pub fn string_literal_unescaped<'a, 'b, I: ByteInput<'a>, S>(syntax: StringSyntax<'b>) -> impl Parser<I, String, S> + 'b {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice();
+        let (start, end, len, raw) = string_span(bytes, &syntax)?;
+        let content = &bytes[start..end];
+
+        let res = match raw {
+            true => String::from(from_utf8(content).ok()?),
+            false => unescape(content, &syntax)?
+        };
+
+        s.input = s.input.slice_from(len);
+        Some(res)
+    })
+}
+
+/// Get the first character of `bytes`, if it's valid UTF-8.
+fn first_char(bytes: &[u8]) -> Option<(char, usize)> {
+    let len = match bytes.first()? {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        _ => 4
+    };
+    from_utf8(bytes.get(..len)?).ok()?.chars().next().map(|c| (c, len))
+}
+
+/// Create a parser for a character literal, e.g. `'a'`, `'\n'`, `'\x41'` or `'\u{1F980}'`.
+/// The supported escapes are `\n`, `\r`, `\t`, `\0`, `\\`, `\'`, `\"`, `\xHH`, `\uHHHH` and
+/// `\u{H...}`.
+///
+/// ### Consuming
+/// Consumes the literal, on successful parse. The parser fails if the literal doesn't
+/// contain exactly one character, or on an invalid escape.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::literal::char_literal;
+///
+/// assert_eq!(parse(char_literal(), "'ö'").result, Some('ö'));
+/// assert_eq!(parse(char_literal(), r"'\u{1F980}'").result, Some('🦀'));
+/// assert_eq!(parse(char_literal(), "'ab'").result, None);
+/// ```
+#[inline]
+pub fn char_literal<'a, I: ByteInput<'a>, S>() -> impl Parser<I, char, S> {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice().strip_prefix(b"'")?;
+        let (c, len) = match bytes {
+            [b'\\', rest @ ..] => escape(rest, &CHAR_SYNTAX).and_then(|(c, len)| Some((char::from_u32(c)?, len + 1)))?,
+            [b'\'' | b'\n', ..] => return None,
+            _ => first_char(bytes)?
+        };
+
+        if bytes.get(len) != Some(&b'\'') {
+            return None
+        }
+
+        s.input = s.input.slice_from(len + 2);
+        Some(c)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{core::parse, literal::{char_literal, number_literal, string_literal, NumberLiteral::*, NumberSyntax, StringSyntax}};
+
+    #[test]
+    fn numbers() {
+        let rust = number_literal(NumberSyntax::RUST);
+        let int = |value, radix, suffix| Some(Integer { value, radix, suffix });
+        assert_eq!(parse(rust, "0b1010_1010").result, int(0b1010_1010, 2, None));
+        assert_eq!(parse(rust, "0o17i8").result, int(0o17, 8, Some("i8")));
+        assert_eq!(parse(rust, "0xDEAD_beef_usize").result, int(0xdead_beef, 16, Some("usize")));
+        assert_eq!(parse(rust, "007").result, int(7, 10, None));
+        assert_eq!(parse(rust, "340282366920938463463374607431768211455").result, int(u128::MAX, 10, None));
+        assert_eq!(parse(rust, "1e3").result, Some(Float { value: 1e3, suffix: None }));
+        assert_eq!(parse(rust, "2.5E-1_f64").result, Some(Float { value: 0.25, suffix: Some("f64") }));
+
+        let res = parse(rust, "1..2");
+        assert_eq!((res.result, res.state), (int(1, 10, None), "..2"));
+        let res = parse(rust, "1.max(2)");
+        assert_eq!((res.result, res.state), (int(1, 10, None), ".max(2)"));
+        let res = parse(rust, "1. + 2");
+        assert_eq!((res.result, res.state), (Some(Float { value: 1.0, suffix: None }), " + 2"));
+        let res = parse(rust, "3e+x");
+        assert_eq!(res.result, None);
+
+        let c = number_literal(NumberSyntax::C);
+        assert_eq!(parse(c, "0").result, int(0, 10, None));
+        assert_eq!(parse(c, "1'000'000llu").result, int(1_000_000, 10, Some("llu")));
+        assert_eq!(parse(c, "10L").result, int(10, 10, Some("L")));
+        assert_eq!(parse(c, "0.5F").result, Some(Float { value: 0.5, suffix: Some("F") }));
+        assert_eq!(parse(c, "09.5").result, Some(Float { value: 9.5, suffix: None }));
+
+        for invalid in ["", "x", "0x", "0xg", "1u7", "0b2", "1a", "340282366920938463463374607431768211456"] {
+            assert_eq!(parse(rust, invalid).result, None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn strings() {
+        let res = parse(string_literal(StringSyntax::JSON), r#""é\/" x"#);
+        assert_eq!((res.result, res.state), (Some(r#"é\/"#), " x"));
+        assert_eq!(parse(string_literal(StringSyntax::RUST), br#"r"a\""#.as_slice()).result, Some(&b"a\\"[..]));
+        assert_eq!(parse(string_literal(StringSyntax::RUST), r###"r##"a"#"##"###).result, Some(r##"a"#"##));
+        assert_eq!(parse(string_literal(StringSyntax::JAVASCRIPT), "`a\nb`").result, Some("a\nb"));
+
+        for invalid in ["", "\"", "\"a", "'a'", r#""\x41""#, "\"a\nb\"", r#""\u12""#, "r\"", "r#\"\""] {
+            let syntax = if invalid.starts_with('r') { StringSyntax::RUST } else { StringSyntax::JSON };
+            assert_eq!(parse(string_literal(syntax), invalid).result, None, "{}", invalid);
+        }
+
+        #[cfg(feature = "alloc")]
+        {
+            use crate::literal::string_literal_unescaped;
+            let unescape = |syntax, input| parse(string_literal_unescaped(syntax), input).result;
+            assert_eq!(unescape(StringSyntax::JSON, r#""🦀\"""#).as_deref(), Some("🦀\""));
+            assert_eq!(unescape(StringSyntax::JSON, r#""\udd80""#), None);
+            assert_eq!(unescape(StringSyntax::RUST, r#""\x41\u{e9}\0""#).as_deref(), Some("Aé\0"));
+            assert_eq!(unescape(StringSyntax::RUST, r#"r"\n""#).as_deref(), Some("\\n"));
+            assert_eq!(unescape(StringSyntax::JAVASCRIPT, r"'A\v'").as_deref(), Some("A\x0b"));
+            assert_eq!(parse(string_literal_unescaped(StringSyntax::JSON), b"\"\xff\"".as_slice()).result, None);
+        }
+    }
+
+    #[test]
+    fn chars() {
+        let res = parse(char_literal(), r"'\''x");
+        assert_eq!((res.result, res.state), (Some('\''), "x"));
+
+        for (input, expected) in [(r"'\n'", '\n'), (r"'\x7f'", '\x7f'), (r"'é'", 'é'), ("'\"'", '"')] {
+            assert_eq!(parse(char_literal(), input.as_bytes()).result, Some(expected), "{}", input);
+        }
+
+        for invalid in ["''", "'''", "'\n'", "'a", r"'\q'", r"'\u{}'", r"'\u{110000}'", r"'\x4'"] {
+            assert_eq!(parse(char_literal(), invalid).result, None, "{}", invalid);
+        }
+    }
+}
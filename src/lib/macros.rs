@@ -6,7 +6,7 @@
 #[macro_export]
 macro_rules! create_parser {
     ($state:ident, $f:expr) => {
-        move |$state: &mut $crate::core::AnpaState<_, _>| $f
+        (move |$state: &mut $crate::core::AnpaState<_, _>| $f)
     }
 }
 
@@ -28,7 +28,7 @@ macro_rules! defer_parser {
 /// Variadic version of `map`, where all provided parsers must succeed.
 /// ### Arguments
 /// * `f` - the transformation function. Its arguments must match the result types of `p...` in
-///         both type and number.
+///   both type and number.
 /// * `p...` - any number of parsers.
 #[macro_export]
 macro_rules! map {
@@ -40,7 +40,7 @@ macro_rules! map {
 /// Variadic version of `map_if`, where all provided parsers must succeed.
 /// ### Arguments
 /// * `f` - the transformation function. Its arguments must match the result types of `p...` in
-///         both type and number.
+///   both type and number.
 /// * `p...` - any number of parsers.
 #[macro_export]
 macro_rules! map_if {
@@ -49,6 +49,44 @@ macro_rules! map_if {
     };
 }
 
+/// Variant of `map!` that constructs a struct with named fields from the results, to avoid
+/// mixing up the positions of the results for structs with many fields of the same type,
+/// e.g. binary headers.
+///
+/// The parsers are applied in the order of the fields, and the number of fields must match
+/// the number of parsers.
+///
+/// ### Arguments
+/// * `T { field... }` - the path of the struct, without generic arguments, and the names of
+///   the fields to assign the results to.
+/// * `p...` - a parser for each field.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::map_struct;
+/// use anpa::parsers::{item, skip};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Header { magic: u8, version: u8, flags: u8 }
+///
+/// let byte = item().map(|b: &u8| *b);
+/// let p = map_struct!(Header { magic, version, flags }, byte, byte, byte);
+///
+/// let input: &[u8] = &[0x7f, 2, 0b101];
+///
+/// assert_eq!(parse(p, input).result, Some(Header { magic: 0x7f, version: 2, flags: 0b101 }));
+/// ```
+#[macro_export]
+macro_rules! map_struct {
+    ($($t:ident)::+ { $($field:ident),* $(,)? }, $($p:expr),* $(,)?) => {
+        $crate::create_parser!(s, {
+            $(let $field = $p(s)?;)*
+            Some($($t)::+ { $($field),* })
+        })
+    };
+}
+
 /// Convert a number of parsers to a single parser producing a tuple with all the results.
 /// ### Arguments
 /// * `p...` - any number of parsers.
@@ -59,6 +97,48 @@ macro_rules! tuplify {
     };
 }
 
+/// Convert a number of parsers to a single parser producing a tuple with the results
+/// of all parsers not marked with `_:`. The marked parsers must still succeed, but
+/// their results are ignored.
+///
+/// If only one result is kept, it will be returned as is, without a tuple.
+///
+/// ### Arguments
+/// * `p...` - any number of parsers, optionally prefixed with `_:` to ignore the result.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::seq;
+/// use anpa::number::integer;
+/// use anpa::parsers::{item_while, skip};
+/// use anpa::whitespace::skip_ascii_whitespace;
+///
+/// let ident = item_while(|c: char| c.is_alphabetic());
+/// let ws = skip_ascii_whitespace();
+/// let p = seq!(ident, _: ws, _: skip('='), _: ws, integer());
+///
+/// let input = "x = 123";
+/// assert_eq!(parse(p, input).result, Some(("x", 123)));
+/// ```
+#[macro_export]
+macro_rules! seq {
+    (@parse $s:ident, [$($res:ident)*]) => {
+        Some(($($res),*))
+    };
+    (@parse $s:ident, [$($res:ident)*] _ : $p:expr $(, $($rest:tt)*)?) => {{
+        $p($s)?;
+        $crate::seq!(@parse $s, [$($res)*] $($($rest)*)?)
+    }};
+    (@parse $s:ident, [$($res:ident)*] $p:expr $(, $($rest:tt)*)?) => {{
+        let res = $p($s)?;
+        $crate::seq!(@parse $s, [$($res)* res] $($($rest)*)?)
+    }};
+    ($($p:tt)*) => {
+        $crate::create_parser!(s, $crate::seq!(@parse s, [] $($p)*))
+    };
+}
+
 /// Create a parser that successfully returns `x`.
 ///
 /// ### Arguments
@@ -227,11 +307,228 @@ macro_rules! greedy_or {
     };
 }
 
+/// Variant of `greedy_or!` that also returns how much input was consumed by the chosen
+/// parser, as `(consumed, result)`. This is useful for maximal munch tokenization.
+///
+/// If several parsers consume the same amount of input, the leftmost of them is chosen.
+///
+/// Note: For `&str`, the number of bytes consumed will be returned
+///
+/// ### Arguments
+/// * `p...` - any number of parsers.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::longest_of;
+/// use anpa::parsers::{item_while, take};
+///
+/// let keyword = take("let").map(|_| "keyword");
+/// let ident = item_while(|c: char| c.is_alphanumeric()).map(|_| "identifier");
+/// let p = longest_of!(keyword, ident);
+///
+/// assert_eq!(parse(p, "let x").result, Some((3, "keyword")));
+/// assert_eq!(parse(p, "letter").result, Some((6, "identifier")));
+/// ```
+#[macro_export]
+macro_rules! longest_of {
+    ($($p:expr),* $(,)?) => {
+        $crate::combinators::count_consumed($crate::greedy_or!($($p),*))
+    };
+}
+
+/// Create a parser that parses a single item matching a `char` pattern, returning the item.
+/// The pattern can be anything accepted by `matches!`, including alternatives with `|`,
+/// ranges and guards.
+///
+/// Any input with items implementing [`CharLike`](crate::charlike::CharLike) can be parsed,
+/// e.g. `&str` and `&[u8]`, since the items are converted to `char` before matching.
+///
+/// ### Consuming
+/// The matching item on successful parse.
+///
+/// ### Arguments
+/// * `pattern` - the pattern to match
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::item_matching;
+///
+/// let sign = item_matching!('+' | '-');
+/// let hex = item_matching!('0'..='9' | 'a'..='f' | 'A'..='F');
+/// let upper = item_matching!(c if c.is_uppercase());
+///
+/// assert_eq!(parse(sign, "-1").result, Some('-'));
+/// assert_eq!(parse(hex, b"f0".as_slice()).result, Some(&b'f'));
+/// assert_eq!(parse(upper, "Ärger").result, Some('Ä'));
+/// assert_eq!(parse(upper, "ärger").result, None);
+/// ```
+#[macro_export]
+macro_rules! item_matching {
+    ($($pattern:tt)+) => {
+        $crate::parsers::item_if(|c| matches!($crate::charlike::CharLike::as_char(c), $($pattern)+))
+    };
+}
+
+/// Create a parser from a small regex-like pattern, returning the parsed input.
+///
+/// The pattern is a sequence of elements, where each element is either:
+/// * A character class, i.e. a `char` pattern in brackets, e.g. `['a'..='z' | '_']`.
+///   A class is negated with `^`, e.g. `[^ '"' | '\\']`.
+/// * A literal to be parsed as with [`skip`](crate::parsers::skip), e.g. `"::"` or `','`.
+///
+/// A character class can be followed by `*` (zero or more), `+` (one or more) or `?`
+/// (zero or one). Repetitions are greedy and never backtrack.
+///
+/// Since the classes are ordinary patterns, they are compiled to plain comparisons.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::pat;
+///
+/// let ident = pat!(['a'..='z' | 'A'..='Z' | '_'] ['a'..='z' | 'A'..='Z' | '0'..='9' | '_']*);
+/// let float = pat!(['-']? ['0'..='9']+ '.' ['0'..='9']+);
+///
+/// assert_eq!(parse(ident, "foo_1 = 2").result, Some("foo_1"));
+/// assert_eq!(parse(float, "-1.25e3").result, Some("-1.25"));
+/// ```
+#[macro_export]
+macro_rules! pat {
+    (@pred ^ $($c:tt)*) => {
+        |c: char| !matches!(c, $($c)*)
+    };
+    (@pred $($c:tt)*) => {
+        |c: char| matches!(c, $($c)*)
+    };
+    (@acc [$($e:expr),*]) => {
+        $crate::combinators::get_parsed($crate::right!($($e),*))
+    };
+    (@acc [$($e:expr),*] [$($c:tt)*] * $($rest:tt)*) => {
+        $crate::pat!(@acc [$($e,)* $crate::pattern::class_star($crate::pat!(@pred $($c)*))] $($rest)*)
+    };
+    (@acc [$($e:expr),*] [$($c:tt)*] + $($rest:tt)*) => {
+        $crate::pat!(@acc [$($e,)* $crate::pattern::class_plus($crate::pat!(@pred $($c)*))] $($rest)*)
+    };
+    (@acc [$($e:expr),*] [$($c:tt)*] ? $($rest:tt)*) => {
+        $crate::pat!(@acc [$($e,)* $crate::pattern::class_opt($crate::pat!(@pred $($c)*))] $($rest)*)
+    };
+    (@acc [$($e:expr),*] [$($c:tt)*] $($rest:tt)*) => {
+        $crate::pat!(@acc [$($e,)* $crate::pattern::class_one($crate::pat!(@pred $($c)*))] $($rest)*)
+    };
+    (@acc [$($e:expr),*] $lit:literal $($rest:tt)*) => {
+        $crate::pat!(@acc [$($e,)* $crate::parsers::skip($lit)] $($rest)*)
+    };
+    ($($p:tt)+) => {
+        $crate::pat!(@acc [] $($p)+)
+    };
+}
+
+/// Define a grammar of named rules using an EBNF-like notation, for rapid prototyping.
+///
+/// Each rule `name = ...;` expands into a function `name()` returning a parser for `&str`
+/// that returns the parsed input. Rules can reference each other (also recursively) by name.
+///
+/// A rule is a sequence of elements, where alternatives are separated by `|`. An element is:
+/// * A literal to be parsed as with [`skip`](crate::parsers::skip), e.g. `"let"` or `'('`.
+/// * A character class as in [`pat!`], e.g. `['a'..='z' | '_']` or `[^ '"']`.
+/// * The name of a rule.
+/// * A group of elements in parentheses.
+///
+/// Any element can be followed by `*` (zero or more), `+` (one or more) or `?` (zero or one).
+///
+/// The grammar is interpreted as a PEG, i.e. alternatives are tried in order and the first
+/// successful one is used, and repetitions are greedy. A failed repetition or optional
+/// element doesn't consume any input.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::grammar;
+///
+/// grammar! {
+///     pub expr = term (['+' | '-'] term)*;
+///     term = factor (['*' | '/'] factor)*;
+///     factor = ['0'..='9']+ | '(' expr ')';
+/// }
+///
+/// assert_eq!(parse(expr(), "(1+2)*3-4 rest").result, Some("(1+2)*3-4"));
+/// assert_eq!(parse(expr(), "(1+2").result, None);
+/// ```
+#[macro_export]
+macro_rules! grammar {
+    () => {};
+
+    // Collect the tokens of a rule until `;`.
+    (@rule [$(#[$m:meta])* $vis:vis $name:ident] [$($body:tt)*] ; $($rest:tt)*) => {
+        $(#[$m])*
+        $vis fn $name<'a, S>() -> impl $crate::core::StrParser<'a, &'a str, S> {
+            $crate::combinators::get_parsed($crate::grammar!(@alt [] [] $($body)*))
+        }
+
+        $crate::grammar!($($rest)*);
+    };
+    (@rule $header:tt [$($body:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::grammar!(@rule $header [$($body)* $t] $($rest)*);
+    };
+
+    // Split a rule into alternatives, each a sequence of elements.
+    (@alt [$($seq:expr),*] [$($alts:expr),*]) => {
+        $crate::or!($($alts,)* $crate::right!($($seq),*))
+    };
+    (@alt [$($seq:expr),*] [$($alts:expr),*] | $($rest:tt)*) => {
+        $crate::grammar!(@alt [] [$($alts,)* $crate::right!($($seq),*)] $($rest)*)
+    };
+    (@alt $seq:tt $alts:tt $($rest:tt)+) => {
+        $crate::grammar!(@elem $seq $alts $($rest)+)
+    };
+
+    // Parse a single element.
+    (@elem $seq:tt $alts:tt ($($g:tt)*) $($rest:tt)*) => {
+        $crate::grammar!(@op $seq $alts [$crate::grammar!(@alt [] [] $($g)*)] $($rest)*)
+    };
+    (@elem $seq:tt $alts:tt [$($c:tt)*] $($rest:tt)*) => {
+        $crate::grammar!(@op $seq $alts [$crate::pattern::class_one($crate::pat!(@pred $($c)*))] $($rest)*)
+    };
+    (@elem $seq:tt $alts:tt $lit:literal $($rest:tt)*) => {
+        $crate::grammar!(@op $seq $alts [$crate::parsers::skip($lit)] $($rest)*)
+    };
+    (@elem $seq:tt $alts:tt $rule:ident $($rest:tt)*) => {
+        $crate::grammar!(@op $seq $alts [$crate::defer_parser!($rule())] $($rest)*)
+    };
+
+    // Apply an optional repetition to an element.
+    (@op [$($seq:expr),*] $alts:tt [$p:expr] * $($rest:tt)*) => {
+        $crate::grammar!(@alt [$($seq,)* $crate::core::ParserExt::void(
+            $crate::combinators::many($crate::combinators::attempt($p), true, $crate::combinators::no_separator()))] $alts $($rest)*)
+    };
+    (@op [$($seq:expr),*] $alts:tt [$p:expr] + $($rest:tt)*) => {
+        $crate::grammar!(@alt [$($seq,)* $crate::core::ParserExt::void(
+            $crate::combinators::many($crate::combinators::attempt($p), false, $crate::combinators::no_separator()))] $alts $($rest)*)
+    };
+    (@op [$($seq:expr),*] $alts:tt [$p:expr] ? $($rest:tt)*) => {
+        $crate::grammar!(@alt [$($seq,)* $crate::core::ParserExt::void(
+            $crate::combinators::succeed($crate::combinators::attempt($p)))] $alts $($rest)*)
+    };
+    (@op [$($seq:expr),*] $alts:tt [$p:expr] $($rest:tt)*) => {
+        $crate::grammar!(@alt [$($seq,)* $crate::core::ParserExt::void($p)] $alts $($rest)*)
+    };
+
+    ($(#[$m:meta])* $vis:vis $name:ident = $($rest:tt)*) => {
+        $crate::grammar!(@rule [$(#[$m])* $vis $name] [] $($rest)*);
+    };
+}
+
 /// Create a parser that takes the result of a parser, and returns different
 /// parsers depending on the provided conditions.
 ///
 /// If none of the provided conditions match, the parser will fail.
 ///
+/// Alternatively, the result can be matched against `match`-style patterns with optional
+/// guards, including a default branch `_ => parser`. Variables bound by the patterns can
+/// be used in the parsers.
+///
 /// ### Example:
 /// ```
 /// use anpa::core::*;
@@ -256,8 +553,37 @@ macro_rules! greedy_or {
 /// assert_eq!(parse(p, input4).result, None);
 /// assert_eq!(parse(p, input5).result, None);
 /// ```
+///
+/// ### Example with patterns:
+/// ```
+/// use anpa::core::*;
+/// use anpa::choose;
+/// use anpa::parsers::{item, take};
+///
+/// let p = choose!(item() => match: char {
+///     'a' | 'b' => take("ab"),
+///     c if c.is_ascii_digit() => take("digit"),
+///     _ => take("other")
+/// });
+///
+/// assert_eq!(parse(p, "aab").result, Some("ab"));
+/// assert_eq!(parse(p, "1digit").result, Some("digit"));
+/// assert_eq!(parse(p, "xother").result, Some("other"));
+/// assert_eq!(parse(p, "xab").result, None);
+/// ```
 #[macro_export]
 macro_rules! choose {
+    ($p:expr => match $(: $t:ty)? { $($($pat:pat_param)|+ $(if $guard:expr)? => $new_p:expr),* $(,)? }) => {
+        $crate::create_parser!(s, {
+            let res $(: $t)? = $p(s)?;
+
+            match res {
+                $($($pat)|+ $(if $guard)? => $new_p(s),)*
+                #[allow(unreachable_patterns)]
+                _ => None
+            }
+        })
+    };
     ($p:expr => $res:ident $(: $t:ty)?; $($cond:expr => $new_p:expr),* $(,)?) => {
         $crate::create_parser!(s, {
             let $res $(:$t)? = $p(s)?;
@@ -271,6 +597,190 @@ macro_rules! choose {
     };
 }
 
+/// Create a parser that matches the longest of a set of keywords or operators, and returns
+/// the value for the matched keyword. The keywords are compiled into a
+/// [`Trie`](crate::keywords::Trie) at compile time, so the input is only traversed once,
+/// instead of trying each keyword in turn as with e.g. [`or!`]. For a handful of short
+/// keywords, an [`or!`] of [`take`](crate::parsers::take) may still be faster, but then the
+/// longer keywords must be listed first.
+///
+/// The values must be `Copy`, and the input must be `&str` or `&[u8]`.
+///
+/// Note that a keyword will match the start of a longer word, e.g. `let` in `letter`. Check
+/// what follows the keyword if this is not desired.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::keywords;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Tok { Let, Fn, Lt, Le, Shl }
+///
+/// let p = keywords! {
+///     "let" => Tok::Let,
+///     "fn" => Tok::Fn,
+///     "<" => Tok::Lt,
+///     "<=" => Tok::Le,
+///     "<<" => Tok::Shl,
+/// };
+///
+/// assert_eq!(parse(p, "fn main").result, Some(Tok::Fn));
+///
+/// let res = parse(p, "<= 2");
+/// assert_eq!(res.result, Some(Tok::Le));
+/// assert_eq!(res.state, " 2");
+/// ```
+#[macro_export]
+macro_rules! keywords {
+    ($($key:literal => $value:expr),+ $(,)?) => {{
+        const KEYS: &[&str] = &[$($key),+];
+        const TRIE: $crate::keywords::Trie<{ $crate::keywords::node_count(KEYS) }> =
+            $crate::keywords::Trie::new(KEYS);
+        $crate::keywords::keywords(&TRIE, [$($value),+])
+    }};
+}
+
+/// Create a parser that dispatches to different parsers depending on the first item of the
+/// input, which is matched as a `char` against `match`-style patterns with optional guards.
+/// The first item is not consumed before applying the chosen parser.
+///
+/// This is faster than trying each alternative in order with e.g. [`or!`], when the
+/// alternatives can be distinguished by their first item.
+///
+/// The parser fails if no pattern matches, unless a default branch `_ => parser` is provided.
+/// It always fails on empty input, also with a default branch, since there is no item to
+/// match.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::{dispatch, pure};
+/// use anpa::number::integer;
+/// use anpa::parsers::{skip, until};
+/// use anpa::combinators::right;
+///
+/// let string = right(skip('"'), until('"'));
+/// let number = integer().map(|_: u32| "number");
+/// let p = dispatch!('"' => string,
+///                   '0'..='9' => number);
+///
+/// assert_eq!(parse(p, "\"abc\"").result, Some("abc"));
+/// assert_eq!(parse(p, "123").result, Some("number"));
+/// assert_eq!(parse(p, "x").result, None);
+///
+/// let with_default = dispatch!('0'..='9' => number,
+///                              _ => pure!("other"));
+/// assert_eq!(parse(with_default, "x").result, Some("other"));
+/// assert_eq!(parse(with_default, "").result, None);
+/// ```
+#[macro_export]
+macro_rules! dispatch {
+    ($($($pat:pat_param)|+ $(if $guard:expr)? => $p:expr),* $(,)?) => {
+        $crate::create_parser!(s, {
+            let first = $crate::charlike::CharLike::as_char(
+                $crate::combinators::peek($crate::parsers::item())(s)?);
+
+            match first {
+                $($($pat)|+ $(if $guard)? => $p(s),)*
+                #[allow(unreachable_patterns)]
+                _ => None
+            }
+        })
+    };
+}
+
+/// Implement `FromStr` for a type using a parser, which must consume the whole input. The
+/// error type is [`FromStrError`](crate::core::FromStrError).
+///
+/// ### Arguments
+/// * `t` - the type to implement `FromStr` for.
+/// * `p` - a parser for `&str` producing a `t`.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::{impl_from_str, seq};
+/// use anpa::combinators::labelled;
+/// use anpa::number::integer;
+/// use anpa::parsers::skip;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Point { x: u32, y: u32 }
+///
+/// impl_from_str!(Point, seq!(integer(), _: labelled(skip(','), "','"), integer())
+///     .map(|(x, y)| Point { x, y }));
+///
+/// assert_eq!("1,2".parse(), Ok(Point { x: 1, y: 2 }));
+///
+/// let err = "1;2".parse::<Point>().unwrap_err();
+/// assert_eq!((err.kind, err.offset, err.found), (AnpaErrorKind::Failed, 1, Some(';')));
+/// assert_eq!(err.to_string(), "expected ',', found ';' at offset 1");
+///
+/// let err = "1,2,".parse::<Point>().unwrap_err();
+/// assert_eq!((err.kind, err.offset), (AnpaErrorKind::Incomplete, 3));
+/// ```
+#[macro_export]
+macro_rules! impl_from_str {
+    ($t:ty, $p:expr) => {
+        impl ::core::str::FromStr for $t {
+            type Err = $crate::core::FromStrError;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                $crate::core::parse_all($p, s).map_err(::core::convert::From::from)
+            }
+        }
+    };
+}
+
+/// Parse a string literal at compile time using a `const fn` returning an `Option`, e.g.
+/// [`semver::parse_const`](crate::semver::parse_const), and get the result. The compilation
+/// fails if the function returns `None`. Use [`ConstCursor`](crate::const_parse::ConstCursor)
+/// to write such functions.
+///
+/// ### Arguments
+/// * `f` - a `const fn(&str) -> Option<T>`.
+/// * `lit` - the string literal to parse.
+///
+/// ### Example
+/// ```
+/// use anpa::const_parse;
+/// use anpa::const_parse::ConstCursor;
+/// use anpa::findbyte::{byteset, ByteSet};
+///
+/// const HEX: ByteSet = byteset(b"0123456789abcdefABCDEF");
+///
+/// const fn uuid(text: &str) -> Option<&str> {
+///     let c = ConstCursor::new(text).repeat(HEX, 8, 8).byte(b'-').repeat(HEX, 4, 4).byte(b'-')
+///         .repeat(HEX, 4, 4).byte(b'-').repeat(HEX, 4, 4).byte(b'-').repeat(HEX, 12, 12);
+///     if c.is_done() { Some(text) } else { None }
+/// }
+///
+/// let id = const_parse!(uuid, "67e55044-10b1-426f-9247-bb680e5fe0c8");
+/// assert_eq!(id.len(), 36);
+/// ```
+///
+/// An invalid literal is a compile error:
+/// ```compile_fail
+/// # use anpa::const_parse;
+/// const fn non_empty(text: &str) -> Option<&str> {
+///     if text.is_empty() { None } else { Some(text) }
+/// }
+///
+/// let text = const_parse!(non_empty, "");
+/// ```
+#[macro_export]
+macro_rules! const_parse {
+    ($f:expr, $lit:literal) => {
+        const {
+            match $f($lit) {
+                ::core::option::Option::Some(value) => value,
+                ::core::option::Option::None => ::core::panic!(::core::concat!("invalid literal: ", $lit))
+            }
+        }
+    };
+}
+
 /// Create a new parser trait with a concrete input type for cleaner APIs.
 /// ### Arguments
 /// * `id` - The identifier of the new trait
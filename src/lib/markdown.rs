@@ -0,0 +1,356 @@
+use crate::{core::Parser, findbyte::{byteset, eq, find, ByteSet, ByteInput}};
+
+/// An inline element of Markdown text, as produced by [`inline_parser`].
+///
+/// The content of emphasis, links and images may contain further inline elements, which can
+/// be tokenized by parsing the content again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inline<I> {
+    /// Plain text. An escaped character, e.g. `\*`, is returned as text by itself.
+    Text(I),
+
+    /// The content of a code span, e.g. `` `code` ``.
+    Code(I),
+
+    /// The content of emphasis, e.g. `*text*` or `_text_`.
+    Emphasis(I),
+
+    /// The content of strong emphasis, e.g. `**text**` or `__text__`.
+    Strong(I),
+
+    /// A link, e.g. `[text](url "title")`.
+    Link { text: I, url: I, title: Option<I> },
+
+    /// An image, e.g. `![alt](url "title")`.
+    Image { alt: I, url: I, title: Option<I> },
+
+    /// The URL or email address of an autolink, e.g. `<https://example.com>`.
+    Autolink(I),
+
+    /// A hard line break, i.e. a newline preceded by two spaces or a backslash.
+    LineBreak
+}
+
+/// The bytes that may start an inline element.
+const SPECIAL: ByteSet = byteset(b"\\`*_[!<\n ");
+
+/// The bytes that need to be skipped when looking for the end of emphasis.
+const EMPHASIS_SPECIAL: ByteSet = byteset(b"\\`*_");
+
+fn is_whitespace(byte: Option<u8>) -> bool {
+    byte.map_or(true, |b| b.is_ascii_whitespace())
+}
+
+fn is_punctuation(byte: Option<u8>) -> bool {
+    byte.is_some_and(|b| b.is_ascii_punctuation())
+}
+
+/// Get the length of the run of the byte at `pos`.
+fn run_len(bytes: &[u8], pos: usize) -> usize {
+    bytes[pos..].iter().take_while(|b| **b == bytes[pos]).count()
+}
+
+/// Check if the delimiter run `bytes[start..end]` can open and close emphasis, as specified
+/// by CommonMark. Non-ASCII characters are treated as letters.
+fn flanking(bytes: &[u8], start: usize, end: usize) -> (bool, bool) {
+    let prev = start.checked_sub(1).map(|i| bytes[i]);
+    let next = bytes.get(end).copied();
+    let left = !is_whitespace(next) && (!is_punctuation(next) || is_whitespace(prev) || is_punctuation(prev));
+    let right = !is_whitespace(prev) && (!is_punctuation(prev) || is_whitespace(next) || is_punctuation(next));
+
+    match bytes[start] {
+        b'_' => (left && (!right || is_punctuation(prev)), right && (!left || is_punctuation(next))),
+        _ => (left, right)
+    }
+}
+
+/// Find the end of the code span starting at `pos`. Returns the end of the content and of
+/// the code span.
+fn code_span(bytes: &[u8], pos: usize) -> Option<(usize, usize)> {
+    let n = run_len(bytes, pos);
+    let mut i = pos + n;
+    loop {
+        i += find(&bytes[i..], eq(b'`'))?;
+        let len = run_len(bytes, i);
+        if len == n {
+            return Some((i, i + len))
+        }
+        i += len;
+    }
+}
+
+/// Find the end of the emphasis opened by the delimiter run at `pos`. Returns the range of
+/// the content and the end of the emphasis.
+fn emphasis(bytes: &[u8], pos: usize) -> Option<(usize, usize, usize)> {
+    let n = run_len(bytes, pos);
+    if !flanking(bytes, pos, pos + n).0 {
+        return None
+    }
+
+    let marker = bytes[pos];
+    let needed = n.min(2);
+    let mut depth = 0;
+    let mut i = pos + n;
+
+    loop {
+        i += find(&bytes[i..], EMPHASIS_SPECIAL)?;
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'`' => i = code_span(bytes, i).map_or(i + run_len(bytes, i), |(_, end)| end),
+            b if b == marker => {
+                let len = run_len(bytes, i);
+                let (open, close) = flanking(bytes, i, i + len);
+                if close && depth == 0 && len >= needed {
+                    // Any extra delimiters belong to nested emphasis, or are left as text.
+                    let used = len.min(n);
+                    return Some((pos + needed, i + used - needed, i + used))
+                } else if close && depth > 0 {
+                    depth -= 1;
+                } else if open {
+                    depth += 1;
+                }
+                i += len;
+            },
+            _ => i += 1
+        }
+        i = i.min(bytes.len());
+    }
+}
+
+/// Find the `]` matching the `[` at `pos`.
+fn closing_bracket(bytes: &[u8], pos: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = pos;
+    loop {
+        match bytes.get(i)? {
+            b'\\' => i += 1,
+            b'`' => i = code_span(bytes, i).map_or(i + run_len(bytes, i), |(_, end)| end) - 1,
+            b'[' => depth += 1,
+            b']' if depth == 1 => return Some(i),
+            b']' => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+fn skip_spaces(bytes: &[u8], pos: usize) -> usize {
+    pos + bytes[pos..].iter().take_while(|b| matches!(b, b' ' | b'\t' | b'\n')).count()
+}
+
+/// Parse the destination and title of a link, e.g. `(url "title")`, starting at the `(` at
+/// `pos`. Returns the ranges of the URL and title, and the end of the link.
+#[allow(clippy::type_complexity)]
+fn destination(bytes: &[u8], pos: usize) -> Option<((usize, usize), Option<(usize, usize)>, usize)> {
+    if bytes.get(pos) != Some(&b'(') {
+        return None
+    }
+
+    let url_start = skip_spaces(bytes, pos + 1);
+    let (url, mut i) = match bytes.get(url_start)? {
+        b'<' => {
+            let end = url_start + 1 + bytes[url_start + 1..].iter().position(|b| matches!(b, b'>' | b'<' | b'\n'))?;
+            (bytes[end] == b'>').then_some(((url_start + 1, end), end + 1))?
+        },
+        _ => {
+            let mut depth = 0;
+            let mut end = url_start;
+            while let Some(b) = bytes.get(end).filter(|b| !b.is_ascii_whitespace() && !b.is_ascii_control()) {
+                match b {
+                    b'\\' => end += 1,
+                    b'(' => depth += 1,
+                    b')' if depth == 0 => break,
+                    b')' => depth -= 1,
+                    _ => {}
+                }
+                end += 1;
+            }
+            ((url_start, end.min(bytes.len())), end.min(bytes.len()))
+        }
+    };
+
+    i = skip_spaces(bytes, i);
+    let mut title = None;
+    if let Some(open @ (b'"' | b'\'' | b'(')) = bytes.get(i).copied() {
+        let close = if open == b'(' { b')' } else { open };
+        let mut end = i + 1;
+        while *bytes.get(end)? != close {
+            end += if bytes[end] == b'\\' { 2 } else { 1 };
+        }
+        title = Some((i + 1, end));
+        i = skip_spaces(bytes, end + 1);
+    }
+
+    (bytes.get(i) == Some(&b')')).then_some((url, title, i + 1))
+}
+
+/// Find the end of the autolink starting at the `<` at `pos`.
+fn autolink(bytes: &[u8], pos: usize) -> Option<usize> {
+    let end = pos + 1 + bytes[pos + 1..].iter().position(|b| matches!(b, b'>' | b'<') || b.is_ascii_whitespace() || b.is_ascii_control())?;
+    let content = &bytes[pos + 1..end];
+    if bytes[end] != b'>' {
+        return None
+    }
+
+    let scheme_len = content.iter().position(|b| *b == b':').unwrap_or(0);
+    let scheme = &content[..scheme_len];
+    let is_uri = (2..=32).contains(&scheme_len) && scheme[0].is_ascii_alphabetic()
+        && scheme.iter().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'.' | b'-'));
+
+    let is_email = match content.iter().position(|b| *b == b'@') {
+        Some(at) => at > 0 && at + 1 < content.len() && !content[at + 1..].contains(&b'@')
+            && content[at + 1..].iter().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.')),
+        None => false
+    };
+
+    (is_uri || is_email).then_some(end + 1)
+}
+
+/// Parse the inline element at `pos`, other than text.
+fn element<'a, I: ByteInput<'a>>(input: I, pos: usize) -> Option<(Inline<I>, usize)> {
+    let bytes = input.as_byte_slice();
+    let slice = |start: usize, end: usize| input.slice_to(end).slice_from(start);
+
+    match bytes[pos] {
+        b'\\' => match bytes.get(pos + 1)? {
+            b'\n' => Some((Inline::LineBreak, pos + 2)),
+            b if b.is_ascii_punctuation() => Some((Inline::Text(slice(pos + 1, pos + 2)), pos + 2)),
+            _ => None
+        },
+        b' ' => {
+            let spaces = run_len(bytes, pos);
+            (spaces >= 2 && bytes.get(pos + spaces) == Some(&b'\n')).then_some((Inline::LineBreak, pos + spaces + 1))
+        },
+        b'`' => {
+            let (content_end, end) = code_span(bytes, pos)?;
+            let content_start = pos + run_len(bytes, pos);
+            let content = &bytes[content_start..content_end];
+
+            // One space is stripped from both sides, unless the content is only spaces.
+            let strip = content.len() >= 2 && content[0] == b' ' && content[content.len() - 1] == b' '
+                && content.iter().any(|b| *b != b' ');
+            Some((Inline::Code(slice(content_start + strip as usize, content_end - strip as usize)), end))
+        },
+        b'*' | b'_' => {
+            let (start, end, emphasis_end) = emphasis(bytes, pos)?;
+            let content = slice(start, end);
+            let strong = start - pos == 2;
+            Some((if strong { Inline::Strong(content) } else { Inline::Emphasis(content) }, emphasis_end))
+        },
+        b'[' => {
+            let close = closing_bracket(bytes, pos)?;
+            let ((url_start, url_end), title, end) = destination(bytes, close + 1)?;
+            let link = Inline::Link {
+                text: slice(pos + 1, close),
+                url: slice(url_start, url_end),
+                title: title.map(|(start, end)| slice(start, end))
+            };
+            Some((link, end))
+        },
+        b'!' if bytes.get(pos + 1) == Some(&b'[') => match element(input, pos + 1)? {
+            (Inline::Link { text, url, title }, end) => Some((Inline::Image { alt: text, url, title }, end)),
+            _ => None
+        },
+        b'<' => {
+            let end = autolink(bytes, pos)?;
+            Some((Inline::Autolink(slice(pos + 1, end - 1)), end))
+        },
+        _ => None
+    }
+}
+
+/// Create a parser for a single inline element of Markdown text, i.e. text, a code span,
+/// emphasis, a link, an image, an autolink or a hard line break. Use e.g.
+/// [`parse_iter`](crate::core::parse_iter) to get an iterator over the elements of a
+/// paragraph. No allocation is needed, and the elements are slices of the input.
+///
+/// The input should be the content of a block, e.g. a paragraph, since block structure is
+/// left to the caller. The syntax follows CommonMark, but simplified: raw HTML, entities and
+/// reference links are not recognized, and only ASCII whitespace and punctuation are
+/// considered for emphasis.
+///
+/// ### Consuming
+/// Consumes the element, on successful parse. The parser only fails on empty input.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::markdown::{inline_parser, Inline};
+///
+/// let input = "Use `x*y`, **not _snake_case_**, see [docs](https://x.y \"Docs\")";
+/// let elements: Vec<_> = parse_iter(inline_parser(), input).collect();
+///
+/// assert_eq!(elements, [Inline::Text("Use "), Inline::Code("x*y"), Inline::Text(", "),
+///                       Inline::Strong("not _snake_case_"), Inline::Text(", see "),
+///                       Inline::Link { text: "docs", url: "https://x.y", title: Some("Docs") }]);
+///
+/// // Emphasis is tokenized by parsing the content again.
+/// let nested: Vec<_> = parse_iter(inline_parser(), "not _snake_case_").collect();
+/// assert_eq!(nested, [Inline::Text("not "), Inline::Emphasis("snake_case")]);
+/// ```
+#[inline]
+pub fn inline_parser<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Inline<I>, S> {
+    create_parser!(s, {
+        let input = s.input;
+        let bytes = input.as_byte_slice();
+        if bytes.is_empty() {
+            return None
+        }
+
+        let (inline, end) = element(input, 0).unwrap_or_else(|| {
+            // Skip a whole run of delimiters, since a run can't start in the middle.
+            let mut pos = match bytes[0] {
+                b'*' | b'_' | b'`' => run_len(bytes, 0),
+                _ => 1
+            };
+
+            loop {
+                let Some(next) = find(&bytes[pos..], SPECIAL).map(|i| pos + i) else {
+                    break (Inline::Text(input), bytes.len())
+                };
+
+                if element(input, next).is_some() {
+                    break (Inline::Text(input.slice_to(next)), next)
+                }
+
+                pos = next + match bytes[next] {
+                    b'*' | b'_' | b'`' | b' ' => run_len(bytes, next),
+                    _ => 1
+                };
+            }
+        });
+
+        s.input = input.slice_from(end);
+        Some(inline)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{core::parse_iter, markdown::{inline_parser, Inline::*}};
+
+    #[test]
+    fn inlines() {
+        let tokens = |input| parse_iter(inline_parser(), input);
+
+        assert!(tokens("").eq([]));
+        assert!(tokens("a *b* c").eq([Text("a "), Emphasis("b"), Text(" c")]));
+        assert!(tokens("***a***").eq([Strong("*a*")]));
+        assert!(tokens("**a***").eq([Strong("a"), Text("*")]));
+        assert!(tokens("*a **b** c*").eq([Emphasis("a **b** c")]));
+        assert!(tokens("* a * snake_case **x").eq([Text("* a * snake_case **x")]));
+        assert!(tokens("*a `*` b*").eq([Emphasis("a `*` b")]));
+        assert!(tokens("`` a ` b `` `  ` ``x").eq([Code("a ` b"), Text(" "), Code("  "), Text(" ``x")]));
+        assert!(tokens("\\*a\\*\\q").eq([Text("*"), Text("a"), Text("*"), Text("\\q")]));
+        assert!(tokens("a  \nb\\\nc \nd").eq([Text("a"), LineBreak, Text("b"), LineBreak, Text("c \nd")]));
+
+        assert!(tokens("[a [b]](<u v> 'c') ![i](j(k))").eq([
+            Link { text: "a [b]", url: "u v", title: Some("c") }, Text(" "),
+            Image { alt: "i", url: "j(k)", title: None }
+        ]));
+        assert!(tokens("[a](b c) [a] (b) [a](").eq([Text("[a](b c) [a] (b) [a](")]));
+        assert!(tokens("<https://a.b/c> <a@b.c> <a b> <x:y>").eq([
+            Autolink("https://a.b/c"), Text(" "), Autolink("a@b.c"), Text(" <a b> <x:y>")
+        ]));
+    }
+}
@@ -0,0 +1,118 @@
+use core::hash::Hash;
+use std::collections::HashMap;
+
+use crate::{core::Parser, slicelike::SliceLike};
+
+/// Cache for the results of a memoized parser. See [`memo`].
+///
+/// Results are keyed by the position in the input, so a table must not be reused
+/// between different inputs without calling [`clear`](MemoTable::clear) in between.
+pub struct MemoTable<I: SliceLike, O> {
+    entries: HashMap<I::Idx, Option<(O, I)>>
+}
+
+impl<I: SliceLike, O> MemoTable<I, O> where I::Idx: Hash + Eq {
+    /// Create a new, empty table.
+    pub fn new() -> Self {
+        Self { entries: HashMap::new() }
+    }
+
+    /// Remove all cached results.
+    pub fn clear(&mut self) {
+        self.entries.clear()
+    }
+
+    /// The number of cached results.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the table is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<I: SliceLike, O> Default for MemoTable<I, O> where I::Idx: Hash + Eq {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Create a parser that caches the results of `p` in a [`MemoTable`] in the user state,
+/// so that `p` is applied at most once per position in the input. This can turn exponential
+/// backtracking into linear time for ambiguous grammars (a.k.a. packrat parsing).
+///
+/// Failures are cached as well. Note that any modifications to the user state made by `p`
+/// will not be repeated when a cached result is used.
+///
+/// ### Arguments
+/// * `table` - a function selecting the table for this parser in the user state.
+/// * `p` - the parser
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::memo::{memo, MemoTable};
+/// use anpa::number::integer;
+///
+/// let p = memo(|t: &mut MemoTable<_, _>| t, integer::<u32, _, _, _>());
+///
+/// let mut table = MemoTable::new();
+/// assert_eq!(parse_state(p, "123", &mut table).result, Some(123));
+/// assert_eq!(table.len(), 1);
+/// ```
+#[inline]
+pub fn memo<I: SliceLike, O: Clone, S>(table: impl FnOnce(&mut S) -> &mut MemoTable<I, O> + Copy,
+                                       p: impl Parser<I, O, S>
+) -> impl Parser<I, O, S> where I::Idx: Hash + Eq {
+    create_parser!(s, {
+        // The remaining length uniquely identifies the position in the input.
+        let pos = s.input.slice_len();
+
+        if let Some(entry) = table(s.user_state).entries.get(&pos) {
+            let (res, rest) = entry.clone()?;
+            s.input = rest;
+            return Some(res)
+        }
+
+        let res = p(s);
+        let entry = res.clone().map(|r| (r, s.input));
+        table(s.user_state).entries.insert(pos, entry);
+        res
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{combinators::{left, lift_to_state, or, right}, core::{parse_state, StrParser}, memo::{memo, MemoTable}, parsers::{success, take}};
+
+    struct State<'a> {
+        calls: usize,
+        table: MemoTable<&'a str, &'a str>
+    }
+
+    // An ambiguous grammar that backtracks exponentially without memoization:
+    // X := '(' X ')' 'a' | '(' X ')' 'b' | 'x'
+    fn nested<'a>() -> impl StrParser<'a, &'a str, State<'a>> {
+        let counted = right(lift_to_state(|s: &mut State, _| s.calls += 1, success()),
+                            defer_parser!(nested_inner()));
+        memo(|s: &mut State<'a>| &mut s.table, counted)
+    }
+
+    fn nested_inner<'a>() -> impl StrParser<'a, &'a str, State<'a>> {
+        let inner = right(take('('), left(nested(), take(')')));
+        or(or(left(inner, take('a')), left(inner, take('b'))), take("x"))
+    }
+
+    #[test]
+    fn memo_linear() {
+        let depth = 20;
+        let input = "(".repeat(depth) + "x" + &")b".repeat(depth);
+        let mut state = State { calls: 0, table: MemoTable::new() };
+        let res = parse_state(nested(), input.as_str(), &mut state);
+        assert_eq!(res.result, Some("x"));
+        assert!(res.state.input.is_empty());
+        assert_eq!(state.calls, depth + 1);
+    }
+}
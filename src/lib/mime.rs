@@ -0,0 +1,296 @@
+use alloc::vec::Vec;
+
+use crate::{core::Parser, findbyte::{get_seq_pos, ByteInput}};
+
+/// A part of a multipart body, as produced by [`multipart_parser`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Part<I> {
+    /// The unparsed header fields of the part, without the empty line ending them.
+    pub headers: I,
+
+    /// The body of the part.
+    pub body: I
+}
+
+/// An encoded word of a header field, as produced by [`encoded_word`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedWord<I> {
+    /// The charset of the decoded text, e.g. `UTF-8`.
+    pub charset: I,
+
+    /// The decoded text, not converted from `charset`.
+    pub text: Vec<u8>
+}
+
+/// Find the next delimiter line `--boundary` at `offset` or later. Returns the position of
+/// the line break before the delimiter (or the delimiter itself, at the start of the input),
+/// the position after the delimiter line, and whether it is the closing delimiter.
+fn find_delimiter<'a>(input: impl ByteInput<'a>, boundary: &[u8], mut offset: usize) -> Option<(usize, usize, bool)> {
+    let bytes = input.as_byte_slice();
+    loop {
+        let pos = offset + get_seq_pos(&bytes[offset..], boundary)?;
+        let end = pos + boundary.len();
+        offset = pos + 1;
+
+        let Some(dashes) = pos.checked_sub(2) else { continue };
+        let start = match dashes.checked_sub(2) {
+            None if dashes == 0 => 0,
+            Some(start) if &bytes[start..dashes] == b"\r\n" => start,
+            _ => continue
+        };
+
+        if &bytes[dashes..pos] != b"--" || !input.is_split_point(dashes) || !input.is_split_point(end) {
+            continue
+        }
+
+        if bytes[end..].starts_with(b"--") {
+            return Some((start, end + 2, true))
+        }
+
+        // Skip any transport padding after the delimiter.
+        let padding = bytes[end..].iter().take_while(|b| **b == b' ' || **b == b'\t').count();
+        if bytes[end + padding..].starts_with(b"\r\n") {
+            return Some((start, end + padding + 2, false))
+        }
+    }
+}
+
+/// Create a parser that splits a multipart body (e.g. `multipart/form-data`) into its parts.
+/// Any preamble before the first delimiter is ignored. The body must end with a closing
+/// delimiter, and the epilogue after it is left unconsumed.
+///
+/// The boundary should be the `boundary` parameter of the `Content-Type` header, i.e.
+/// without the leading `--`.
+///
+/// ### Consuming
+/// Consumes the preamble, the parts and the closing delimiter, on successful parse.
+///
+/// ### Arguments
+/// * `boundary` - the boundary separating the parts
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::mime::{multipart_parser, Part};
+///
+/// let body = "preamble\r\n--xyz\r\nContent-Type: text/plain\r\n\r\nHello\r\n\
+///             --xyz\r\n\r\nWorld\r\n--xyz--\r\n";
+///
+/// let res = parse(multipart_parser(b"xyz"), body);
+/// assert_eq!(res.result, Some(vec![Part { headers: "Content-Type: text/plain", body: "Hello" },
+///                                  Part { headers: "", body: "World" }]));
+/// assert_eq!(res.state, "\r\n");
+/// ```
+#[inline]
+pub fn multipart_parser<'a, 'b, I: ByteInput<'a>, S>(boundary: &'b [u8]) -> impl Parser<I, Vec<Part<I>>, S> + 'b {
+    create_parser!(s, {
+        let mut parts = Vec::new();
+        let (_, mut pos, mut closing) = find_delimiter(s.input, boundary, 0)?;
+
+        while !closing {
+            let (end, next, is_closing) = find_delimiter(s.input, boundary, pos)?;
+            let part = s.input.slice_to(end).slice_from(pos);
+            let part_bytes = part.as_byte_slice();
+
+            let (headers, body) = if part_bytes.starts_with(b"\r\n") {
+                (part.slice_to(0), part.slice_from(2))
+            } else {
+                let headers_end = get_seq_pos(part_bytes, b"\r\n\r\n")?;
+                (part.slice_to(headers_end), part.slice_from(headers_end + 4))
+            };
+
+            parts.push(Part { headers, body });
+            pos = next;
+            closing = is_closing;
+        }
+
+        s.input = s.input.slice_from(pos);
+        Some(parts)
+    })
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|d| d as u8)
+}
+
+/// Decode quoted-printable `bytes`. If `q_encoding` is set, `_` is decoded as a space and
+/// soft line breaks are not allowed, as for the "Q" encoding of encoded words.
+fn decode_quoted_printable(bytes: &[u8], q_encoding: bool) -> Option<Vec<u8>> {
+    let mut res = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'=' if !q_encoding && bytes[i + 1..].starts_with(b"\r\n") => i += 3,
+            b'=' if !q_encoding && bytes[i + 1..].starts_with(b"\n") => i += 2,
+            b'=' => {
+                let high = hex_value(*bytes.get(i + 1)?)?;
+                let low = hex_value(*bytes.get(i + 2)?)?;
+                res.push(high << 4 | low);
+                i += 3;
+            },
+            b'_' if q_encoding => {
+                res.push(b' ');
+                i += 1;
+            },
+            b => {
+                res.push(b);
+                i += 1;
+            }
+        }
+    }
+    Some(res)
+}
+
+fn base64_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'A'..=b'Z' => Some(byte - b'A'),
+        b'a'..=b'z' => Some(byte - b'a' + 26),
+        b'0'..=b'9' => Some(byte - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None
+    }
+}
+
+/// Decode base64 `bytes`, with optional padding.
+fn decode_base64(bytes: &[u8]) -> Option<Vec<u8>> {
+    let padding = bytes.iter().rev().take_while(|b| **b == b'=').count();
+    let data = &bytes[..bytes.len() - padding];
+    if padding > 2 || (padding > 0 && bytes.len() % 4 != 0) || data.len() % 4 == 1 {
+        return None
+    }
+
+    let mut res = Vec::with_capacity(data.len() / 4 * 3 + 2);
+    for chunk in data.chunks(4) {
+        let mut word = 0u32;
+        for b in chunk {
+            word = word << 6 | base64_value(*b)? as u32;
+        }
+        word <<= 6 * (4 - chunk.len()) as u32;
+        res.extend_from_slice(&word.to_be_bytes()[1..chunk.len()]);
+    }
+    Some(res)
+}
+
+/// Create a parser that decodes quoted-printable text, as used by the
+/// `Content-Transfer-Encoding: quoted-printable` of a body. Soft line breaks are removed,
+/// and the parser fails if an `=` is not followed by a line break or two hex digits.
+///
+/// ### Consuming
+/// Consumes the rest of the input, on successful parse.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::mime::quoted_printable;
+///
+/// let res = parse(quoted_printable(), "Caf=C3=A9 au l=\r\nait");
+/// assert_eq!(res.result, Some("Café au lait".as_bytes().to_vec()));
+///
+/// assert_eq!(parse(quoted_printable(), "100=").result, None);
+/// ```
+#[inline]
+pub fn quoted_printable<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Vec<u8>, S> {
+    create_parser!(s, {
+        let res = decode_quoted_printable(s.input.as_byte_slice(), false)?;
+        s.input = s.input.slice_from(s.input.as_byte_slice().len());
+        Some(res)
+    })
+}
+
+/// Create a parser for an encoded word of a header field, as specified by RFC 2047, e.g.
+/// `=?UTF-8?B?SGVsbG8=?=`. Both the "B" (base64) and "Q" (quoted-printable) encodings are
+/// supported. The decoded text is not converted from its charset.
+///
+/// ### Consuming
+/// Consumes the encoded word, on successful parse.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::mime::encoded_word;
+///
+/// let res = parse(encoded_word(), "=?UTF-8?Q?Caf=C3=A9_au_lait?= rest");
+/// let word = res.result.unwrap();
+/// assert_eq!(word.charset, "UTF-8");
+/// assert_eq!(word.text, "Café au lait".as_bytes());
+/// assert_eq!(res.state, " rest");
+///
+/// let word = parse(encoded_word(), "=?utf-8?b?SGVsbG8=?=").result.unwrap();
+/// assert_eq!(word.text, b"Hello");
+/// ```
+#[inline]
+pub fn encoded_word<'a, I: ByteInput<'a>, S>() -> impl Parser<I, EncodedWord<I>, S> {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice();
+        if !bytes.starts_with(b"=?") {
+            return None
+        }
+
+        let charset_len = bytes[2..].iter().position(|b| *b == b'?')?;
+        let encoding_pos = 2 + charset_len + 1;
+        let text_pos = encoding_pos + 2;
+        if bytes.get(encoding_pos + 1) != Some(&b'?') {
+            return None
+        }
+
+        let text_len = bytes.get(text_pos..)?.iter().position(|b| *b == b'?')?;
+        let end = text_pos + text_len;
+        if bytes.get(end + 1) != Some(&b'=') {
+            return None
+        }
+
+        let encoded = &bytes[text_pos..end];
+        let text = match bytes[encoding_pos] {
+            b'B' | b'b' => decode_base64(encoded)?,
+            b'Q' | b'q' => decode_quoted_printable(encoded, true)?,
+            _ => return None
+        };
+
+        // All delimiters are ASCII, so these are valid split points for `&str`.
+        let charset = s.input.slice_to(2 + charset_len).slice_from(2);
+        s.input = s.input.slice_from(end + 2);
+        Some(EncodedWord { charset, text })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::{core::parse, mime::{decode_base64, encoded_word, multipart_parser, quoted_printable, Part}};
+
+    #[test]
+    fn multipart() {
+        let body = b"--b\r\n\r\nx\r\n--bb\r\n--b  \r\nH: 1\r\nI: 2\r\n\r\n\r\n--b--".as_slice();
+        let res = parse(multipart_parser(b"b"), body);
+        assert_eq!(res.result, Some(vec![Part { headers: &b""[..], body: b"x\r\n--bb" },
+                                         Part { headers: b"H: 1\r\nI: 2", body: b"" }]));
+        assert_eq!(res.state, b"");
+
+        assert_eq!(parse(multipart_parser(b"b"), "--b--").result, Some(vec![]));
+        assert_eq!(parse(multipart_parser(b"b"), "x--b--").result, None);
+        assert_eq!(parse(multipart_parser(b"b"), "--b\r\n\r\nunterminated").result, None);
+        assert_eq!(parse(multipart_parser(b"b"), "--b\r\nno headers end\r\n--b--").result, None);
+    }
+
+    #[test]
+    fn decoding() {
+        assert_eq!(decode_base64(b"").unwrap(), b"");
+        assert_eq!(decode_base64(b"TQ==").unwrap(), b"M");
+        assert_eq!(decode_base64(b"TWE").unwrap(), b"Ma");
+        assert_eq!(decode_base64(b"TWFu").unwrap(), b"Man");
+        assert_eq!(decode_base64(b"TWF=="), None);
+        assert_eq!(decode_base64(b"T"), None);
+        assert_eq!(decode_base64(b"TW!u"), None);
+
+        assert_eq!(parse(quoted_printable(), "a=3db=\nc_").result.unwrap(), b"a=bc_");
+        assert_eq!(parse(quoted_printable(), "=4").result, None);
+        assert_eq!(parse(quoted_printable(), "=XY").result, None);
+
+        assert_eq!(parse(encoded_word(), "=?ISO-8859-1?q?a_=E9?=").result.unwrap().text, b"a \xe9");
+        assert_eq!(parse(encoded_word(), "=?UTF-8?X?abc?=").result, None);
+        assert_eq!(parse(encoded_word(), "=?UTF-8?Q?abc").result, None);
+        assert_eq!(parse(encoded_word(), "=?UTF-8?Q?a?b?=").result, None);
+    }
+}
@@ -1,11 +1,36 @@
 use core::borrow::Borrow;
 
-use crate::slicelike::SliceLike;
+use crate::{findbyte::get_seq_pos, slicelike::SliceLike};
+
+/// The maximum length of needles searched for using [`get_seq_pos`].
+const SHORT_NEEDLE_LEN: usize = 8;
 
 /// Trait for a type that can be sought after in the collection `Parent`.
 pub trait Needle<Parent: SliceLike, Result>: Copy {
     /// Find the index of the needle in the provided haystack.
     fn find_in(&self, haystack: Parent) -> Option<(Parent::Idx, Parent::Idx)>;
+
+    /// Find the index of the last occurrence of the needle in the provided haystack.
+    ///
+    /// The default implementation searches repeatedly from the left using
+    /// [`find_in`](Needle::find_in), so implement it when a search from the right is possible.
+    fn rfind_in(&self, haystack: Parent) -> Option<(Parent::Idx, Parent::Idx)> {
+        let mut last = None;
+        let mut offset = Parent::Idx::default();
+
+        while let Some((len, pos)) = self.find_in(haystack.slice_from(offset)) {
+            let pos = offset + pos;
+            last = Some((len, pos));
+
+            // Continue after the first item of the match, to also find overlapping matches.
+            match haystack.slice_from(pos).slice_first_if(|_| true) {
+                Some((_, rest)) => offset = haystack.slice_len() - rest.slice_len(),
+                None => break
+            }
+        }
+
+        last
+    }
 }
 
 impl<T: PartialEq + Copy> Needle<&[T], T> for T {
@@ -14,6 +39,12 @@ impl<T: PartialEq + Copy> Needle<&[T], T> for T {
             .position(|x| x == self)
             .map(|pos| (1, pos))
     }
+
+    fn rfind_in(&self, haystack: &[T]) -> Option<(usize, usize)> {
+        haystack.iter()
+            .rposition(|x| x == self)
+            .map(|pos| (1, pos))
+    }
 }
 
 impl<'a, T: PartialEq + Copy, S: Borrow<[T]> + Copy> Needle<&'a [T], &'a [T]> for S {
@@ -22,6 +53,12 @@ impl<'a, T: PartialEq + Copy, S: Borrow<[T]> + Copy> Needle<&'a [T], &'a [T]> fo
             .position(|w| w == self.borrow())
             .map(|pos| (self.borrow().len(), pos))
     }
+
+    fn rfind_in(&self, haystack: &[T]) -> Option<(usize, usize)> {
+        haystack.windows(self.borrow().len())
+            .rposition(|w| w == self.borrow())
+            .map(|pos| (self.borrow().len(), pos))
+    }
 }
 
 impl Needle<&str, char> for char {
@@ -30,11 +67,191 @@ impl Needle<&str, char> for char {
         haystack.find(*self)
             .map(|pos| (self.len_utf8(), pos))
     }
+
+    #[inline]
+    fn rfind_in(&self, haystack: &str) -> Option<(usize, usize)> {
+        haystack.rfind(*self)
+            .map(|pos| (self.len_utf8(), pos))
+    }
 }
 
 impl<'a, S: Borrow<str> + Copy> Needle<&'a str, &'a str> for S {
     fn find_in(&self, haystack: &str) -> Option<(usize, usize)> {
-        haystack.find(self.borrow())
-            .map(|pos| (self.borrow().len(), pos))
+        let needle = self.borrow();
+
+        // Short needles are faster to find using a SWAR search. Any match of a valid UTF-8
+        // needle is at a character boundary, so the position can be used directly.
+        let pos = if (2..=SHORT_NEEDLE_LEN).contains(&needle.len()) {
+            get_seq_pos(haystack.as_bytes(), needle.as_bytes())
+        } else {
+            haystack.find(needle)
+        };
+
+        pos.map(|pos| (needle.len(), pos))
+    }
+
+    fn rfind_in(&self, haystack: &str) -> Option<(usize, usize)> {
+        let needle = self.borrow();
+        haystack.rfind(needle)
+            .map(|pos| (needle.len(), pos))
     }
-}
\ No newline at end of file
+}
+
+/// A needle with a precomputed search table, for faster repeated searches of the same needle.
+/// See [`compiled_needle`].
+#[derive(Debug, Clone)]
+pub struct CompiledNeedle<'a> {
+    needle: &'a [u8],
+    shift: [u8; 256]
+}
+
+/// Precompute a search table for `needle`, for use with e.g. [`until`](crate::parsers::until).
+/// The table is built once, which avoids redoing the setup for every search when the needle is
+/// used repeatedly, e.g. inside [`many`](crate::combinators::many).
+///
+/// The search uses the Boyer-Moore-Horspool algorithm, which is most effective for longer
+/// needles. The resulting needle is used by reference, to avoid copying the table.
+///
+/// ### Arguments
+/// * `needle` - the needle to search for
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{many, no_separator};
+/// use anpa::needle::compiled_needle;
+/// use anpa::parsers::until;
+///
+/// let needle = compiled_needle("<!-- end -->");
+/// let p = many(until(&needle), true, no_separator());
+///
+/// let res = parse(p, "a<!-- end -->b<!-- end -->c");
+/// assert_eq!(res.result, Some("a<!-- end -->b<!-- end -->"));
+/// assert_eq!(res.state, "c");
+/// ```
+pub fn compiled_needle<N: AsRef<[u8]> + ?Sized>(needle: &N) -> CompiledNeedle<'_> {
+    let needle = needle.as_ref();
+    let len = needle.len().min(u8::MAX as usize) as u8;
+    let mut shift = [len; 256];
+
+    // Shifts are saturated, which is safe since a smaller shift only means a slower search.
+    for (i, b) in needle.iter().enumerate().take(needle.len().saturating_sub(1)) {
+        shift[*b as usize] = (needle.len() - 1 - i).min(u8::MAX as usize) as u8;
+    }
+
+    CompiledNeedle { needle, shift }
+}
+
+impl CompiledNeedle<'_> {
+    /// Find the position of the first occurrence of the needle in `haystack` starting at or
+    /// after `from`.
+    fn find_from(&self, haystack: &[u8], from: usize) -> Option<usize> {
+        let Some((last, init)) = self.needle.split_last() else {
+            return Some(from)
+        };
+
+        let mut i = from;
+        while i + self.needle.len() <= haystack.len() {
+            let c = haystack[i + init.len()];
+            if c == *last && &haystack[i..i + init.len()] == init {
+                return Some(i)
+            }
+            i += self.shift[c as usize] as usize;
+        }
+        None
+    }
+
+    /// Find the position of the last occurrence of the needle in `haystack` ending at or
+    /// before `end`.
+    fn rfind_to(&self, haystack: &[u8], end: usize) -> Option<usize> {
+        if self.needle.is_empty() {
+            return Some(end)
+        }
+        haystack[..end].windows(self.needle.len())
+            .rposition(|w| w == self.needle)
+    }
+}
+
+impl<'a> Needle<&'a [u8], &'a [u8]> for &CompiledNeedle<'_> {
+    fn find_in(&self, haystack: &[u8]) -> Option<(usize, usize)> {
+        self.find_from(haystack, 0)
+            .map(|pos| (self.needle.len(), pos))
+    }
+
+    fn rfind_in(&self, haystack: &[u8]) -> Option<(usize, usize)> {
+        self.rfind_to(haystack, haystack.len())
+            .map(|pos| (self.needle.len(), pos))
+    }
+}
+
+impl<'a> Needle<&'a str, &'a str> for &CompiledNeedle<'_> {
+    fn find_in(&self, haystack: &str) -> Option<(usize, usize)> {
+        let mut from = 0;
+
+        // Only needles that aren't valid UTF-8 can match outside of character boundaries.
+        loop {
+            let pos = self.find_from(haystack.as_bytes(), from)?;
+            if haystack.is_char_boundary(pos) && haystack.is_char_boundary(pos + self.needle.len()) {
+                return Some((self.needle.len(), pos))
+            }
+            from = pos + 1;
+        }
+    }
+
+    fn rfind_in(&self, haystack: &str) -> Option<(usize, usize)> {
+        let mut end = haystack.len();
+        loop {
+            let pos = self.rfind_to(haystack.as_bytes(), end)?;
+            if haystack.is_char_boundary(pos) && haystack.is_char_boundary(pos + self.needle.len()) {
+                return Some((self.needle.len(), pos))
+            }
+            end = pos + self.needle.len() - 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::needle::{compiled_needle, Needle};
+
+    #[test]
+    fn compiled() {
+        let haystack = "abcabdabcabcabe";
+        for needle in ["abcabe", "cab", "e", "x", "", "abcabcabcabcabcabc"] {
+            let compiled = compiled_needle(needle);
+            assert_eq!((&compiled).find_in(haystack), needle.find_in(haystack));
+            assert_eq!((&compiled).rfind_in(haystack), needle.rfind_in(haystack));
+            if !needle.is_empty() {
+                assert_eq!((&compiled).find_in(haystack.as_bytes()), needle.as_bytes().find_in(haystack.as_bytes()));
+                assert_eq!((&compiled).rfind_in(haystack.as_bytes()), needle.as_bytes().rfind_in(haystack.as_bytes()));
+            }
+        }
+
+        let invalid = compiled_needle(&[0xa5]);
+        assert_eq!((&invalid).find_in("åa"), None);
+        assert_eq!((&invalid).rfind_in("åa"), None);
+    }
+
+    #[test]
+    fn rfind_default() {
+        use crate::slicelike::SliceLike;
+
+        // A needle implementing only `find_in`.
+        #[derive(Clone, Copy)]
+        struct FindOnly<N>(N);
+
+        impl<P: SliceLike, R, N: Needle<P, R>> Needle<P, R> for FindOnly<N> {
+            fn find_in(&self, haystack: P) -> Option<(P::Idx, P::Idx)> {
+                self.0.find_in(haystack)
+            }
+        }
+
+        for haystack in ["", "a", "aaaa", "åaåa", "xåååy"] {
+            for needle in ["a", "aa", "å", "åå", ""] {
+                assert_eq!(FindOnly(needle).rfind_in(haystack), needle.rfind_in(haystack), "{} {}", haystack, needle);
+            }
+            assert_eq!(FindOnly('å').rfind_in(haystack), 'å'.rfind_in(haystack));
+        }
+        assert_eq!(FindOnly(b"aa".as_slice()).rfind_in(b"aaab".as_slice()), Some((2, 1)));
+    }
+}
@@ -1,6 +1,6 @@
-use core::ops::{Add, Div, Mul, Sub};
+use core::{ops::{Add, Div, Mul, Sub}, str::{from_utf8, FromStr}};
 
-use crate::{charlike::CharLike, combinators::{or, right}, core::{Parser, ParserExt}, parsers::item_if, slicelike::SliceLike};
+use crate::{charlike::CharLike, core::Parser, findbyte::{get_byte_pos, in_range, ByteInput}, slicelike::SliceLike};
 
 /// Trait for types that act like numbers.
 pub trait NumLike:
@@ -19,8 +19,40 @@ Add<Output = Self>
 pub trait FloatLike: Add<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Copy {
     const ONE: Self;
     const MINUS_ONE: Self;
-    fn cast_usize(n: usize) -> Self;
-    fn cast_isize(n: isize) -> Self;
+
+    /// Convert from a `usize`. The default implementation builds the number from [`ONE`]
+    /// by repeated doubling.
+    ///
+    /// [`ONE`]: FloatLike::ONE
+    #[deprecated(since = "0.8.0", note = "not used by the float parsers anymore")]
+    fn cast_usize(n: usize) -> Self {
+        let zero = Self::ONE + Self::MINUS_ONE;
+        (0..usize::BITS).rev().fold(zero, |acc, bit| {
+            let acc = acc + acc;
+            if (n >> bit) & 1 == 1 { acc + Self::ONE } else { acc }
+        })
+    }
+
+    /// Convert from an `isize`. The default implementation uses
+    /// [`cast_usize`](FloatLike::cast_usize).
+    #[deprecated(since = "0.8.0", note = "not used by the float parsers anymore")]
+    fn cast_isize(n: isize) -> Self {
+        #[allow(deprecated)]
+        let abs = Self::cast_usize(n.unsigned_abs());
+        if n < 0 { Self::MINUS_ONE * abs } else { abs }
+    }
+
+    /// Whether the number is neither infinite nor NaN. The default implementation considers
+    /// all numbers finite.
+    fn is_finite(self) -> bool {
+        true
+    }
+
+    /// Create a number from `mantissa * 10^exp`, if it can be done exactly, i.e. with correct
+    /// rounding. Returns `None` otherwise, which the default implementation always does.
+    fn from_decimal(_mantissa: u64, _exp: i32) -> Option<Self> {
+        None
+    }
 }
 
 macro_rules! impl_NumLike {
@@ -39,8 +71,12 @@ macro_rules! impl_NumLike {
     }
 }
 
+// Powers of ten that are exactly representable in a `f64`.
+const POW10: [f64; 23] = [1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10, 1e11,
+                          1e12, 1e13, 1e14, 1e15, 1e16, 1e17, 1e18, 1e19, 1e20, 1e21, 1e22];
+
 macro_rules! impl_FloatLike {
-    ($($type:tt),*) => {
+    ($($type:tt: $mantissa_bits:expr, $max_exp:expr),*) => {
         $(
             impl FloatLike for $type {
                 const ONE: Self = 1.0;
@@ -54,6 +90,23 @@ macro_rules! impl_FloatLike {
                 fn cast_isize(n: isize) -> Self {
                     n as $type
                 }
+
+                #[inline(always)]
+                fn is_finite(self) -> bool {
+                    $type::is_finite(self)
+                }
+
+                #[inline(always)]
+                fn from_decimal(mantissa: u64, exp: i32) -> Option<Self> {
+                    // Both the mantissa and the power of ten are exact, so a single
+                    // multiplication or division is correctly rounded.
+                    if mantissa >> $mantissa_bits != 0 || !(-$max_exp..=$max_exp).contains(&exp) {
+                        return None
+                    }
+
+                    let pow = POW10[exp.unsigned_abs() as usize] as $type;
+                    Some(if exp < 0 { mantissa as $type / pow } else { mantissa as $type * pow })
+                }
             }
         )*
     }
@@ -61,18 +114,18 @@ macro_rules! impl_FloatLike {
 
 
 impl_NumLike!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize);
-impl_FloatLike!(f32, f64);
+impl_FloatLike!(f32: 24, 10, f64: 53, 22);
 
 #[inline(always)]
-fn integer_internal<const CHECKED: bool, const NEG: bool, const DEC_DIVISOR: bool,
+fn integer_internal<const CHECKED: bool, const NEG: bool,
                     O: NumLike,
                     A: CharLike,
                     I: SliceLike<RefItem = A>,
-                    S>() -> impl Parser<I, (O, usize, bool), S> {
+                    S>(min_digits: usize, max_digits: usize) -> impl Parser<I, O, S> {
     create_parser!(s, {
         let mut idx: I::Idx = Default::default();
+        let mut digits = 0;
         let mut acc = O::cast_u8(0);
-        let mut dec_divisor = 1;
 
         // The number 10 is guaranteed to fit into all our `NumLike` types
         let ten = O::cast_u8(10);
@@ -81,32 +134,24 @@ fn integer_internal<const CHECKED: bool, const NEG: bool, const DEC_DIVISOR: boo
             // Digits are between 0 and 9, so they always fit in all types
             let digit = O::cast_u8(digit as u8);
 
-            if checked {
-                if acc > (O::MAX / ten) {
-                    return None
-                }
+            if checked && acc > (O::MAX / ten) {
+                return None
             }
             acc = acc * ten;
 
             if is_negative {
-                if checked {
-                    if acc < O::MIN + digit {
-                        return None
-                    }
+                if checked && acc < O::MIN + digit {
+                    return None
                 }
                 acc = acc - digit;
             } else {
-                if checked {
-                    if acc > O::MAX - digit {
-                        return None
-                    }
+                if checked && acc > O::MAX - digit {
+                    return None
                 }
                 acc = acc + digit;
             }
             idx += true.into();
-            if DEC_DIVISOR {
-                dec_divisor *= 10;
-            }
+            digits += 1;
 
             Some(())
         };
@@ -117,22 +162,23 @@ fn integer_internal<const CHECKED: bool, const NEG: bool, const DEC_DIVISOR: boo
                 true
             } else {
                 // We don't care about checking the result here, since a single digit can never fail.
-                consume(c.as_char().to_digit(10)?, false, false);
+                consume(c.to_digit(10)?, false, false);
                 false
             }
         } else {
             false
         };
 
-        for digit in iter.map_while(|d| d.as_char().to_digit(10)) {
+        let first_consumed = NEG && !is_negative;
+        for digit in iter.map_while(|d| d.to_digit(10)).take(max_digits.saturating_sub(first_consumed.into())) {
             consume(digit, is_negative, CHECKED)?;
         }
 
-        if idx == Default::default() {
+        if digits < min_digits.max(1) {
             None
         } else {
             s.input = s.input.slice_from(idx + is_negative.into());
-            Some((acc, dec_divisor, is_negative))
+            Some(acc)
         }
     })
 }
@@ -143,7 +189,7 @@ pub fn integer<O: NumLike,
                A: CharLike,
                I: SliceLike<RefItem = A>,
                S>() -> impl Parser<I, O, S> {
-    integer_internal::<false, false, false,_,_,_,_>().map(|(n, _, _)| n)
+    integer_internal::<false, false,_,_,_,_>(1, usize::MAX)
 }
 
 /// Parse an unsigned integer. The type of the integer will be inferred from the context.
@@ -153,7 +199,7 @@ pub fn integer_checked<O: NumLike,
                        A: CharLike,
                        I: SliceLike<RefItem = A>,
                        S>() -> impl Parser<I, O, S> {
-    integer_internal::<true, false, false,_,_,_,_>().map(|(n, _, _)| n)
+    integer_internal::<true, false,_,_,_,_>(1, usize::MAX)
 }
 
 /// Parse an signed integer. The type of the integer will be inferred from the context.
@@ -162,7 +208,7 @@ pub fn integer_signed<O: NumLike,
                       A: CharLike,
                       I: SliceLike<RefItem = A>,
                       S>() -> impl Parser<I, O, S> {
-    integer_internal::<false, true, false,_,_,_,_>().map(|(n, _, _)| n)
+    integer_internal::<false, true,_,_,_,_>(1, usize::MAX)
 }
 
 /// Parse an signed integer. The type of the integer will be inferred from the context.
@@ -172,48 +218,400 @@ pub fn integer_signed_checked<O: NumLike,
                               A: CharLike,
                               I: SliceLike<RefItem = A>,
                               S>() -> impl Parser<I, O, S> {
-    integer_internal::<true, true, false,_,_,_,_>().map(|(n, _, _)| n)
+    integer_internal::<true, true,_,_,_,_>(1, usize::MAX)
+}
+
+/// Parse an unsigned integer of at least `min` and at most `max` digits, e.g. for fixed-width
+/// fields. The type of the integer will be inferred from the context.
+/// At least one digit is always required, and no more than `max` digits are consumed even if
+/// more follow. This parser will fail if the result does not fit in the inferred integer type.
+///
+/// ### Arguments
+/// * `min` - the minimum number of digits
+/// * `max` - the maximum number of digits
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::right;
+/// use anpa::number::integer_digits;
+/// use anpa::parsers::skip;
+/// use anpa::tuplify;
+///
+/// let date = tuplify!(integer_digits(4, 4), integer_digits(2, 2), integer_digits(2, 2));
+/// assert_eq!(parse(date, "20240131").result, Some((2024u16, 1u8, 31u8)));
+/// assert_eq!(parse(date, "2024131").result, None);
+///
+/// let component = right(skip(','), integer_digits::<u8, _, _, _>(1, 3));
+/// assert_eq!(parse(component, ",255").result, Some(255));
+/// assert_eq!(parse(component, ",256").result, None);
+/// ```
+#[inline]
+pub fn integer_digits<O: NumLike,
+                      A: CharLike,
+                      I: SliceLike<RefItem = A>,
+                      S>(min: usize, max: usize) -> impl Parser<I, O, S> {
+    integer_internal::<true, false,_,_,_,_>(min, max)
+}
+
+/// Parse a signed integer of at least `min` and at most `max` digits, not counting the sign.
+/// The type of the integer will be inferred from the context.
+/// At least one digit is always required, and no more than `max` digits are consumed even if
+/// more follow. This parser will fail if the result does not fit in the inferred integer type.
+///
+/// ### Arguments
+/// * `min` - the minimum number of digits
+/// * `max` - the maximum number of digits
+#[inline]
+pub fn integer_signed_digits<O: NumLike,
+                             A: CharLike,
+                             I: SliceLike<RefItem = A>,
+                             S>(min: usize, max: usize) -> impl Parser<I, O, S> {
+    integer_internal::<true, true,_,_,_,_>(min, max)
+}
+
+/// The number of significant digits that are kept when parsing a float. This is enough to
+/// correctly round any decimal number to a `f64`, as long as it's known whether any of the
+/// dropped digits are non-zero.
+const MAX_DIGITS: usize = 768;
+
+/// A decimal number equal to `digits * 10^exp`, with the digits stored as ASCII.
+struct Decimal {
+    /// The significant digits, with room for a sticky digit and an exponent, see
+    /// [`into_float`](Decimal::into_float).
+    digits: [u8; MAX_DIGITS + 13],
+    len: usize,
+    exp: i32,
+
+    /// Whether any non-zero digits were dropped, since they didn't fit in `digits`.
+    truncated: bool
+}
+
+impl Decimal {
+    fn new() -> Self {
+        Self { digits: [0; MAX_DIGITS + 13], len: 0, exp: 0, truncated: false }
+    }
+
+    #[inline(always)]
+    fn push(&mut self, digit: u32, fraction: bool) {
+        if self.len == 0 && digit == 0 {
+            // Leading zeros are not significant.
+            self.exp = self.exp.saturating_sub(fraction as i32);
+        } else if self.len < MAX_DIGITS {
+            self.digits[self.len] = b'0' + digit as u8;
+            self.len += 1;
+            self.exp = self.exp.saturating_sub(fraction as i32);
+        } else {
+            self.truncated |= digit != 0;
+            self.exp = self.exp.saturating_add(!fraction as i32);
+        }
+    }
+
+    /// Convert to the closest float, or infinity if the number is too large.
+    fn into_float<O: FromStr>(mut self) -> Option<O> {
+        // The dropped digits can only affect the rounding by not being all zero, which a
+        // single non-zero digit after the kept ones also accomplishes.
+        if self.truncated {
+            self.digits[self.len] = b'1';
+            self.len += 1;
+            self.exp = self.exp.saturating_sub(1);
+        }
+
+        self.digits[self.len] = b'e';
+        self.len += 1;
+        if self.exp < 0 {
+            self.digits[self.len] = b'-';
+            self.len += 1;
+        }
+
+        let exp_start = self.len;
+        let mut exp = self.exp.unsigned_abs();
+        loop {
+            self.digits[self.len] = b'0' + (exp % 10) as u8;
+            self.len += 1;
+            exp /= 10;
+            if exp == 0 {
+                break
+            }
+        }
+        self.digits[exp_start..self.len].reverse();
+
+        // Only ASCII has been written, so it's always valid UTF-8.
+        from_utf8(&self.digits[..self.len]).ok()?.parse().ok()
+    }
+}
+
+/// A decimal number equal to `mantissa * 10^exp`, for numbers with at most 19 significant
+/// digits, which always fit in a `u64`. This avoids building a [`Decimal`] for most numbers.
+#[derive(Default)]
+struct ShortDecimal {
+    mantissa: u64,
+    digits: usize,
+    exp: i32
+}
+
+impl ShortDecimal {
+    #[inline(always)]
+    fn push(&mut self, digit: u32, fraction: bool) {
+        // Leading zeros are not significant. The mantissa is only used with at most 19 digits,
+        // so it may wrap.
+        if self.mantissa != 0 || digit != 0 {
+            self.mantissa = self.mantissa.wrapping_mul(10).wrapping_add(digit as u64);
+            self.digits += 1;
+        }
+        self.exp = self.exp.saturating_sub(fraction as i32);
+    }
+
+    /// Convert to a float, if it can be done exactly, see [`FloatLike::from_decimal`].
+    #[inline(always)]
+    fn into_float<O: FloatLike>(self) -> Option<O> {
+        if self.digits > 19 {
+            return None
+        }
+        O::from_decimal(self.mantissa, self.exp)
+    }
+}
+
+/// Call `push` with each digit at the start of `input`. Returns the number of digits
+/// together with the rest of the input.
+#[inline(always)]
+fn push_digits<A: CharLike, I: SliceLike<RefItem = A>>(input: I,
+                                                       push: &mut impl FnMut(u32, bool),
+                                                       fraction: bool) -> (usize, I) {
+    let mut idx: I::Idx = Default::default();
+    let mut count = 0;
+    for digit in input.slice_iter().map_while(|d| d.to_digit(10)) {
+        push(digit, fraction);
+        idx += true.into();
+        count += 1;
+    }
+    (count, input.slice_from(idx))
+}
+
+/// Call `push` with each digit of the number on the form `[0-9]+(.[0-9]+)?` at the start of
+/// `input`, together with whether it's part of the fraction. Returns the rest of the input,
+/// or `None` if there is no number.
+#[inline(always)]
+fn push_number<A: CharLike, I: SliceLike<RefItem = A>>(input: I, mut push: impl FnMut(u32, bool)) -> Option<I> {
+    let (count, mut input) = push_digits(input, &mut push, false);
+    if count == 0 {
+        return None
+    }
+
+    // The period is only part of the number if followed by a digit.
+    if let Some((_, rest)) = input.slice_first_if(|c| c.as_char() == '.') {
+        let (count, rest) = push_digits(rest, &mut push, true);
+        if count > 0 {
+            input = rest;
+        }
+    }
+    Some(input)
 }
 
 #[inline(always)]
 fn float_internal<const CHECKED: bool,
-                  O: FloatLike,
+                  O: FloatLike + FromStr,
                   A: CharLike,
                   I: SliceLike<RefItem = A>,
                   S>() -> impl Parser<I, O, S> {
-    // First parse a possibly negative signed integer
-    integer_internal::<CHECKED, true, false,_,_,_,_>().bind(|(n, _, is_neg)| {
-        // Then parse a period followed by an unsigned integer.
-        let dec = right(item_if(|c: I::RefItem| c.as_char() == '.'),
-                                              integer_internal::<CHECKED,false,true,_,_,_,_>())
-            .map(move |(dec, div, _)|
-                O::cast_isize(n) + if is_neg {O::MINUS_ONE} else {O::ONE} * O::cast_usize(dec) / O::cast_usize(div));
-        or(dec, pure!(O::cast_isize(n)))
+    create_parser!(s, {
+        let (is_negative, input) = match s.input.slice_first_if(|c| c.as_char() == '-') {
+            Some((_, rest)) => (true, rest),
+            None => (false, s.input)
+        };
+
+        let mut short = ShortDecimal::default();
+        let rest = push_number(input, |digit, fraction| short.push(digit, fraction))?;
+
+        let n = match short.into_float::<O>() {
+            Some(n) => n,
+            None => {
+                // Too many digits or a too large exponent, so all digits are needed.
+                let mut decimal = Decimal::new();
+                push_number(input, |digit, fraction| decimal.push(digit, fraction));
+                decimal.into_float::<O>()?
+            }
+        };
+        if CHECKED && !n.is_finite() {
+            return None
+        }
+
+        s.input = rest;
+        Some(if is_negative { O::MINUS_ONE * n } else { n })
     })
 }
 
-/// Parse a floating point number. The type of the number will be inferred from the context.
-/// This parser is incomplete, in that it will attempt to parse the float as
-/// `isize.usize`, and if the parsed number does not fit within those types, it will panic.
+/// Parse a floating point number on the form `-?[0-9]+(.[0-9]+)?`. The type of the number
+/// will be inferred from the context.
+///
+/// The number is correctly rounded, regardless of its number of digits. Numbers too large
+/// for the type are parsed as infinity, use [`float_checked`] to fail instead. Use
+/// [`float_fast`] to also parse an exponent.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::number::float;
+///
+/// let res = parse(float(), "-13.37.");
+/// assert_eq!(res.result, Some(-13.37f64));
+/// assert_eq!(res.state, ".");
+/// ```
 #[inline]
-pub fn float<O: FloatLike, A: CharLike, I: SliceLike<RefItem = A>, S>() -> impl Parser<I, O, S> {
+pub fn float<O: FloatLike + FromStr, A: CharLike, I: SliceLike<RefItem = A>, S>() -> impl Parser<I, O, S> {
     float_internal::<false,_,_,_,_>()
 }
 
-/// Parse a floating point number. The type of the number will be inferred from the context.
-/// This parser is incomplete, in that it will attempt to parse the float as
-/// `isize.usize`, and if the parsed number does not fit within those types, it will fail.
+/// Parse a floating point number on the form `-?[0-9]+(.[0-9]+)?`. The type of the number
+/// will be inferred from the context.
+/// This parser will fail if the number is too large for the inferred type.
 #[inline]
-pub fn float_checked<O: FloatLike,
+pub fn float_checked<O: FloatLike + FromStr,
                      A: CharLike,
                      I: SliceLike<RefItem = A>,
                      S>() -> impl Parser<I, O, S> {
     float_internal::<true,_,_,_,_>()
 }
 
+/// Split a number on the form `-?[0-9]*(.[0-9]*)?([eE][+-]?[0-9]+)?` into its sign, mantissa
+/// and exponent. Returns `None` if the number doesn't match, or if the mantissa doesn't fit in
+/// a `u64`.
+#[inline(always)]
+fn decimal_parts(bytes: &[u8]) -> Option<(bool, u64, i32)> {
+    let (neg, mut bytes) = match bytes.split_first() {
+        Some((b'-', rest)) => (true, rest),
+        _ => (false, bytes)
+    };
+
+    let mut mantissa = 0u64;
+    let mut digits = 0;
+    let mut exp = 0i32;
+    let mut in_fraction = false;
+
+    while let Some((&b, rest)) = bytes.split_first() {
+        match b {
+            b'0'..=b'9' => {
+                // 19 digits always fit in a `u64`.
+                digits += 1;
+                if digits > 19 {
+                    return None
+                }
+                mantissa = mantissa * 10 + (b - b'0') as u64;
+                exp -= in_fraction as i32;
+            },
+            b'.' if !in_fraction => in_fraction = true,
+            _ => break
+        }
+        bytes = rest;
+    }
+
+    if digits == 0 {
+        return None
+    }
+
+    if let Some((b'e' | b'E', rest)) = bytes.split_first() {
+        let (exp_neg, rest) = match rest.split_first() {
+            Some((b'-', rest)) => (true, rest),
+            Some((b'+', rest)) => (false, rest),
+            _ => (false, rest)
+        };
+
+        // Larger exponents are left to the slow path.
+        if rest.is_empty() || rest.len() > 4 {
+            return None
+        }
+
+        let mut e = 0i32;
+        for &b in rest {
+            if !b.is_ascii_digit() {
+                return None
+            }
+            e = e * 10 + (b - b'0') as i32;
+        }
+        exp += if exp_neg { -e } else { e };
+    } else if !bytes.is_empty() {
+        return None
+    }
+
+    Some((neg, mantissa, exp))
+}
+
+/// Get the length of the longest prefix of `bytes` on the form
+/// `[+-]?[0-9]*(.[0-9]+)?([eE][+-]?[0-9]+)?`, i.e. the span of a possible number. As for
+/// [`float`], the period and the exponent are only part of the number if followed by a digit.
+#[inline(always)]
+fn number_len(bytes: &[u8]) -> usize {
+    let digits = |pos: usize| get_byte_pos(&bytes[pos..], !in_range(b'0', b'9'))
+        .map_or(bytes.len(), |len| pos + len);
+    let sign = |pos: usize| pos + matches!(bytes.get(pos), Some(b'+' | b'-')) as usize;
+
+    let mut pos = digits(sign(0));
+    if bytes.get(pos) == Some(&b'.') && matches!(bytes.get(pos + 1), Some(b'0'..=b'9')) {
+        pos = digits(pos + 1);
+    }
+    if matches!(bytes.get(pos), Some(b'e' | b'E')) {
+        let start = sign(pos + 1);
+        if matches!(bytes.get(start), Some(b'0'..=b'9')) {
+            pos = digits(start);
+        }
+    }
+    pos
+}
+
+/// Convert the span of a number, as found by [`number_len`], to a float.
+#[inline(always)]
+pub(crate) fn convert_number<O: FloatLike + FromStr>(bytes: &[u8]) -> Option<O> {
+    decimal_parts(bytes)
+        .and_then(|(neg, mantissa, exp)| O::from_decimal(mantissa, exp)
+            .map(|n| if neg { O::MINUS_ONE * n } else { n }))
+        // The span only contains ASCII, so it's always valid UTF-8.
+        .or_else(|| from_utf8(bytes).ok().and_then(|n| n.parse().ok()))
+}
+
+/// Parse a floating point number, including an optional exponent, e.g. `-1.5e-3`. The type
+/// of the number will be inferred from the context.
+///
+/// The span of the number, on the form `[+-]?[0-9]*(.[0-9]+)?([eE][+-]?[0-9]+)?`, is first
+/// found using a fast byte search for the digits. Like for [`float`], a period or an exponent
+/// marker not followed by a digit is not part of the number. Numbers whose significant digits
+/// form an integer below 2^53 (2^24 for `f32`), and whose decimal exponent is at most 22 (10
+/// for `f32`) in magnitude, are then converted directly with a single correctly rounded
+/// operation. All other numbers are converted using [`FromStr`], so the result is always
+/// correctly rounded. The parser fails if the conversion fails.
+///
+/// Note that [`FromStr`] for floats accepts some numbers that e.g. JSON doesn't, such as
+/// `+1` and `.5`.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::number::float_fast;
+///
+/// let res = parse(float_fast(), "-1.25e2,");
+/// assert_eq!(res.result, Some(-125.0f64));
+/// assert_eq!(res.state, ",");
+///
+/// let res = parse(float_fast(), "1/2");
+/// assert_eq!(res.result, Some(1.0f64));
+/// assert_eq!(res.state, "/2");
+/// ```
+#[inline]
+pub fn float_fast<'a, O: FloatLike + FromStr, I: ByteInput<'a>, S>() -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let pos = s.input;
+        let bytes;
+        (bytes, s.input) = s.input.slice_split_at(number_len(s.input.as_byte_slice()));
+        let res = convert_number(bytes.as_byte_slice());
+        if res.is_none() {
+            s.input = pos;
+        }
+        res
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{core::parse, number::{integer, integer_checked, float, integer_signed, integer_signed_checked}};
+    use crate::{core::parse, number::{integer, integer_checked, integer_digits, integer_signed_digits, float, float_checked, float_fast, integer_signed, integer_signed_checked}};
 
     #[test]
     fn unsigned_integer() {
@@ -238,6 +636,26 @@ mod tests {
         assert!((parse(integer_signed_checked(), "128").result as Option<i8>).is_none());
     }
 
+    #[test]
+    fn integer_digits_test() {
+        let res = parse(integer_digits::<u32, _, _, _>(2, 3), "12345");
+        assert_eq!(res.result, Some(123));
+        assert_eq!(res.state, "45");
+        assert_eq!(parse(integer_digits::<u32, _, _, _>(2, 3), "12").result, Some(12));
+        assert_eq!(parse(integer_digits::<u32, _, _, _>(2, 3), "1x").result, None);
+        assert_eq!(parse(integer_digits::<u8, _, _, _>(1, 3), "256").result, None);
+        assert_eq!(parse(integer_digits::<u8, _, _, _>(0, 3), "x").result, None);
+
+        let res = parse(integer_signed_digits::<i32, _, _, _>(1, 2), "-123");
+        assert_eq!(res.result, Some(-12));
+        assert_eq!(res.state, "3");
+        let res = parse(integer_signed_digits::<i32, _, _, _>(1, 2), "123");
+        assert_eq!(res.result, Some(12));
+        assert_eq!(res.state, "3");
+        assert_eq!(parse(integer_signed_digits::<i32, _, _, _>(2, 2), "-1").result, None);
+        assert_eq!(parse(integer_signed_digits::<i32, _, _, _>(1, 1), "-").result, None);
+    }
+
     #[test]
     fn float_test() {
         assert_eq!(0f32, parse(float(), "0").result.unwrap());
@@ -250,5 +668,118 @@ mod tests {
         assert_eq!(1.123f32, parse(float(), "1.123").result.unwrap());
         assert_eq!(0.001f32, parse(float(), "0.001").result.unwrap());
         assert_eq!(-0.001f32, parse(float(), "-0.001").result.unwrap());
+
+        // Larger than `isize`, and more digits than fit in a `u64`.
+        assert_eq!(1e30f64, parse(float(), "1000000000000000000000000000000").result.unwrap());
+        assert_eq!(0.30000000000000004f64, parse(float(), "0.30000000000000004").result.unwrap());
+        let mut max = [b'0'; 311];
+        max[..17].copy_from_slice(b"17976931348623157");
+        max[309] = b'.';
+        assert_eq!(f64::MAX, parse(float(), max.as_slice()).result.unwrap());
+        let mut min = [b'0'; 326];
+        min[1] = b'.';
+        min[325] = b'5';
+        assert_eq!(5e-324f64, parse(float(), min.as_slice()).result.unwrap());
+        assert_eq!(f32::INFINITY, parse(float(), "1000000000000000000000000000000000000000").result.unwrap());
+        assert_eq!(None, parse(float_checked::<f32, _, _, _>(), "1000000000000000000000000000000000000000").result);
+        assert_eq!(Some(1e38f32), parse(float_checked(), "100000000000000000000000000000000000000").result);
+
+        // Halfway between 1 and the next `f64`, with a non-zero digit beyond `MAX_DIGITS`.
+        let halfway = "1.00000000000000011102230246251565404236316680908203125";
+        assert_eq!(1f64, parse(float(), halfway).result.unwrap());
+        let mut above_halfway = [b'0'; 1000];
+        above_halfway[..halfway.len()].copy_from_slice(halfway.as_bytes());
+        above_halfway[999] = b'1';
+        assert_eq!(1f64 + f64::EPSILON, parse(float(), above_halfway.as_slice()).result.unwrap());
+
+        // Leading zeros are not significant, but count towards the exponent in the fraction.
+        assert_eq!(12.5f64, parse(float(), "000000000000000000000012.5").result.unwrap());
+        assert_eq!(1e-24f64, parse(float(), "0.000000000000000000000001").result.unwrap());
+        assert_eq!(12345678901234567890f64, parse(float(), "12345678901234567890").result.unwrap());
+
+        let res = parse(float::<f64, _, _, _>(), b"-1.x".as_slice());
+        assert_eq!(res.result, Some(-1.0));
+        assert_eq!(res.state, b".x");
+
+        for input in ["", "-", ".5", "-.5", "x"] {
+            let res = parse(float::<f64, _, _, _>(), input);
+            assert_eq!(res.result, None);
+            assert_eq!(res.state, input);
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn float_fast_test() {
+        assert_eq!(0f64, parse(float_fast(), "0").result.unwrap());
+        assert_eq!(-13.37f64, parse(float_fast(), "-13.37").result.unwrap());
+        assert_eq!(0.1f32, parse(float_fast(), "0.1").result.unwrap());
+        assert_eq!(1.5e-7f64, parse(float_fast(), "1.5e-7").result.unwrap());
+        assert_eq!(2e300f64, parse(float_fast(), "2E+300").result.unwrap());
+
+        // Too many digits or a too large exponent for the fast path.
+        assert_eq!(0.30000000000000004f64, parse(float_fast(), "0.30000000000000004").result.unwrap());
+        assert_eq!(123456789.0f32, parse(float_fast(), "123456789").result.unwrap());
+        assert_eq!(1e-30f64, parse(float_fast(), "1e-30").result.unwrap());
+
+        let res = parse(float_fast(), "1.5,2");
+        assert_eq!(res.result, Some(1.5f64));
+        assert_eq!(res.state, ",2");
+
+        // The number ends at the first byte that can't continue it.
+        for (input, expected, rest) in [("1+2", 1.0, "+2"), ("1/2", 1.0, "/2"), ("2024-01-01", 2024.0, "-01-01"),
+                                        ("1.2.3", 1.2, ".3"), ("-1e-2-3", -0.01, "-3"), ("+.5x", 0.5, "x"),
+                                        ("2em", 2.0, "em"), ("1e", 1.0, "e"), ("1e+", 1.0, "e+"),
+                                        ("3.px", 3.0, ".px"), ("1.e5", 1.0, ".e5")] {
+            let res = parse(float_fast::<f64, _, ()>(), input);
+            assert_eq!(res.result, Some(expected), "{}", input);
+            assert_eq!(res.state, rest);
+        }
+
+        for input in ["", "-", ".", "e5", "-.e1", "/1"] {
+            let res = parse(float_fast::<f64, _, ()>(), input);
+            assert_eq!(res.result, None);
+            assert_eq!(res.state, input);
+        }
+    }
+
+    #[test]
+    fn float_like_defaults() {
+        use crate::number::FloatLike;
+
+        // A type implementing only the required items, which uses the slow path.
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Float(f64);
+
+        impl core::ops::Add for Float {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self { Float(self.0 + rhs.0) }
+        }
+
+        impl core::ops::Mul for Float {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self { Float(self.0 * rhs.0) }
+        }
+
+        impl core::ops::Div for Float {
+            type Output = Self;
+            fn div(self, rhs: Self) -> Self { Float(self.0 / rhs.0) }
+        }
+
+        impl core::str::FromStr for Float {
+            type Err = core::num::ParseFloatError;
+            fn from_str(s: &str) -> Result<Self, Self::Err> { s.parse().map(Float) }
+        }
+
+        impl FloatLike for Float {
+            const ONE: Self = Float(1.0);
+            const MINUS_ONE: Self = Float(-1.0);
+        }
+
+        assert_eq!(parse(float_fast(), "-1.5e2").result, Some(Float(-150.0)));
+        assert_eq!(parse(float_checked(), "2.5").result, Some(Float(2.5)));
+
+        #[allow(deprecated)]
+        let casts = (Float::cast_usize(0), Float::cast_usize(1337), Float::cast_isize(-42));
+        assert_eq!(casts, (Float(0.0), Float(1337.0), Float(-42.0)));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{combinators::{attempt, bind, fold, left, no_separator, not_empty, or, or_default, right}, core::{Parser, ParserExt}, findbyte::{eq, take_while_byteclass, ByteInput}};
+
+/// Succeed without consuming anything if the input doesn't continue with an ASCII letter,
+/// i.e. at the end of a word.
+fn word_end<'a, I: ByteInput<'a>, S>() -> impl Parser<I, (), S> {
+    create_parser!(s, {
+        let next = s.input.as_byte_slice().first();
+        (!next.is_some_and(u8::is_ascii_alphabetic)).then_some(())
+    })
+}
+
+/// Transform a parser to only succeed if it parses a whole word, and not e.g. the start of
+/// `seventy` when parsing `seven`.
+fn word<'a, I: ByteInput<'a>, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, O, S> {
+    attempt(left(p, word_end()))
+}
+
+/// Parse one or more spaces.
+fn spaces<'a, I: ByteInput<'a>, S>() -> impl Parser<I, I, S> {
+    not_empty(take_while_byteclass(eq(b' ')))
+}
+
+/// Transform a parser to a parser of one or more spaces followed by the parser.
+fn after_spaces<'a, I: ByteInput<'a>, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, O, S> {
+    attempt(right(spaces(), p))
+}
+
+/// Parse a roman numeral between `I` and `MMMCMXCIX` (1 and 3999), in the standard
+/// subtractive form, e.g. `XIV`, but not `XIIII` or `IC`. Only upper case letters are
+/// accepted.
+///
+/// ### Consuming
+/// The numeral, on successful parse. The parser fails if the numeral is followed by a letter,
+/// e.g. an invalid numeral such as `IIII`.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::numeral::roman_numeral;
+///
+/// assert_eq!(parse(roman_numeral(), "MCMXCIV").result, Some(1994));
+/// assert_eq!(parse(roman_numeral(), "XLII.").result, Some(42));
+/// assert_eq!(parse(roman_numeral(), "IIII").result, None);
+/// assert_eq!(parse(roman_numeral(), "VX").result, None);
+/// ```
+#[inline]
+pub fn roman_numeral<'a, I: ByteInput<'a>, S>() -> impl Parser<I, u16, S> {
+    // The digits of each decimal place are independent, and written from the largest place.
+    let thousands = keywords!("M" => 1000, "MM" => 2000, "MMM" => 3000);
+    let hundreds = keywords!("C" => 100, "CC" => 200, "CCC" => 300, "CD" => 400, "D" => 500,
+                             "DC" => 600, "DCC" => 700, "DCCC" => 800, "CM" => 900);
+    let tens = keywords!("X" => 10, "XX" => 20, "XXX" => 30, "XL" => 40, "L" => 50,
+                         "LX" => 60, "LXX" => 70, "LXXX" => 80, "XC" => 90);
+    let ones = keywords!("I" => 1, "II" => 2, "III" => 3, "IV" => 4, "V" => 5,
+                         "VI" => 6, "VII" => 7, "VIII" => 8, "IX" => 9);
+
+    let places = tuplify!(or_default(thousands), or_default(hundreds), or_default(tens), or_default(ones));
+    word(places)
+        .map(|(m, c, x, i)| m + c + x + i)
+        .filter(|n| *n > 0)
+}
+
+/// The groups of an English number, e.g. `twenty thousand` followed by `five`, where the
+/// scale of each group must be smaller than that of the previous group.
+#[derive(Clone, Copy)]
+struct Groups {
+    total: u64,
+    scale: u64,
+    valid: bool
+}
+
+impl Groups {
+    fn push(&mut self, (n, scale): (u64, u64)) {
+        self.valid &= scale < self.scale;
+        self.scale = scale;
+        self.total += n * scale;
+    }
+}
+
+/// Parse a number spelled out in English, from `zero` up to one less than a quadrillion, e.g.
+/// `twenty-three` or `one hundred and five thousand six`.
+///
+/// The words must be lower case and separated by spaces, but tens and ones may also be
+/// joined by `-`. The scales `thousand`, `million`, `billion` and `trillion` must be in
+/// decreasing order, and `and` is allowed after `hundred`.
+///
+/// ### Consuming
+/// The words of the number, on successful parse. The parser fails if the scales are out of
+/// order, e.g. for `one thousand two million`.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::numeral::english_number;
+///
+/// assert_eq!(parse(english_number(), "twenty-three").result, Some(23));
+/// assert_eq!(parse(english_number(), "four million two hundred and one").result, Some(4_000_201));
+///
+/// let res = parse(english_number(), "seventy seven apples");
+/// assert_eq!(res.result, Some(77));
+/// assert_eq!(res.state, " apples");
+/// ```
+#[inline]
+pub fn english_number<'a, I: ByteInput<'a>, S>() -> impl Parser<I, u64, S> {
+    let ones = word(keywords!(
+        "one" => 1, "two" => 2, "three" => 3, "four" => 4, "five" => 5, "six" => 6,
+        "seven" => 7, "eight" => 8, "nine" => 9, "ten" => 10, "eleven" => 11, "twelve" => 12,
+        "thirteen" => 13, "fourteen" => 14, "fifteen" => 15, "sixteen" => 16,
+        "seventeen" => 17, "eighteen" => 18, "nineteen" => 19
+    ));
+    let tens = word(keywords!(
+        "twenty" => 20, "thirty" => 30, "forty" => 40, "fifty" => 50, "sixty" => 60,
+        "seventy" => 70, "eighty" => 80, "ninety" => 90
+    ));
+    let scale = word(keywords!(
+        "thousand" => 1_000, "million" => 1_000_000, "billion" => 1_000_000_000,
+        "trillion" => 1_000_000_000_000
+    ));
+    let hundred = word(keywords!("hundred" => 100));
+    let and = word(keywords!("and" => ()));
+    let hyphen = keywords!("-" => ());
+
+    let tens_ones = or(attempt(right(hyphen, ones)), after_spaces(ones));
+    let below_hundred = or(tuplify!(tens, or_default(tens_ones)).map(|(t, o)| t + o), ones);
+
+    let hundreds = attempt(tuplify!(ones, after_spaces(hundred))).map(|(n, h)| n * h);
+    let rest = after_spaces(right(or_default(attempt(left(and, spaces()))), below_hundred));
+    let below_thousand = or(tuplify!(hundreds, or_default(rest)).map(|(h, n)| h + n), below_hundred);
+
+    let group = tuplify!(below_thousand, or(after_spaces(scale), pure!(1)));
+    let groups = bind(group, move |first| {
+        let init = move || {
+            let mut groups = Groups { total: 0, scale: u64::MAX, valid: true };
+            groups.push(first);
+            groups
+        };
+        fold(after_spaces(group), init, Groups::push, true, no_separator())
+    });
+
+    or(word(keywords!("zero" => 0)), groups.filter(|g| g.valid).map(|g| g.total))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{core::parse, numeral::{english_number, roman_numeral}};
+
+    #[test]
+    fn roman_numeral_test() {
+        let cases = [("I", 1), ("IV", 4), ("IX", 9), ("XIV", 14), ("XL", 40), ("XC", 90),
+                     ("CD", 400), ("CM", 900), ("MMXXIV", 2024), ("MMMCMXCIX", 3999)];
+        for (input, expected) in cases {
+            assert_eq!(parse(roman_numeral(), input).result, Some(expected));
+        }
+
+        for input in ["", "IIII", "IC", "VV", "MMMM", "XM", "iv", "IVx"] {
+            assert_eq!(parse(roman_numeral::<&str, ()>(), input).result, None, "{}", input);
+        }
+
+        let res = parse(roman_numeral(), b"XII)".as_slice());
+        assert_eq!(res.result, Some(12));
+        assert_eq!(res.state, b")");
+    }
+
+    #[test]
+    fn english_number_test() {
+        let cases = [("zero", 0), ("seven", 7), ("seventeen", 17), ("seventy", 70),
+                     ("seventy-seven", 77), ("ninety nine", 99), ("one hundred", 100),
+                     ("one hundred and one", 101), ("three hundred twelve", 312),
+                     ("twenty thousand", 20_000),
+                     ("one million two hundred thousand and", 1_200_000),
+                     ("nine hundred ninety-nine trillion nine hundred ninety-nine billion \
+                       nine hundred ninety-nine million nine hundred ninety-nine thousand \
+                       nine hundred ninety-nine", 999_999_999_999_999)];
+        for (input, expected) in cases {
+            assert_eq!(parse(english_number(), input).result, Some(expected), "{}", input);
+        }
+
+        for input in ["", "thousand", "one thousand two thousand", "five million one billion",
+                      "seventyseven", "Seven", "hundred"] {
+            assert_eq!(parse(english_number::<&str, ()>(), input).result, None, "{}", input);
+        }
+
+        let res = parse(english_number(), "one hundred and, two");
+        assert_eq!(res.result, Some(100));
+        assert_eq!(res.state, " and, two");
+    }
+}
@@ -0,0 +1,132 @@
+use std::{num::NonZeroUsize, thread, vec::Vec};
+
+use crate::{core::{parse, Parser}, findbyte::{find_split_point, ByteFinder, ByteInput}};
+
+/// Inputs smaller than this are parsed on the calling thread.
+const MIN_CHUNK_LEN: usize = 64 * 1024;
+
+/// Split `input` into records ending with a byte matched by `boundary`, e.g. lines, and parse
+/// each record using `p` on multiple threads. The results are returned in the same order as
+/// the records.
+///
+/// The boundary byte is not included in the records, and the input after the last boundary
+/// is only a record if it isn't empty, i.e. the same as for
+/// [`RecordReader::next_record`](crate::read::RecordReader::next_record). Like for the
+/// parsers in [`findbyte`](crate::findbyte), matches that are not at a character boundary of
+/// a `&str` are ignored.
+///
+/// The input is split into one chunk per available thread, so the records should be roughly
+/// equally expensive to parse. Small inputs are parsed on the calling thread.
+///
+/// ### Arguments
+/// * `p` - the parser for a record
+/// * `input` - the input to be parsed
+/// * `boundary` - the finder for the byte that ends each record
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::findbyte::eq;
+/// use anpa::number::integer;
+/// use anpa::parallel::parse_records_parallel;
+///
+/// let input: String = (0..100000).map(|n| format!("{}\n", n)).collect();
+/// let numbers = parse_records_parallel(integer::<u32, _, _, _>(), input.as_str(), eq(b'\n'));
+///
+/// assert_eq!(numbers.len(), 100000);
+/// assert!(numbers.iter().enumerate().all(|(i, n)| *n == Some(i as u32)));
+/// ```
+pub fn parse_records_parallel<'a, I: ByteInput<'a> + Send, O: Send>(p: impl Parser<I, O> + Send,
+                                                                     input: I,
+                                                                     boundary: impl ByteFinder + Send
+) -> Vec<Option<O>> {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let threads = threads.min(input.slice_len() / MIN_CHUNK_LEN).max(1);
+    let chunks = split_chunks(input, boundary, threads);
+
+    if chunks.len() <= 1 {
+        return chunks.into_iter().flat_map(|c| parse_chunk(p, c, boundary)).collect()
+    }
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = chunks.into_iter()
+            .map(|chunk| scope.spawn(move || parse_chunk(p, chunk, boundary)))
+            .collect();
+
+        handles.into_iter()
+            .flat_map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    })
+}
+
+/// Split `input` into at most `count` chunks of roughly equal size, each ending right
+/// after a boundary, except for the last one.
+fn split_chunks<'a, I: ByteInput<'a>>(input: I, boundary: impl ByteFinder, count: usize) -> Vec<I> {
+    let mut chunks = Vec::with_capacity(count);
+    let mut rest = input;
+    for i in (1..count).rev() {
+        let target = rest.slice_len() / (i + 1);
+        let Some(pos) = find_boundary_from(rest, boundary, target) else {
+            break
+        };
+        chunks.push(rest.slice_to(pos + 1));
+        rest = rest.slice_from(pos + 1);
+    }
+
+    if !rest.slice_is_empty() {
+        chunks.push(rest);
+    }
+    chunks
+}
+
+/// Get the position of the first boundary at or after `from`.
+fn find_boundary_from<'a, I: ByteInput<'a>>(input: I, boundary: impl ByteFinder, from: usize) -> Option<usize> {
+    let mut from = from;
+    while !input.is_split_point(from) {
+        from += 1;
+    }
+    find_split_point(input.slice_from(from), boundary).map(|pos| from + pos)
+}
+
+fn parse_chunk<'a, I: ByteInput<'a>, O>(p: impl Parser<I, O>, chunk: I, boundary: impl ByteFinder) -> Vec<Option<O>> {
+    let mut results = Vec::new();
+    let mut rest = chunk;
+    while !rest.slice_is_empty() {
+        let (record, next) = match find_split_point(rest, boundary) {
+            Some(pos) => (rest.slice_to(pos), rest.slice_from(pos + 1)),
+            None => (rest, rest.slice_from(rest.slice_len()))
+        };
+        results.push(parse(p, record).result);
+        rest = next;
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{string::String, vec::Vec};
+
+    use crate::{findbyte::eq, parallel::{parse_chunk, parse_records_parallel, split_chunks}, parsers::rest};
+
+    #[test]
+    fn chunks() {
+        let input = "aé;b;;cc;d";
+        let chunks = split_chunks(input, eq(b';'), 3);
+        assert_eq!(chunks, ["aé;", "b;;cc;", "d"]);
+        assert_eq!(split_chunks("a;b", eq(b';'), 4), ["a;", "b"]);
+        assert_eq!(split_chunks("a;", eq(b';'), 2), ["a;"]);
+        assert_eq!(split_chunks(b"ab".as_slice(), eq(b';'), 2), [b"ab"]);
+
+        let records: Vec<_> = chunks.into_iter().flat_map(|c| parse_chunk(rest(), c, eq(b';'))).collect();
+        assert_eq!(records, [Some("aé"), Some("b"), Some(""), Some("cc"), Some("d")]);
+    }
+
+    #[test]
+    fn parallel() {
+        let input: String = (0..200000).map(|n| if n % 7 == 0 { "x\n".into() } else { std::format!("{}\n", n) }).collect();
+        let res = parse_records_parallel(crate::number::integer::<u32, _, _, _>(), input.as_bytes(), eq(b'\n'));
+        assert_eq!(res.len(), 200000);
+        assert!(res.iter().enumerate().all(|(i, n)| *n == (i % 7 != 0).then_some(i as u32)));
+        assert!(parse_records_parallel(rest(), "", eq(b'\n')).is_empty());
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{core::Parser, needle::Needle, prefix::Prefix, slicelike::SliceLike};
+use core::convert::TryInto;
+
+use crate::{charlike::CharLike, combinators::not_empty, core::Parser, findbyte::ByteInput, needle::Needle, prefix::{AnyOf, LongestOf, Prefix}, slicelike::SliceLike, suffix::Suffix};
 
 /// Create a parser that always succeeds.
 #[inline]
@@ -128,6 +130,166 @@ pub fn skip<I: SliceLike, O, S>(prefix: impl Prefix<I, O>) -> impl Parser<I, (),
     skip!(prefix)
 }
 
+/// Create a parser for matching the provided suffix at the end of the input, e.g. a trailing
+/// marker or a file extension. Returns the parsed suffix on success.
+///
+/// The suffix can be anything implementing the [`Suffix`] trait for the parser input.
+/// Implementations are provided for single elements and sequences of both `&str`
+/// and `&[T]`.
+///
+/// ### Consuming
+/// Removes the suffix from the end of the input on successful parse. The start of the input
+/// is left as is.
+///
+/// ### Arguments
+/// * `suffix` - the suffix to match
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::parsers::take_suffix;
+///
+/// let res = parse(take_suffix(".tar.gz"), "archive.tar.gz");
+/// assert_eq!(res.result, Some(".tar.gz"));
+/// assert_eq!(res.state, "archive");
+/// assert_eq!(parse(take_suffix(".zip"), "archive.tar.gz").result, None);
+/// ```
+#[inline]
+pub fn take_suffix<I: SliceLike, O, S>(suffix: impl Suffix<I, O>) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let (res, rest) = suffix.take_suffix(s.input)?;
+        s.input = rest;
+        Some(res)
+    })
+}
+
+/// Create a parser for matching the provided suffix at the end of the input. See
+/// [`take_suffix`].
+///
+/// ### Consuming
+/// Removes the suffix from the end of the input on successful parse.
+///
+/// ### Arguments
+/// * `suffix` - the suffix to match
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::right;
+/// use anpa::parsers::{rest, skip_suffix};
+///
+/// let p = right(skip_suffix("\nEND"), rest());
+/// assert_eq!(parse(p, "a\nb\nEND").result, Some("a\nb"));
+/// ```
+#[inline]
+pub fn skip_suffix<I: SliceLike, O, S>(suffix: impl Suffix<I, O>) -> impl Parser<I, (), S> {
+    create_parser!(s, {
+        s.input = suffix.skip_suffix(s.input)?;
+        Some(())
+    })
+}
+
+/// Transform a parser to a parser that parses a suffix of the input, e.g. a trailing
+/// checksum. `p` is tried at each position starting from the end of the input, and the first
+/// position where `p` succeeds and consumes the rest of the input is used, i.e. the shortest
+/// suffix that `p` can parse.
+///
+/// Note that the search is linear in the length of the input, so `p` should fail quickly
+/// where it doesn't match.
+///
+/// ### Consuming
+/// Removes the suffix parsed by `p` from the end of the input on successful parse. Nothing is
+/// consumed on failure.
+///
+/// ### Arguments
+/// * `p` - the parser for the suffix
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::right;
+/// use anpa::number::integer;
+/// use anpa::parsers::{ends_with, rest, skip};
+/// use anpa::tuplify;
+///
+/// let checksum = ends_with(right(skip('*'), integer::<u8, _, _, _>()));
+/// let p = tuplify!(checksum, rest());
+///
+/// assert_eq!(parse(p, "a*b*42").result, Some((42, "a*b")));
+/// assert_eq!(parse(p, "a*b*x").result, None);
+/// ```
+#[inline]
+pub fn ends_with<'a, I: ByteInput<'a>, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let input = s.input;
+        for pos in (0..=input.slice_len()).rev().filter(|pos| input.is_split_point(*pos)) {
+            s.input = input.slice_from(pos);
+            if let Some(res) = p(s).filter(|_| s.input.slice_is_empty()) {
+                s.input = input.slice_to(pos);
+                return Some(res)
+            }
+        }
+        s.input = input;
+        None
+    })
+}
+
+/// Create a parser for matching the first of several candidate prefixes, e.g. keywords.
+/// Returns the index of the matching candidate along with the parsed prefix.
+///
+/// The candidates are tried in order, so if a candidate is a prefix of a later one (e.g.
+/// `"in"` and `"int"`), the later one will never match. Use [`take_longest`] in that case.
+///
+/// ### Consuming
+/// Consumes the matching prefix on successful parse
+///
+/// ### Arguments
+/// * `candidates` - the prefixes to match
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::parsers::take_any;
+///
+/// #[derive(Debug, PartialEq, Clone, Copy)]
+/// enum Method { Get, Post, Put }
+///
+/// let method = take_any(&["GET", "POST", "PUT"])
+///     .map(|(i, _)| [Method::Get, Method::Post, Method::Put][i]);
+///
+/// assert_eq!(parse(method, "POST /").result, Some(Method::Post));
+/// assert_eq!(parse(method, "HEAD /").result, None);
+/// ```
+#[inline]
+pub fn take_any<I: SliceLike, O, P: Prefix<I, O>, S>(candidates: &[P]) -> impl Parser<I, (usize, O), S> + '_ {
+    take!(AnyOf(candidates))
+}
+
+/// Create a parser for matching the longest of several candidate prefixes, e.g. operators.
+/// Returns the index of the matching candidate along with the parsed prefix. If several
+/// candidates match the same length, the first of them is chosen.
+///
+/// ### Consuming
+/// Consumes the matching prefix on successful parse
+///
+/// ### Arguments
+/// * `candidates` - the prefixes to match
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::parsers::take_longest;
+///
+/// let op = take_longest(&["<", "<=", "<<", "="]);
+///
+/// assert_eq!(parse(op, "<= 2").result, Some((1, "<=")));
+/// assert_eq!(parse(op, "< 2").result, Some((0, "<")));
+/// ```
+#[inline]
+pub fn take_longest<I: SliceLike, O, P: Prefix<I, O>, S>(candidates: &[P]) -> impl Parser<I, (usize, O), S> + '_ {
+    take!(LongestOf(candidates))
+}
+
 /// Create a parser that parses while the items in the input matches the predicate.
 ///
 /// This parser never fails, so if an empty parse should not be permitted, wrap it in
@@ -190,6 +352,110 @@ pub fn until<O, I: SliceLike, N: Needle<I, O>, S>(needle: N) -> impl Parser<I, I
     until!(needle)
 }
 
+/// Create a parser that parses until the input matches the provided argument, with control
+/// over whether the needle is included in the result, and whether it is consumed.
+///
+/// `until_with(needle, false, true)` is equivalent to [`until`](fn@until).
+///
+/// ### Consuming
+/// Consumes all items before the matching needle, and the needle itself if `consume` is `true`.
+///
+/// ### Arguments
+/// * `needle` - the needle to search for
+/// * `include` - whether to include the needle in the result
+/// * `consume` - whether to consume the needle
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::parsers::until_with;
+///
+/// let res = parse(until_with("-->", true, true), "comment --> rest");
+/// assert_eq!(res.result, Some("comment -->"));
+/// assert_eq!(res.state, " rest");
+///
+/// let res = parse(until_with("-->", false, false), "comment --> rest");
+/// assert_eq!(res.result, Some("comment "));
+/// assert_eq!(res.state, "--> rest");
+/// ```
+#[inline]
+pub fn until_with<O, I: SliceLike, N: Needle<I, O>, S>(needle: N,
+                                                       include: bool,
+                                                       consume: bool
+) -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        let (size, index) = needle.find_in(s.input)?;
+        let end = index + size;
+        let res = s.input.slice_to(if include { end } else { index });
+        s.input = s.input.slice_from(if consume { end } else { index });
+        Some(res)
+    })
+}
+
+/// Create a parser that parses until the last occurrence of the provided argument in the
+/// input, e.g. to split a path on the final `/`, or a file name on the last `.`.
+///
+/// The argument can be anything implementing the [`Needle`] trait for the parser input.
+///
+/// ### Consuming
+/// Consumes all items before the last matching needle, and the needle itself.
+///
+/// ### Arguments
+/// * `needle` - the needle to search for
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::parsers::until_last;
+///
+/// let res = parse(until_last('.'), "archive.tar.gz");
+/// assert_eq!(res.result, Some("archive.tar"));
+/// assert_eq!(res.state, "gz");
+/// assert_eq!(parse(until_last('.'), "README").result, None);
+/// ```
+#[inline]
+pub fn until_last<O, I: SliceLike, N: Needle<I, O>, S>(needle: N) -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        let (size, index) = needle.rfind_in(s.input)?;
+        let res = s.input.slice_to(index);
+        s.input = s.input.slice_from(index + size);
+        Some(res)
+    })
+}
+
+/// Create a parser that splits the input on the first occurrence of the provided argument,
+/// e.g. for `key=value` lines or headers. Returns the input before and after the needle.
+///
+/// This is equivalent to combining [`until`](fn@until) and [`rest`], but searches only once.
+///
+/// ### Consuming
+/// All input on successful parse. Nothing is consumed on failure.
+///
+/// ### Arguments
+/// * `needle` - the needle to search for
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::parsers::split_on;
+///
+/// let res = parse(split_on('='), "name=a=b");
+/// assert_eq!(res.result, Some(("name", "a=b")));
+/// assert_eq!(res.state, "");
+/// assert_eq!(parse(split_on(": "), "Host: example.com").result, Some(("Host", "example.com")));
+/// assert_eq!(parse(split_on('='), "name").result, None);
+/// ```
+#[inline]
+pub fn split_on<O, I: SliceLike, N: Needle<I, O>, S>(needle: N) -> impl Parser<I, (I, I), S> {
+    create_parser!(s, {
+        let (size, index) = needle.find_in(s.input)?;
+        let before = s.input.slice_to(index);
+        let after = s.input.slice_from(index + size);
+        s.input = after.slice_from(after.slice_len());
+        Some((before, after))
+    })
+}
+
 /// Create a parser that parses the rest of the input. This parser can never fail.
 ///
 /// ### Consuming
@@ -213,6 +479,117 @@ pub fn rest<I: SliceLike, S>() -> impl Parser<I, I, S> {
     })
 }
 
+/// Create a parser that parses a length using `len`, and then returns exactly that many
+/// following bytes, e.g. for length-prefixed fields in protocols such as netstrings. The
+/// length can be any type convertible to `usize`.
+///
+/// For `&str`, the parser fails if the length doesn't end at a character boundary.
+///
+/// ### Consuming
+/// The length and the returned bytes on successful parse. Nothing is consumed on failure.
+///
+/// ### Arguments
+/// * `len` - the parser for the length
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::left;
+/// use anpa::number::integer;
+/// use anpa::parsers::{length_take, skip};
+///
+/// // A netstring, e.g. `5:hello,`.
+/// let netstring = left(length_take(left(integer::<u32, _, _, _>(), skip(':'))), skip(','));
+///
+/// assert_eq!(parse(netstring, "5:hello,").result, Some("hello"));
+/// assert_eq!(parse(netstring, "6:hello,").result, None);
+/// ```
+#[inline]
+pub fn length_take<'a, I: ByteInput<'a>, N: TryInto<usize>, S>(len: impl Parser<I, N, S>) -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        let pos = s.input;
+        let res = len(s)
+            .and_then(|n| n.try_into().ok())
+            .filter(|n| *n <= s.input.slice_len() && s.input.is_split_point(*n))
+            .map(|n| s.input.slice_split_at(n));
+
+        match res {
+            Some((res, rest)) => {
+                s.input = rest;
+                Some(res)
+            },
+            None => {
+                s.input = pos;
+                None
+            }
+        }
+    })
+}
+
+/// Create a parser that parses a line, i.e. everything up to and including the next newline.
+/// Both `\n` and `\r\n` are accepted as newlines. The returned line does not include the
+/// newline.
+///
+/// The last line of the input doesn't need to end with a newline, but the parser fails if
+/// the input is empty.
+///
+/// ### Consuming
+/// The line, including the newline.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::parsers::line;
+///
+/// let res = parse(line(), "first\r\nsecond");
+/// assert_eq!(res.result, Some("first"));
+/// assert_eq!(res.state, "second");
+///
+/// let res = parse(line(), "second");
+/// assert_eq!(res.result, Some("second"));
+/// assert_eq!(res.state, "");
+/// ```
+#[inline]
+pub fn line<A: CharLike, I: SliceLike<RefItem = A>, S>() -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        if s.input.slice_is_empty() {
+            return None
+        }
+
+        let mut offset: I::Idx = Default::default();
+        loop {
+            let Some(idx) = s.input.slice_from(offset)
+                .slice_find_pred(|c: A| matches!(c.as_char(), '\r' | '\n')) else {
+                return rest()(s)
+            };
+
+            let pos = offset + idx;
+            let (line, newline) = s.input.slice_split_at(pos);
+            let (c, after) = newline.slice_first_if(|_| true)?;
+
+            if c.as_char() == '\n' {
+                s.input = after;
+                return Some(line)
+            } else if let Some((_, after)) = after.slice_first_if(|c: A| c.as_char() == '\n') {
+                s.input = after;
+                return Some(line)
+            }
+
+            // A lone `\r` is part of the line.
+            offset = pos + true.into();
+        }
+    })
+}
+
+/// Create a parser that parses a non-empty line. See [`line`](fn@line).
+///
+/// ### Consuming
+/// The line, including the newline, on successful parse.
+#[inline]
+pub fn non_empty_line<A: CharLike, I: SliceLike<RefItem = A>, S>() -> impl Parser<I, I, S> {
+    not_empty(line())
+}
+
 /// Create a parser that is successful only if the input is empty.
 /// Returns the empty input on success.
 ///
@@ -239,7 +616,7 @@ pub fn empty<I: SliceLike, S>() -> impl Parser<I, I, S> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{core::parse, parsers::until};
+    use crate::{combinators::{map, right}, core::parse, number::integer, parsers::{ends_with, item, length_take, line, non_empty_line, skip, skip_suffix, split_on, take_any, take_longest, take_suffix, until, until_last}};
 
     use super::item_while;
     #[test]
@@ -249,7 +626,7 @@ mod tests {
         assert_eq!(res.result.unwrap(), "xxxx");
         assert_eq!(res.state, "y");
 
-        let p = item_while(|c: char| c.is_digit(10));
+        let p = item_while(|c: char| c.is_ascii_digit());
         assert_eq!(parse(p, "1234abcd").result.unwrap(), "1234")
     }
 
@@ -270,4 +647,98 @@ mod tests {
         assert_eq!(res.result.unwrap(), "xxxx");
         assert_eq!(res.state, "");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn suffix_test() {
+        let res = parse(take_suffix('é'), "café");
+        assert_eq!((res.result, res.state), (Some('é'), "caf"));
+        assert_eq!(parse(skip_suffix("fé"), "café").state, "ca");
+        assert_eq!(parse(take_suffix("cafés"), "café").result, None);
+
+        let input: &[u8] = &[1, 2, 3];
+        let res = parse(take_suffix([2, 3].as_slice()), input);
+        assert_eq!((res.result, res.state), (Some([2, 3].as_slice()), [1].as_slice()));
+        assert_eq!(parse(take_suffix(3), input).result, Some(3));
+
+        let digits = ends_with(item_while(|c: char| c.is_ascii_digit()));
+        let res = parse(digits, "ü12");
+        assert_eq!((res.result, res.state), (Some(""), "ü12"));
+        let res = parse(ends_with(right(skip('ü'), until_last('2'))), "aü12");
+        assert_eq!((res.result, res.state), (Some("1"), "a"));
+        assert_eq!(parse(ends_with(skip('x')), "ab").state, "ab");
+    }
+
+    #[test]
+    fn until_last_test() {
+        let res = parse(until_last('/'), "usr/local/bin");
+        assert_eq!((res.result, res.state), (Some("usr/local"), "bin"));
+
+        let res = parse(until_last("xx"), "xxxxy");
+        assert_eq!((res.result, res.state), (Some("xx"), "y"));
+
+        let input: &[u8] = b"a;b;c";
+        let res = parse(until_last(b';'), input);
+        assert_eq!((res.result, res.state), (Some(b"a;b".as_slice()), b"c".as_slice()));
+        assert_eq!(parse(until_last(b";;".as_slice()), input).result, None);
+    }
+
+    #[test]
+    fn split_on_test() {
+        let res = parse(split_on("=="), "a==b==c");
+        assert_eq!((res.result, res.state), (Some(("a", "b==c")), ""));
+        assert_eq!(parse(split_on('='), "a=").result, Some(("a", "")));
+
+        let input: &[u8] = b"k:v";
+        assert_eq!(parse(split_on(b':'), input).result, Some((b"k".as_slice(), b"v".as_slice())));
+        assert_eq!(parse(split_on(b'='), input).state, input);
+    }
+
+    #[test]
+    fn take_any_test() {
+        let input: &[u8] = b"int x";
+        assert_eq!(parse(take_any(&[b"in".as_slice(), b"int"]), input).result, Some((0, b"in".as_slice())));
+        assert_eq!(parse(take_longest(&[b"in".as_slice(), b"int"]), input).result, Some((1, b"int".as_slice())));
+
+        let res = parse(take_any(&['+', '-']), "-1");
+        assert_eq!(res.result, Some((1, '-')));
+        assert_eq!(res.state, "1");
+        assert_eq!(parse(take_any::<_, &str, &str, _>(&[]), "x").result, None);
+    }
+
+    #[test]
+    fn line_test() {
+        let input = "a\r\n\nb\rc\nd\r";
+        let res = parse(line(), input);
+        assert_eq!(res.result, Some("a"));
+
+        let res = parse(line(), res.state);
+        assert_eq!(res.result, Some(""));
+
+        let res = parse(line(), res.state);
+        assert_eq!(res.result, Some("b\rc"));
+
+        let res = parse(line(), res.state);
+        assert_eq!(res.result, Some("d\r"));
+        assert_eq!(res.state, "");
+
+        assert!(parse(line(), "").result.is_none());
+        assert!(parse(non_empty_line(), "\nx").result.is_none());
+
+        let input: &[u8] = b"ab\ncd";
+        assert_eq!(parse(line(), input).result, Some(b"ab".as_slice()));
+    }
+
+    #[test]
+    fn length_take_test() {
+        let p = length_take(map(item(), |n: &u8| *n));
+        let res = parse(p, [2, 7, 8, 9].as_slice());
+        assert_eq!(res.result, Some([7, 8].as_slice()));
+        assert_eq!(res.state, [9]);
+        assert_eq!(parse(p, [0].as_slice()).result, Some([].as_slice()));
+        assert_eq!(parse(p, [2, 7].as_slice()).state, [2, 7]);
+
+        let p = length_take(integer::<i8, _, _, _>());
+        assert_eq!(parse(p, "2åx").result, Some("å"));
+        assert_eq!(parse(p, "1åx").state, "1åx");
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{charlike::CharLike, combinators::{not_empty, succeed}, core::{Parser, ParserExt}, parsers::{item_if, item_while}, slicelike::SliceLike};
+
+/// Create a parser that parses a single character matching `pred`.
+///
+/// Used by [`pat!`] for character classes without repetition, e.g. `['a'..='z']`.
+///
+/// ### Arguments
+/// * `pred` - the predicate for the character class
+#[inline]
+pub fn class_one<A: CharLike, I: SliceLike<RefItem = A>, S>(pred: impl FnOnce(char) -> bool + Copy) -> impl Parser<I, (), S> {
+    item_if(move |c: A| pred(c.as_char())).void()
+}
+
+/// Create a parser that parses zero or more characters matching `pred`.
+///
+/// Used by [`pat!`] for character classes with the `*` repetition, e.g. `['a'..='z']*`.
+///
+/// ### Arguments
+/// * `pred` - the predicate for the character class
+#[inline]
+pub fn class_star<A: CharLike, I: SliceLike<RefItem = A>, S>(pred: impl FnOnce(char) -> bool + Copy) -> impl Parser<I, (), S> {
+    item_while(move |c: A| pred(c.as_char())).void()
+}
+
+/// Create a parser that parses one or more characters matching `pred`.
+///
+/// Used by [`pat!`] for character classes with the `+` repetition, e.g. `['a'..='z']+`.
+///
+/// ### Arguments
+/// * `pred` - the predicate for the character class
+#[inline]
+pub fn class_plus<A: CharLike, I: SliceLike<RefItem = A>, S>(pred: impl FnOnce(char) -> bool + Copy) -> impl Parser<I, (), S> {
+    not_empty(item_while(move |c: A| pred(c.as_char()))).void()
+}
+
+/// Create a parser that parses zero or one character matching `pred`.
+///
+/// Used by [`pat!`] for character classes with the `?` repetition, e.g. `['a'..='z']?`.
+///
+/// ### Arguments
+/// * `pred` - the predicate for the character class
+#[inline]
+pub fn class_opt<A: CharLike, I: SliceLike<RefItem = A>, S>(pred: impl FnOnce(char) -> bool + Copy) -> impl Parser<I, (), S> {
+    succeed(class_one(pred)).void()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::parse;
+
+    grammar! {
+        expr = num ("+" num)*;
+        num = ['0'..='9']+;
+        sign = ("-" num)? "!";
+    }
+
+    #[test]
+    fn item_matching() {
+        let digit = item_matching!('0'..='9');
+        assert_eq!(parse(digit, "7a").result, Some('7'));
+        assert_eq!(parse(digit, "a7").result, None);
+
+        let input: &[u16] = &[0x41, 0xd800];
+        let letter = item_matching!(c if c.is_alphabetic());
+        assert_eq!(parse(letter, input).result, Some(&0x41));
+        assert_eq!(parse(letter, &input[1..]).result, None);
+    }
+
+    #[test]
+    fn patterns() {
+        let ident = pat!(['a'..='z' | 'A'..='Z' | '_'] ['a'..='z' | 'A'..='Z' | '0'..='9' | '_']*);
+        assert_eq!(parse(ident, "_foo1 bar").result, Some("_foo1"));
+        assert_eq!(parse(ident, "1foo").result, None);
+
+        let number = pat!(['-']? ['0'..='9']+ "." ['0'..='9']+);
+        assert_eq!(parse(number, "-12.5x").result, Some("-12.5"));
+        assert_eq!(parse(number, "12.").result, None);
+
+        let string = pat!('"' [^ '"']* '"');
+        assert_eq!(parse(string, r#""abc" d"#).result, Some(r#""abc""#));
+
+        let bytes = pat!([^ ' ']+);
+        assert_eq!(parse(bytes, b"ab c".as_slice()).result, Some(b"ab".as_slice()));
+    }
+    #[test]
+    fn grammar_items() {
+        assert_eq!(parse(expr(), "1+2+3").result, Some("1+2+3"));
+        assert_eq!(parse(num(), "12a").result, Some("12"));
+
+        // A failed repetition or optional element doesn't consume any input.
+        let res = parse(expr(), "1+ rest");
+        assert_eq!(res.result, Some("1"));
+        assert_eq!(res.state, "+ rest");
+        assert_eq!(parse(sign(), "-!").result, None);
+        assert_eq!(parse(sign(), "-1!").result, Some("-1!"));
+    }
+}
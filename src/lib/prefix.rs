@@ -1,5 +1,7 @@
 use core::borrow::Borrow;
 
+use crate::slicelike::SliceLike;
+
 /// Trait for a type that can remove a prefix in the collection `Parent`.
 pub trait Prefix<Parent, Result>: Copy {
     /// Remove the prefix described by this prefix from the haystack if present, and if so,
@@ -52,4 +54,37 @@ impl<'a, S: Borrow<str> + Copy> Prefix<&'a str, &'a str> for S {
     fn skip_prefix(&self, haystack: &'a str) -> Option<&'a str> {
         haystack.strip_prefix(self.borrow())
     }
-}
\ No newline at end of file
+}
+
+/// `Prefix` that matches the first matching prefix of a list of candidates. The result is the
+/// index of the matching candidate along with its result. See [`take_any`](crate::parsers::take_any).
+#[derive(Clone, Copy)]
+pub struct AnyOf<'p, P>(pub &'p [P]);
+
+/// `Prefix` that matches the longest matching prefix of a list of candidates. If several
+/// candidates match the same length, the first one is chosen. The result is the index of the
+/// matching candidate along with its result. See [`take_longest`](crate::parsers::take_longest).
+#[derive(Clone, Copy)]
+pub struct LongestOf<'p, P>(pub &'p [P]);
+
+impl<I: SliceLike, O, P: Prefix<I, O>> Prefix<I, (usize, O)> for AnyOf<'_, P> {
+    fn take_prefix(&self, haystack: I) -> Option<((usize, O), I)> {
+        self.0.iter().enumerate().find_map(|(i, p)| {
+            p.take_prefix(haystack).map(|(res, rest)| ((i, res), rest))
+        })
+    }
+}
+
+impl<I: SliceLike, O, P: Prefix<I, O>> Prefix<I, (usize, O)> for LongestOf<'_, P> {
+    fn take_prefix(&self, haystack: I) -> Option<((usize, O), I)> {
+        let mut best: Option<((usize, O), I)> = None;
+        for (i, p) in self.0.iter().enumerate() {
+            if let Some((res, rest)) = p.take_prefix(haystack) {
+                if best.as_ref().map_or(true, |(_, best_rest)| rest.slice_len() < best_rest.slice_len()) {
+                    best = Some(((i, res), rest));
+                }
+            }
+        }
+        best
+    }
+}
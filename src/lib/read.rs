@@ -0,0 +1,163 @@
+use std::{io::{self, ErrorKind, Read}, vec, vec::Vec};
+
+use crate::{core::{parse_state, Parser}, findbyte::{eq, find}};
+
+const DEFAULT_CAPACITY: usize = 8 * 1024;
+
+/// Buffered reader for parsing record based data, e.g. lines, from any [`Read`], such as
+/// files or sockets, without reading all of it into memory first.
+///
+/// The data is split into records by a delimiter, which are then parsed individually as
+/// `&[u8]`. Note that this is not a streaming input, i.e. it doesn't implement
+/// [`SliceLike`](crate::slicelike::SliceLike), so a parser only ever sees a single record.
+/// The internal buffer grows as needed to fit the longest record.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::number::integer;
+/// use anpa::read::RecordReader;
+///
+/// let data = "1\n2\r\n3".as_bytes();
+/// let mut reader = RecordReader::new(data);
+///
+/// let mut sum = 0;
+/// while let Some(n) = reader.parse_line(integer(), &mut ()).unwrap() {
+///     sum += n.unwrap_or(0);
+/// }
+/// assert_eq!(sum, 6);
+/// ```
+pub struct RecordReader<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    start: usize,
+    end: usize,
+    eof: bool
+}
+
+impl<R: Read> RecordReader<R> {
+    /// Create a new record reader for `reader` with a default initial buffer capacity.
+    pub fn new(reader: R) -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY, reader)
+    }
+
+    /// Create a new record reader for `reader` with the provided initial buffer capacity.
+    pub fn with_capacity(capacity: usize, reader: R) -> Self {
+        Self { reader, buffer: vec![0; capacity.max(1)], start: 0, end: 0, eof: false }
+    }
+
+    /// Get the next record, i.e. all data until the next `delimiter` or the end of the
+    /// data. The delimiter is consumed, but not included in the record.
+    ///
+    /// Returns `None` once all data has been consumed.
+    pub fn next_record(&mut self, delimiter: u8) -> io::Result<Option<&[u8]>> {
+        let mut searched = 0;
+
+        loop {
+            let data = &self.buffer[self.start..self.end];
+            if let Some(pos) = find(&data[searched..], eq(delimiter)) {
+                let record_start = self.start;
+                let record_end = self.start + searched + pos;
+                self.start = record_end + 1;
+                return Ok(Some(&self.buffer[record_start..record_end]))
+            }
+
+            searched = data.len();
+
+            if !self.fill()? {
+                if self.start == self.end {
+                    return Ok(None)
+                }
+
+                let record_start = self.start;
+                self.start = self.end;
+                return Ok(Some(&self.buffer[record_start..self.end]))
+            }
+        }
+    }
+
+    /// Get the next line. Both `\n` and `\r\n` are accepted as newlines, and are not
+    /// included in the line.
+    ///
+    /// Returns `None` once all data has been consumed.
+    pub fn next_line(&mut self) -> io::Result<Option<&[u8]>> {
+        Ok(self.next_record(b'\n')?.map(|line| line.strip_suffix(b"\r").unwrap_or(line)))
+    }
+
+    /// Parse the next record using `p`. See [`next_record`](RecordReader::next_record).
+    ///
+    /// Returns `None` once all data has been consumed, otherwise the result of the parse.
+    pub fn parse_record<'a, O, S>(&'a mut self,
+                                  delimiter: u8,
+                                  p: impl Parser<&'a [u8], O, S>,
+                                  user_state: &mut S) -> io::Result<Option<Option<O>>> {
+        Ok(self.next_record(delimiter)?.map(|record| parse_state(p, record, user_state).result))
+    }
+
+    /// Parse the next line using `p`. See [`next_line`](RecordReader::next_line).
+    ///
+    /// Returns `None` once all data has been consumed, otherwise the result of the parse.
+    pub fn parse_line<'a, O, S>(&'a mut self,
+                                p: impl Parser<&'a [u8], O, S>,
+                                user_state: &mut S) -> io::Result<Option<Option<O>>> {
+        Ok(self.next_line()?.map(|line| parse_state(p, line, user_state).result))
+    }
+
+    /// Read more data into the buffer. Returns `false` if there is no more data.
+    fn fill(&mut self) -> io::Result<bool> {
+        if self.eof {
+            return Ok(false)
+        }
+
+        // Move the unconsumed data to the start of the buffer, and grow it if it's full.
+        self.buffer.copy_within(self.start..self.end, 0);
+        self.end -= self.start;
+        self.start = 0;
+
+        if self.end == self.buffer.len() {
+            self.buffer.resize(self.buffer.len() * 2, 0);
+        }
+
+        loop {
+            match self.reader.read(&mut self.buffer[self.end..]) {
+                Ok(0) => {
+                    self.eof = true;
+                    return Ok(false)
+                }
+                Ok(n) => {
+                    self.end += n;
+                    return Ok(true)
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{string::String, vec::Vec};
+
+    use crate::{parsers::rest, read::RecordReader};
+
+    #[test]
+    fn records_larger_than_buffer() {
+        let data = "first line\nsecond,\n\nlast";
+        let mut reader = RecordReader::with_capacity(4, data.as_bytes());
+
+        let mut lines = Vec::new();
+        while let Some(line) = reader.next_line().unwrap() {
+            lines.push(String::from_utf8(line.to_vec()).unwrap());
+        }
+        assert_eq!(lines, ["first line", "second,", "", "last"]);
+    }
+
+    #[test]
+    fn parse_records() {
+        let mut reader = RecordReader::with_capacity(1, "a;bc;".as_bytes());
+        assert_eq!(reader.parse_record(b';', rest(), &mut ()).unwrap(), Some(Some(b"a".as_slice())));
+        assert_eq!(reader.parse_record(b';', rest(), &mut ()).unwrap(), Some(Some(b"bc".as_slice())));
+        assert_eq!(reader.parse_record(b';', rest(), &mut ()).unwrap(), None);
+    }
+}
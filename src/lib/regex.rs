@@ -0,0 +1,81 @@
+use ::regex::{Captures, Regex};
+
+use crate::core::Parser;
+
+/// Create a parser that matches the compiled regular expression `re` at the start of the
+/// input, and returns its capture groups. This allows for reusing existing regular
+/// expressions within a grammar, e.g. while migrating to parser combinators.
+///
+/// Only matches starting at the current position are accepted, but the search isn't
+/// anchored, so start the expression with `^` to avoid searching the rest of the input
+/// when it doesn't match.
+///
+/// ### Consuming
+/// The whole match, which may be empty.
+///
+/// ### Arguments
+/// * `re` - the regular expression
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::right;
+/// use anpa::parsers::skip;
+/// use anpa::regex::regex;
+/// use regex::Regex;
+///
+/// let date = Regex::new(r"^(\d{4})-(\d{2})-(\d{2})").unwrap();
+/// let p = right(skip("date: "), regex(&date)).map(|c| c[1].len());
+///
+/// let res = parse(p, "date: 2024-01-31 rest");
+/// assert_eq!(res.result, Some(4));
+/// assert_eq!(res.state, " rest");
+/// assert_eq!(parse(p, "date: 24-01-31").result, None);
+/// ```
+#[inline]
+pub fn regex<'a, S>(re: &Regex) -> impl Parser<&'a str, Captures<'a>, S> + '_ {
+    create_parser!(s, {
+        let captures = re.captures(s.input).filter(|c| c.get_match().start() == 0)?;
+        s.input = &s.input[captures.get_match().end()..];
+        Some(captures)
+    })
+}
+
+/// Create a parser that matches the compiled regular expression `re` at the start of the
+/// input, and returns the matched text. See [`regex`](fn@regex).
+///
+/// ### Consuming
+/// The whole match, which may be empty.
+///
+/// ### Arguments
+/// * `re` - the regular expression
+#[inline]
+pub fn regex_match<'a, S>(re: &Regex) -> impl Parser<&'a str, &'a str, S> + '_ {
+    create_parser!(s, {
+        let m = re.find(s.input).filter(|m| m.start() == 0)?;
+        let res;
+        (res, s.input) = s.input.split_at(m.end());
+        Some(res)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ::regex::Regex;
+
+    use crate::{combinators::{many_to_vec, separator}, core::{parse, ParserExt}, parsers::skip, regex::{regex, regex_match}};
+
+    #[test]
+    fn regex_parsers() {
+        let word = Regex::new(r"[a-z]+").unwrap();
+        let pair = Regex::new(r"^(?<key>\w+)=(?<value>\w*)").unwrap();
+
+        let p = many_to_vec(regex(&pair).map(|c| (c["key"].len(), c["value"].len())), false, separator(skip(';'), false));
+        assert_eq!(parse(p, "a=1;bc=").result.unwrap(), [(1, 1), (2, 0)]);
+
+        let res = parse(regex_match(&word), "ab1");
+        assert_eq!((res.result, res.state), (Some("ab"), "1"));
+        assert_eq!(parse(regex_match(&word), "1ab").result, None);
+        assert_eq!(parse(regex_match(&Regex::new("x*").unwrap()), "ab").result, Some(""));
+    }
+}
@@ -0,0 +1,142 @@
+use alloc::vec::Vec;
+
+use crate::{combinators::left, core::Parser, findbyte::until_seq_fast, number::integer_signed_checked, parsers::{length_take, skip}};
+
+const CRLF: &[u8] = b"\r\n";
+
+/// A frame of RESP, the Redis serialization protocol. Strings are stored as slices of the
+/// input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame<'a> {
+    /// A simple string, e.g. `+OK\r\n`.
+    Simple(&'a [u8]),
+
+    /// An error, e.g. `-ERR unknown command\r\n`.
+    Error(&'a [u8]),
+
+    /// An integer, e.g. `:1000\r\n`.
+    Integer(i64),
+
+    /// A bulk string, e.g. `$5\r\nhello\r\n`.
+    Bulk(&'a [u8]),
+
+    /// An array of frames, e.g. `*2\r\n:1\r\n:2\r\n`.
+    Array(Vec<Frame<'a>>),
+
+    /// A null bulk string or array, i.e. `$-1\r\n` or `*-1\r\n`.
+    Null
+}
+
+fn line<'a, S>() -> impl Parser<&'a [u8], &'a [u8], S> {
+    until_seq_fast(CRLF)
+}
+
+fn integer_line<'a, S>() -> impl Parser<&'a [u8], i64, S> {
+    left(integer_signed_checked(), skip(CRLF))
+}
+
+fn frame_parser_internal<'a, S>(max_depth: usize) -> impl Parser<&'a [u8], Frame<'a>, S> {
+    let bulk = left(length_take(integer_line()), skip(CRLF));
+
+    create_parser!(s, {
+        // The arrays being parsed, with the number of elements left in each.
+        let mut stack: Vec<(Vec<Frame<'a>>, i64)> = Vec::new();
+
+        'frame: loop {
+            let (kind, rest) = s.input.split_first()?;
+            s.input = rest;
+
+            let mut frame = match kind {
+                b'+' => Frame::Simple(line()(s)?),
+                b'-' => Frame::Error(line()(s)?),
+                b':' => Frame::Integer(integer_line()(s)?),
+                b'$' if s.input.starts_with(b"-1\r\n") => {
+                    s.input = &s.input[4..];
+                    Frame::Null
+                },
+                b'$' => Frame::Bulk(bulk(s)?),
+                b'*' => match integer_line()(s)? {
+                    -1 => Frame::Null,
+                    0 => Frame::Array(Vec::new()),
+                    len if len > 0 && stack.len() < max_depth => {
+                        // The length is not trusted for the capacity, since it's part of the input.
+                        stack.push((Vec::new(), len));
+                        continue 'frame
+                    },
+                    _ => return None
+                },
+                _ => return None
+            };
+
+            // A frame is complete. Add it to the enclosing arrays, finishing them as we go.
+            loop {
+                let Some((elements, remaining)) = stack.last_mut() else {
+                    return Some(frame)
+                };
+
+                elements.push(frame);
+                *remaining -= 1;
+                if *remaining > 0 {
+                    continue 'frame
+                }
+
+                frame = Frame::Array(stack.pop()?.0);
+            }
+        }
+    })
+}
+
+/// Get a parser for a single RESP frame. Arrays are parsed iteratively, so arbitrarily
+/// deep nesting is supported. Use [`frame_parser_with_max_depth`] for untrusted input.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::resp::{frame_parser, Frame};
+///
+/// // Pipelined commands.
+/// let input = b"*2\r\n$3\r\nGET\r\n$3\r\nkey\r\n+PING\r\n".as_slice();
+/// let frames: Vec<_> = parse_iter(frame_parser(), input).collect();
+///
+/// assert_eq!(frames, [Frame::Array(vec![Frame::Bulk(b"GET"), Frame::Bulk(b"key")]),
+///                     Frame::Simple(b"PING")]);
+/// ```
+pub fn frame_parser<'a>() -> impl Parser<&'a [u8], Frame<'a>> {
+    frame_parser_internal(usize::MAX)
+}
+
+/// Get a parser for a single RESP frame, failing if arrays are nested deeper than
+/// `max_depth`. See [`frame_parser`].
+pub fn frame_parser_with_max_depth<'a>(max_depth: usize) -> impl Parser<&'a [u8], Frame<'a>> {
+    frame_parser_internal(max_depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::{core::parse, resp::{frame_parser, frame_parser_with_max_depth, Frame}};
+
+    #[test]
+    fn frames() {
+        let res = parse(frame_parser(), b"*3\r\n:-12\r\n*2\r\n$-1\r\n-ERR x\r\n$4\r\na\r\nb\r\nrest".as_slice());
+        assert_eq!(res.result, Some(Frame::Array(vec![
+            Frame::Integer(-12),
+            Frame::Array(vec![Frame::Null, Frame::Error(b"ERR x")]),
+            Frame::Bulk(b"a\r\nb")
+        ])));
+        assert_eq!(res.state, b"rest");
+
+        assert_eq!(parse(frame_parser(), b"*0\r\n".as_slice()).result, Some(Frame::Array(vec![])));
+        assert_eq!(parse(frame_parser(), b"*-1\r\n".as_slice()).result, Some(Frame::Null));
+        assert_eq!(parse(frame_parser(), b"$0\r\n\r\n".as_slice()).result, Some(Frame::Bulk(b"")));
+
+        for input in [&b"$4\r\nabc\r\n"[..], b"$3\r\nabcd\r\n", b"*2\r\n:1\r\n", b":1x\r\n", b"+OK", b"*-2\r\n", b"?\r\n"] {
+            assert_eq!(parse(frame_parser(), input).result, None);
+        }
+
+        let nested = b"*1\r\n*1\r\n:1\r\n".as_slice();
+        assert!(parse(frame_parser_with_max_depth(2), nested).result.is_some());
+        assert!(parse(frame_parser_with_max_depth(1), nested).result.is_none());
+    }
+}
@@ -1,6 +1,8 @@
-use crate::{combinators::*, core::{ParserExt, StrParser}, number::integer, parsers::{*}};
+use core::fmt::{self, Display};
 
-#[derive(Debug)]
+use crate::{charlike::CharLike, combinators::*, const_parse::ConstCursor, core::{ParserExt, StrParser}, findbyte::{byteset, ByteSet}, number::integer_checked, parsers::{*}};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AnpaVersion<T> {
     pub major: u64,
     pub minor: u64,
@@ -15,6 +17,81 @@ impl<T> AnpaVersion<T> {
     }
 }
 
+impl<T: AsRef<str>> Display for AnpaVersion<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre_release.as_ref().is_empty() {
+            write!(f, "-{}", self.pre_release.as_ref())?;
+        }
+        if !self.build.as_ref().is_empty() {
+            write!(f, "+{}", self.build.as_ref())?;
+        }
+        Ok(())
+    }
+}
+
+/// Serialize the version as a SemVer string, e.g. `"1.2.3-rc.1"`.
+#[cfg(feature = "serde")]
+impl<T: AsRef<str>> serde::Serialize for AnpaVersion<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct VersionVisitor;
+
+#[cfg(feature = "serde")]
+impl VersionVisitor {
+    fn parse<'a, T: From<&'a str>, E: serde::de::Error>(&self, v: &'a str) -> Result<AnpaVersion<T>, E> {
+        parse_general(v).ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(v), self))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for VersionVisitor {
+    type Value = AnpaVersion<&'de str>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a SemVer string")
+    }
+
+    fn visit_borrowed_str<E: serde::de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        self.parse(v)
+    }
+}
+
+/// Deserialize a version from a borrowed SemVer string, using [`semver`]. Deserializers that
+/// can't borrow from their input, e.g. for strings with escapes, fail.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AnpaVersion<&'de str> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(VersionVisitor)
+    }
+}
+
+/// Deserialize a version from a SemVer string, using [`semver`].
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl<'de> serde::Deserialize<'de> for AnpaVersion<alloc::string::String> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct OwnedVisitor;
+
+        impl serde::de::Visitor<'_> for OwnedVisitor {
+            type Value = AnpaVersion<alloc::string::String>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                VersionVisitor.expecting(f)
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                VersionVisitor.parse(v)
+            }
+        }
+
+        deserializer.deserialize_str(OwnedVisitor)
+    }
+}
+
 /// Parse a SemVer string from `text`. General version that infer the `pre_release` and `build` type
 /// by means of `From<&str>`.
 pub fn parse_general<'a, O: From<&'a str>>(text: &'a str) -> Option<AnpaVersion<O>> {
@@ -34,16 +111,84 @@ pub fn parse(text: &str) -> Option<AnpaVersion<std::string::String>> {
     parse_general(text)
 }
 
+/// Parse a SemVer string from `text` in a `const` context, e.g. to validate a version literal
+/// at compile time using [`const_parse`](macro@crate::const_parse). `pre_release` and `build` will be
+/// stored as slices of the input.
+///
+/// ### Example
+/// ```
+/// use anpa::const_parse;
+/// use anpa::semver::{parse_const, AnpaVersion};
+///
+/// const MIN_VERSION: AnpaVersion<&str> = const_parse!(parse_const, "1.4.0-beta.2");
+/// assert_eq!((MIN_VERSION.minor, MIN_VERSION.pre_release), (4, "beta.2"));
+/// ```
+pub const fn parse_const(text: &str) -> Option<AnpaVersion<&str>> {
+    let (c, major) = const_component(ConstCursor::new(text));
+    let (c, minor) = const_component(c.byte(b'.'));
+    let (c, patch) = const_component(c.byte(b'.'));
+    let (c, pre_release) = const_dot_separated(c, b'-', true);
+    let (c, build) = const_dot_separated(c, b'+', false);
+
+    match c.is_done() {
+        true => Some(AnpaVersion { major, minor, patch, pre_release, build }),
+        false => None
+    }
+}
+
+const DIGITS: ByteSet = byteset(b"0123456789");
+const IDENTIFIER_CHARACTERS: ByteSet = byteset(b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ-");
+
+const fn const_component(c: ConstCursor<'_>) -> (ConstCursor<'_>, u64) {
+    let (next, value) = c.integer();
+    match c.peek() {
+        Some(b'0') if next.pos() - c.pos() > 1 => (next.fail(), 0),
+        _ => (next, value)
+    }
+}
+
+/// Parse the optional pre-release or build identifiers, which are preceded by `prefix`.
+/// Numeric identifiers must not have leading zeros if `numeric` is set.
+const fn const_dot_separated<'a>(c: ConstCursor<'a>, prefix: u8, numeric: bool) -> (ConstCursor<'a>, &'a str) {
+    match c.peek() {
+        Some(b) if b == prefix => {},
+        _ => return (c, "")
+    }
+
+    let mut c = c.advance(1);
+    let start = c.pos();
+    loop {
+        let digits = c.repeat(DIGITS, 0, usize::MAX);
+        let next = digits.repeat(IDENTIFIER_CHARACTERS, 0, usize::MAX);
+        let leading_zero = matches!(c.peek(), Some(b'0')) && digits.pos() - c.pos() > 1;
+        if next.pos() == c.pos() || (numeric && leading_zero && next.pos() == digits.pos()) {
+            return (c.fail(), "")
+        }
+
+        c = next;
+        match c.peek() {
+            Some(b'.') => c = c.advance(1),
+            _ => break
+        }
+    }
+
+    match c.text_from(start) {
+        Some(text) => (c, text),
+        None => (c.fail(), "")
+    }
+}
+
 #[inline]
 pub fn semver<'a, T: From<&'a str>>() -> impl StrParser<'a, AnpaVersion<T>> {
-    map!(|(major, minor, patch), pre: Option<_>, build: Option<_>| {
-        AnpaVersion::new(major, minor, patch, pre.unwrap_or(""), build.unwrap_or(""))
-    }, version_core(), succeed(pre_release()), succeed(build())).left(empty())
+    map!(|(major, minor, patch), pre, build| {
+        AnpaVersion::new(major, minor, patch, pre, build)
+    }, version_core(), pre_release().or_default(), build().or_default()).left(empty())
 }
 
 #[inline]
 fn version_core<'a>() -> impl StrParser<'a, (u64, u64, u64)> {
-    let component = and_parsed(integer()).map_if(|(i, p): (&str, _)| {
+    // Checked, so that untrusted input can't overflow the components.
+    let component = and_parsed(integer_checked()).map_if(|(i, p): (&str, _)| {
         (!i.starts_with('0') || p == 0).then_some(p)
     });
 
@@ -95,13 +240,13 @@ fn identifier_characters<'a>() -> impl StrParser<'a> {
 }
 
 #[inline]
-fn identifier_character(c: char) -> bool {
+fn identifier_character(c: impl CharLike) -> bool {
     digit(c) || non_digit(c)
 }
 
 #[inline]
-fn non_digit(c: char) -> bool {
-    c.is_ascii_alphabetic() || c == '-'
+fn non_digit(c: impl CharLike) -> bool {
+    c.is_ascii_alphabetic() || c.as_char() == '-'
 }
 
 #[inline]
@@ -110,7 +255,7 @@ fn digits<'a>() -> impl StrParser<'a> {
 }
 
 #[inline]
-fn digit(c: char) -> bool {
+fn digit(c: impl CharLike) -> bool {
     c.is_ascii_digit()
 }
 
@@ -160,4 +305,46 @@ mod tests {
         assert_eq!(res.pre_release, "SNAPSHOT");
         assert_eq!(res.build, "build1");
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn version_display() {
+        use crate::semver::AnpaVersion;
+
+        for version in ["1.2.3", "1.2.3-rc.1", "1.2.3+build.5", "0.0.1-alpha-1+x"] {
+            assert_eq!(alloc::format!("{}", parse_inline(version).unwrap()), version);
+        }
+        assert_eq!(alloc::format!("{}", AnpaVersion::<&str>::new(1, 0, 0, "", "b")), "1.0.0+b");
+    }
+
+    #[cfg(all(feature = "serde", feature = "alloc"))]
+    #[test]
+    fn version_serde() {
+        use alloc::string::String;
+        use crate::semver::AnpaVersion;
+        use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+
+        assert_tokens(&AnpaVersion::<&str>::new(1, 2, 3, "rc.1", "b"), &[Token::BorrowedStr("1.2.3-rc.1+b")]);
+        assert_tokens(&AnpaVersion::<String>::new(1, 2, 3, "", "b"), &[Token::Str("1.2.3+b")]);
+        assert_de_tokens(&AnpaVersion::<String>::new(0, 1, 0, "", ""), &[Token::BorrowedStr("0.1.0")]);
+
+        assert_de_tokens_error::<AnpaVersion<&str>>(&[Token::Str("1.2.3")],
+            "invalid type: string \"1.2.3\", expected a SemVer string");
+        assert_de_tokens_error::<AnpaVersion<String>>(&[Token::Str("1.2")],
+            "invalid value: string \"1.2\", expected a SemVer string");
+        assert_de_tokens_error::<AnpaVersion<String>>(&[Token::U8(1)],
+            "invalid type: integer `1`, expected a SemVer string");
+    }
+
+    #[test]
+    fn version_const() {
+        use crate::semver::parse_const;
+
+        for version in ["0.0.0", "1.2.3", "1.2.3-0", "1.2.3-0a.01a.-", "1.2.3+01.x-y", "1.2.3-rc.1+b.2", "18446744073709551615.0.0",
+                        "01.2.3", "1.2", "1.2.3-", "1.2.3-01", "1.2.3-a..b", "1.2.3+", "1.2.3-a+b+c", "1.2.3 ", "18446744073709551616.0.0"] {
+            let expected = parse_inline(version).map(|v| (v.major, v.minor, v.patch, v.pre_release, v.build));
+            let actual = parse_const(version).map(|v| (v.major, v.minor, v.patch, v.pre_release, v.build));
+            assert_eq!(actual, expected, "{}", version);
+        }
+    }
+}
@@ -30,6 +30,43 @@ pub trait SliceLike: Copy {
 
     /// Check if the input is empty.
     fn slice_is_empty(&self) -> bool;
+
+    /// Check if this input is a suffix of `other`, i.e. if it refers to the end of the same
+    /// underlying input. Only used for checking invariants with the feature
+    /// "debug_invariants", and defaults to `true`.
+    fn slice_is_suffix_of(self, _other: Self) -> bool {
+        true
+    }
+}
+
+/// The number of items consumed by a parser, given its input `before` and `after` the parse.
+///
+/// With the feature "debug_invariants", this panics with a clear message if `after` isn't a
+/// suffix of `before`, instead of underflowing or silently returning the wrong input.
+#[inline(always)]
+#[track_caller]
+pub(crate) fn consumed_len<I: SliceLike>(before: I, after: I) -> I::Idx {
+    #[cfg(feature = "debug_invariants")]
+    check_consumed(before, after);
+    before.slice_len() - after.slice_len()
+}
+
+/// The part of `before` that was consumed by a parser that left `after` as its remaining input.
+#[inline(always)]
+#[track_caller]
+pub(crate) fn consumed_input<I: SliceLike>(before: I, after: I) -> I {
+    before.slice_to(consumed_len(before, after))
+}
+
+#[cfg(feature = "debug_invariants")]
+#[track_caller]
+fn check_consumed<I: SliceLike>(before: I, after: I) {
+    assert!(after.slice_len() <= before.slice_len(),
+            "anpa: a parser grew the input. Parsers may only consume input, i.e. set `s.input` \
+             to a suffix of it, e.g. using `slice_from`");
+    assert!(after.slice_is_suffix_of(before),
+            "anpa: a parser replaced the input with one that isn't a suffix of it. Parsers may \
+             only consume input, i.e. set `s.input` to a suffix of it, e.g. using `slice_from`");
 }
 
 impl<'a, A> SliceLike for &'a [A] {
@@ -45,6 +82,8 @@ impl<'a, A> SliceLike for &'a [A] {
         self.split_first().filter(|x| pred(x.0))
     }
 
+    // `pred` is `FnOnce + Copy`, so the closure is needed to make it `FnMut`.
+    #[allow(clippy::redundant_closure)]
     fn slice_find_pred(self, pred: impl FnOnce(Self::RefItem) -> bool + Copy) -> Option<usize> {
         self.iter().position(|x| pred(x))
     }
@@ -68,6 +107,11 @@ impl<'a, A> SliceLike for &'a [A] {
     fn slice_is_empty(&self) -> bool {
         self.is_empty()
     }
+
+    fn slice_is_suffix_of(self, other: Self) -> bool {
+        let (range, other_range) = (self.as_ptr_range(), other.as_ptr_range());
+        other_range.start <= range.start && range.end == other_range.end
+    }
 }
 
 impl<'a> SliceLike for &'a str {
@@ -85,6 +129,8 @@ impl<'a> SliceLike for &'a str {
         pred(first).then_some((first, chars.as_str()))
     }
 
+    // `pred` is `FnOnce + Copy`, so the closure is needed to make it `FnMut`.
+    #[allow(clippy::redundant_closure)]
     fn slice_find_pred(self, pred: impl FnOnce(Self::RefItem) -> bool + Copy) -> Option<usize> {
         self.find(|c| pred(c))
     }
@@ -108,4 +154,8 @@ impl<'a> SliceLike for &'a str {
     fn slice_is_empty(&self) -> bool {
         self.is_empty()
     }
+
+    fn slice_is_suffix_of(self, other: Self) -> bool {
+        self.as_bytes().slice_is_suffix_of(other.as_bytes())
+    }
 }
@@ -0,0 +1,56 @@
+use core::borrow::Borrow;
+
+/// Trait for a type that can remove a suffix in the collection `Parent`. This is the
+/// counterpart of [`Prefix`](crate::prefix::Prefix) for the end of the input.
+pub trait Suffix<Parent, Result>: Copy {
+    /// Remove the suffix described by this suffix from the haystack if present, and if so,
+    /// return it along with the rest of the haystack.
+    fn take_suffix(&self, haystack: Parent) -> Option<(Result, Parent)>;
+
+    /// Remove the suffix described by this suffix from the haystack if present, and return
+    /// the rest of the haystack (result is ignored).
+    fn skip_suffix(&self, haystack: Parent) -> Option<Parent> {
+        Some(self.take_suffix(haystack)?.1)
+    }
+}
+
+impl<'a, T: PartialEq + Copy> Suffix<&'a [T], T> for T {
+    fn take_suffix(&self, haystack: &'a [T]) -> Option<(T, &'a [T])> {
+        haystack.split_last()
+            .filter(|x| x.0 == self)
+            .map(|x| (*x.0, x.1))
+    }
+}
+
+impl<'a, T: PartialEq + Copy, S: Borrow<[T]> + Copy> Suffix<&'a [T], &'a [T]> for S {
+    fn take_suffix(&self, haystack: &'a [T]) -> Option<(&'a [T], &'a [T])> {
+        let rest = haystack.strip_suffix(self.borrow())?;
+        Some((&haystack[rest.len()..], rest))
+    }
+
+    fn skip_suffix(&self, haystack: &'a [T]) -> Option<&'a [T]> {
+        haystack.strip_suffix(self.borrow())
+    }
+}
+
+impl<'a> Suffix<&'a str, char> for char {
+    fn take_suffix(&self, haystack: &'a str) -> Option<(char, &'a str)> {
+        let rest = haystack.strip_suffix(*self)?;
+        Some((*self, rest))
+    }
+
+    fn skip_suffix(&self, haystack: &'a str) -> Option<&'a str> {
+        haystack.strip_suffix(*self)
+    }
+}
+
+impl<'a, S: Borrow<str> + Copy> Suffix<&'a str, &'a str> for S {
+    fn take_suffix(&self, haystack: &'a str) -> Option<(&'a str, &'a str)> {
+        let rest = haystack.strip_suffix(self.borrow())?;
+        Some((&haystack[rest.len()..], rest))
+    }
+
+    fn skip_suffix(&self, haystack: &'a str) -> Option<&'a str> {
+        haystack.strip_suffix(self.borrow())
+    }
+}
@@ -0,0 +1,220 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::borrow::Borrow;
+
+use crate::{core::Parser, slicelike::SliceLike};
+
+/// A scoped symbol table that can be used as, or be part of, the user state, e.g. to
+/// resolve names while parsing a programming language.
+///
+/// Symbols are defined in the innermost scope, where they shadow symbols with the same name
+/// in the enclosing scopes, and are removed when the scope is popped.
+///
+/// The parsers in this module require the user state to implement
+/// `AsMut<SymbolTable<K, V>>`, which is implemented for `SymbolTable` itself. For a custom
+/// user state, implement `AsMut` to return its symbol table.
+///
+/// Note: definitions are not undone when a parser fails and an alternative is tried using
+/// e.g. [`or`](crate::combinators::or), so define symbols only once the definition has been
+/// parsed, e.g. by letting the parser passed to [`define`] parse the whole definition.
+///
+/// ### Example
+/// ```
+/// use anpa::create_parser;
+/// use anpa::core::*;
+/// use anpa::combinators::{many, middle, no_separator, or_diff, right};
+/// use anpa::parsers::{item_while, skip};
+/// use anpa::symbols::{define, resolve, scoped, SymbolTable};
+///
+/// // A block of `let x;` and `use x;` statements, and nested blocks.
+/// fn block<'a>() -> impl Parser<&'a str, (), SymbolTable<&'a str, usize>> {
+///     let name = item_while(|c: char| c.is_ascii_alphabetic());
+///     let var = define(right(skip("let "), name).map(|n: &str| (n, n.len())));
+///     let usage = right(skip("use "), resolve(name)).void();
+///     let statement = or_diff(var, usage).left(skip(';'));
+///     let nested = create_parser!(s, block()(s));
+///     scoped(middle(skip('{'), many(or_diff(statement, nested), true, no_separator()), skip('}')).void())
+/// }
+///
+/// let mut symbols = SymbolTable::new();
+/// assert!(parse_state(block(), "{let x;{let y;use x;use y;}use x;}", &mut symbols).result.is_some());
+/// assert!(parse_state(block(), "{let x;{let y;}use y;}", &mut symbols).result.is_none());
+/// assert!(parse_state(block(), "{let x;let x;}", &mut symbols).result.is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolTable<K, V> {
+    /// The definitions of each symbol, innermost last, with the depth of their scopes.
+    symbols: BTreeMap<K, Vec<(usize, V)>>,
+
+    /// All symbols that are currently defined, in the order they were defined.
+    defined: Vec<K>,
+
+    /// The length of `defined` at the start of each scope.
+    scopes: Vec<usize>
+}
+
+impl<K: Ord + Clone, V> SymbolTable<K, V> {
+    /// Create a new, empty table with only the outermost scope.
+    pub fn new() -> Self {
+        Self { symbols: BTreeMap::new(), defined: Vec::new(), scopes: Vec::new() }
+    }
+
+    /// The number of scopes entered, i.e. 0 for the outermost scope.
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// Enter a new scope.
+    pub fn push_scope(&mut self) {
+        self.scopes.push(self.defined.len())
+    }
+
+    /// Leave the innermost scope, removing the symbols defined in it. Returns `false` if
+    /// there is no scope to leave.
+    pub fn pop_scope(&mut self) -> bool {
+        let Some(start) = self.scopes.pop() else {
+            return false
+        };
+
+        for key in self.defined.drain(start..) {
+            if let Some(definitions) = self.symbols.get_mut(&key) {
+                definitions.pop();
+                if definitions.is_empty() {
+                    self.symbols.remove(&key);
+                }
+            }
+        }
+        true
+    }
+
+    /// Define `key` in the innermost scope, shadowing any definition in the enclosing
+    /// scopes. Returns `false`, without changing the table, if `key` is already defined
+    /// in the innermost scope.
+    pub fn define(&mut self, key: K, value: V) -> bool {
+        let depth = self.depth();
+        let definitions = self.symbols.entry(key.clone()).or_default();
+        if definitions.last().is_some_and(|(d, _)| *d == depth) {
+            return false
+        }
+        definitions.push((depth, value));
+        self.defined.push(key);
+        true
+    }
+
+    /// Get the value of `key`, defining it in the innermost scope using `value` if it is
+    /// not defined in any scope.
+    pub fn intern(&mut self, key: K, value: impl FnOnce() -> V) -> &V {
+        if !self.symbols.contains_key(&key) {
+            self.define(key.clone(), value());
+        }
+        &self.symbols[&key].last().expect("symbols are removed with their last definition").1
+    }
+
+    /// Get the innermost definition of `key`, if any.
+    pub fn lookup<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V> where K: Borrow<Q> {
+        self.symbols.get(key).and_then(|d| d.last()).map(|(_, v)| v)
+    }
+
+    /// Check if `key` is defined in the innermost scope.
+    pub fn is_defined_in_scope<Q: Ord + ?Sized>(&self, key: &Q) -> bool where K: Borrow<Q> {
+        self.symbols.get(key).and_then(|d| d.last()).is_some_and(|(d, _)| *d == self.depth())
+    }
+}
+
+impl<K: Ord + Clone, V> Default for SymbolTable<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> AsMut<SymbolTable<K, V>> for SymbolTable<K, V> {
+    fn as_mut(&mut self) -> &mut SymbolTable<K, V> {
+        self
+    }
+}
+
+/// Transform a parser so that it is run in a new scope of the symbol table. The symbols
+/// defined by `p` are removed afterwards, whether it succeeds or not.
+///
+/// ### Arguments
+/// * `p` - the parser
+#[inline]
+pub fn scoped<I: SliceLike, O, K: Ord + Clone, V, S: AsMut<SymbolTable<K, V>>>(p: impl Parser<I, O, S>
+) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        s.user_state.as_mut().push_scope();
+        let res = p(s);
+        s.user_state.as_mut().pop_scope();
+        res
+    })
+}
+
+/// Transform a parser for a name and its value to a parser that defines the name in the
+/// innermost scope of the symbol table, and returns the name. The parser fails if the name
+/// is already defined in the innermost scope, which is reported as expecting an
+/// "undefined name" at the start of `p`.
+///
+/// ### Arguments
+/// * `p` - the parser for the name and its value
+#[inline]
+pub fn define<I: SliceLike, K: Ord + Clone, V, S: AsMut<SymbolTable<K, V>>>(p: impl Parser<I, (K, V), S>
+) -> impl Parser<I, K, S> {
+    create_parser!(s, {
+        let remaining = s.input.slice_len();
+        let (key, value) = p(s)?;
+        if !s.user_state.as_mut().define(key.clone(), value) {
+            s.expect("undefined name", remaining);
+            return None
+        }
+        Some(key)
+    })
+}
+
+/// Transform a parser for a name to a parser that returns the value of the innermost
+/// definition of the name in the symbol table. The parser fails if the name is not
+/// defined, which is reported as expecting a "defined name" at the start of `p`.
+///
+/// ### Arguments
+/// * `p` - the parser for the name
+#[inline]
+pub fn resolve<I: SliceLike, K: Ord + Clone, V: Clone, S: AsMut<SymbolTable<K, V>>>(p: impl Parser<I, K, S>
+) -> impl Parser<I, V, S> {
+    create_parser!(s, {
+        let remaining = s.input.slice_len();
+        let key = p(s)?;
+        let value = s.user_state.as_mut().lookup(&key).cloned();
+        if value.is_none() {
+            s.expect("defined name", remaining);
+        }
+        value
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{combinators::right, core::{parse_all_state, ParserExt}, parsers::{item, skip}, symbols::{define, resolve, SymbolTable}};
+
+    #[test]
+    fn scopes() {
+        let mut table = SymbolTable::new();
+        assert!(table.define("x", 1));
+        assert!(!table.define("x", 2));
+        assert!(!table.pop_scope());
+
+        table.push_scope();
+        assert_eq!((table.lookup("x"), table.is_defined_in_scope("x")), (Some(&1), false));
+        assert!(table.define("x", 3));
+        assert_eq!(*table.intern("y", || 4), 4);
+        assert_eq!(*table.intern("x", || 5), 3);
+        assert_eq!(table.depth(), 1);
+        assert!(table.pop_scope());
+
+        assert_eq!((table.lookup("x"), table.lookup("y")), (Some(&1), None));
+        assert_eq!(table, SymbolTable { symbols: [("x", [(0, 1)].into())].into(), defined: ["x"].into(), scopes: [].into() });
+
+        let p = right(define(item().map(|c: char| (c, c.len_utf8()))), resolve(right(skip(' '), item())));
+        let mut table = SymbolTable::new();
+        assert_eq!(parse_all_state(p, "a a", &mut table), Ok(1));
+        assert_eq!(parse_all_state(p, "b c", &mut table).unwrap_err().expected, Some("defined name"));
+        assert_eq!(parse_all_state(p, "a b", &mut table).unwrap_err().expected, Some("undefined name"));
+    }
+}
@@ -0,0 +1,159 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{core::Parser, findbyte::{get_seq_pos, ByteInput}};
+
+/// A token of a template, as produced by [`token_parser`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token<I> {
+    /// Text to output as-is.
+    Literal(I),
+
+    /// The content of a tag, without the delimiters and surrounding ASCII whitespace.
+    Tag(I)
+}
+
+/// The syntax of a template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Syntax<'b> {
+    /// The delimiter opening a tag, e.g. `{{`.
+    pub open: &'b [u8],
+
+    /// The delimiter closing a tag, e.g. `}}`.
+    pub close: &'b [u8],
+
+    /// The byte that makes a following opening delimiter literal, if any, e.g. `\`.
+    pub escape: Option<u8>
+}
+
+impl Syntax<'static> {
+    /// Mustache/Handlebars style tags, i.e. `{{ tag }}`, where `\{{` is a literal `{{`.
+    pub const MUSTACHE: Syntax<'static> = Syntax { open: b"{{", close: b"}}", escape: Some(b'\\') };
+
+    /// Jinja style expression tags, i.e. `{{ tag }}`, without escaping.
+    pub const JINJA: Syntax<'static> = Syntax { open: b"{{", close: b"}}", escape: None };
+}
+
+impl Syntax<'_> {
+    fn is_escaped(&self, bytes: &[u8], pos: usize) -> bool {
+        pos > 0 && Some(bytes[pos - 1]) == self.escape
+    }
+}
+
+fn trim_ascii<'a, I: ByteInput<'a>>(input: I) -> I {
+    let bytes = input.as_byte_slice();
+    let start = bytes.iter().take_while(|b| b.is_ascii_whitespace()).count();
+    let end = bytes.len() - bytes[start..].iter().rev().take_while(|b| b.is_ascii_whitespace()).count();
+    input.slice_to(end).slice_from(start)
+}
+
+/// Create a parser for a single token of a template, i.e. a run of literal text or a tag.
+/// An escaped opening delimiter is returned as a separate literal token, without the
+/// escape byte. Use [`template_parser`] to parse a whole template, or
+/// [`parse_iter`](crate::core::parse_iter) to avoid allocating.
+///
+/// The delimiters should be ASCII for `&str` input, and the opening delimiter must not be
+/// empty.
+///
+/// ### Consuming
+/// Consumes the token, on successful parse. The parser fails on empty input, or if a tag
+/// is not closed.
+///
+/// ### Arguments
+/// * `syntax` - the delimiters and escape byte of the template
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::template::{token_parser, Syntax, Token};
+///
+/// let tokens: Vec<_> = parse_iter(token_parser(Syntax::MUSTACHE), "Hi {{ name }}!").collect();
+/// assert_eq!(tokens, [Token::Literal("Hi "), Token::Tag("name"), Token::Literal("!")]);
+///
+/// let erb = Syntax { open: b"<%=", close: b"%>", escape: None };
+/// let tokens: Vec<_> = parse_iter(token_parser(erb), "<%= x %> \\{{").collect();
+/// assert_eq!(tokens, [Token::Tag("x"), Token::Literal(" \\{{")]);
+/// ```
+#[inline]
+pub fn token_parser<'a, 'b, I: ByteInput<'a>, S>(syntax: Syntax<'b>) -> impl Parser<I, Token<I>, S> + 'b {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice();
+
+        let (token, len) = match bytes {
+            [] => return None,
+            [escape, rest @ ..] if Some(*escape) == syntax.escape && rest.starts_with(syntax.open) => {
+                let len = 1 + syntax.open.len();
+                (Token::Literal(s.input.slice_to(len).slice_from(1)), len)
+            },
+            _ if bytes.starts_with(syntax.open) => {
+                let start = syntax.open.len();
+                let content_len = get_seq_pos(&bytes[start..], syntax.close)?;
+                let content = s.input.slice_to(start + content_len).slice_from(start);
+                (Token::Tag(trim_ascii(content)), start + content_len + syntax.close.len())
+            },
+            _ => {
+                // An escaped delimiter can't be at position 1, since the escape byte would
+                // have started an escape token.
+                let len = match get_seq_pos(&bytes[1..], syntax.open).map(|p| p + 1) {
+                    Some(pos) if syntax.is_escaped(bytes, pos) => pos - 1,
+                    Some(pos) => pos,
+                    None => bytes.len()
+                };
+                (Token::Literal(s.input.slice_to(len)), len)
+            }
+        };
+
+        s.input = s.input.slice_from(len);
+        Some(token)
+    })
+}
+
+#[cfg(feature = "alloc")]
+/// Create a parser that splits a whole template into [`Token`]s. See [`token_parser`].
+///
+/// ### Consuming
+/// Consumes the whole input, on successful parse. The parser fails if a tag is not closed.
+///
+/// ### Arguments
+/// * `syntax` - the delimiters and escape byte of the template
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::template::{template_parser, Syntax, Token};
+///
+/// let res = parse(template_parser(Syntax::MUSTACHE), "\\{{ {{#list}}{{.}}{{/list}}");
+/// assert_eq!(res.result, Some(vec![Token::Literal("{{"), Token::Literal(" "), Token::Tag("#list"),
+///                                  Token::Tag("."), Token::Tag("/list")]));
+///
+/// assert_eq!(parse(template_parser(Syntax::MUSTACHE), "{{ unclosed").result, None);
+/// ```
+#[inline]
+pub fn template_parser<'a, 'b, I: ByteInput<'a>, S>(syntax: Syntax<'b>) -> impl Parser<I, Vec<Token<I>>, S> + 'b {
+    create_parser!(s, {
+        let mut tokens = Vec::new();
+        while let Some(token) = token_parser(syntax)(s) {
+            tokens.push(token);
+        }
+        s.input.as_byte_slice().is_empty().then_some(tokens)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{core::{parse, parse_iter}, template::{token_parser, Syntax, Token::*}};
+
+    #[test]
+    fn tokens() {
+        let syntax = Syntax { open: b"<<", close: b">>", escape: Some(b'<') };
+        let tokens = parse_iter(token_parser(syntax), "a<<<b<< c\t>><<>><");
+        assert!(tokens.eq([Literal("a"), Literal("<<"), Literal("b"), Tag("c"), Tag(""), Literal("<")]));
+
+        let tokens = parse_iter(token_parser(Syntax::JINJA), b"\\{{x}}".as_slice());
+        assert!(tokens.eq([Literal(&b"\\"[..]), Tag(b"x")]));
+
+        assert_eq!(parse(token_parser(Syntax::MUSTACHE), "\\\\{{").result, Some(Literal("\\")));
+        assert_eq!(parse(token_parser(Syntax::MUSTACHE), "{{ x }").result, None);
+        assert_eq!(parse(token_parser(Syntax::MUSTACHE), "").result, None);
+    }
+}
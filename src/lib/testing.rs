@@ -0,0 +1,233 @@
+use alloc::string::String;
+use core::fmt::Debug;
+
+use crate::{combinators::get_parsed, core::{parse, parse_all, Parser}, slicelike::SliceLike};
+
+/// A small deterministic pseudo random number generator (xorshift64*), so that the
+/// generated inputs, and any failures, can be reproduced from the seed.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Create a generator from `seed`. All seeds, including 0, are valid.
+    pub fn new(seed: u64) -> Self {
+        Rng((seed ^ 0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    /// Get the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Get a random number in the inclusive range `min..=max`.
+    pub fn range(&mut self, min: u64, max: u64) -> u64 {
+        match (max - min).checked_add(1) {
+            Some(n) => min + self.next_u64() % n,
+            None => self.next_u64()
+        }
+    }
+}
+
+/// A generator of inputs accepted by a parser, created by combining the generators of this
+/// module the same way as the corresponding parsers. The generator appends its output to
+/// the provided `String`.
+///
+/// All copyable `Fn` functions with the correct signature are considered generators.
+pub trait Generator: Fn(&mut Rng, &mut String) + Copy {}
+
+impl<F: Fn(&mut Rng, &mut String) + Copy> Generator for F {}
+
+/// Create a generator for [`skip`](crate::parsers::skip) or
+/// [`take`](crate::parsers::take) of a fixed text.
+#[inline]
+pub fn prefix(text: &str) -> impl Generator + '_ {
+    move |_: &mut Rng, out: &mut String| out.push_str(text)
+}
+
+/// Create a generator for [`integer`](crate::number::integer), producing numbers in the
+/// inclusive range `min..=max`.
+#[inline]
+pub fn integer(min: u64, max: u64) -> impl Generator {
+    move |rng: &mut Rng, out: &mut String| {
+        let n = rng.range(min, max);
+        push_number(out, n, false)
+    }
+}
+
+/// Create a generator for [`integer_signed`](crate::number::integer_signed), producing
+/// numbers in the inclusive range `min..=max`.
+#[inline]
+pub fn integer_signed(min: i64, max: i64) -> impl Generator {
+    move |rng: &mut Rng, out: &mut String| {
+        let n = min.wrapping_add(rng.range(0, max.abs_diff(min)) as i64);
+        push_number(out, n.unsigned_abs(), n < 0)
+    }
+}
+
+fn push_number(out: &mut String, mut n: u64, negative: bool) {
+    let mut digits = [0; 20];
+    let mut len = 0;
+    loop {
+        digits[len] = b'0' + (n % 10) as u8;
+        len += 1;
+        n /= 10;
+        if n == 0 {
+            break
+        }
+    }
+
+    if negative {
+        out.push('-');
+    }
+    out.extend(digits[..len].iter().rev().map(|d| *d as char));
+}
+
+/// Create a generator for [`item_while`](crate::parsers::item_while) and similar parsers,
+/// producing between `min` and `max` characters from `alphabet`.
+///
+/// Note: the character following the generated ones is not controlled by this generator,
+/// so make sure that it isn't accepted by the parser.
+#[inline]
+pub fn chars(alphabet: &str, min: usize, max: usize) -> impl Generator + '_ {
+    move |rng: &mut Rng, out: &mut String| {
+        let count = alphabet.chars().count() as u64;
+        for _ in 0..rng.range(min as u64, max as u64) {
+            out.extend(alphabet.chars().nth(rng.range(0, count - 1) as usize));
+        }
+    }
+}
+
+/// Create a generator for a sequence of parsers, e.g. using [`seq`](crate::seq), producing
+/// the output of `g1` followed by the output of `g2`.
+#[inline]
+pub fn seq(g1: impl Generator, g2: impl Generator) -> impl Generator {
+    move |rng: &mut Rng, out: &mut String| {
+        g1(rng, out);
+        g2(rng, out);
+    }
+}
+
+/// Create a generator for [`or`](crate::combinators::or), producing the output of either
+/// `g1` or `g2`.
+#[inline]
+pub fn or(g1: impl Generator, g2: impl Generator) -> impl Generator {
+    move |rng: &mut Rng, out: &mut String| {
+        match rng.range(0, 1) {
+            0 => g1(rng, out),
+            _ => g2(rng, out)
+        }
+    }
+}
+
+/// Create a generator for [`many`](crate::combinators::many) without a separator,
+/// producing between `min` and `max` outputs of `g`.
+#[inline]
+pub fn many(g: impl Generator, min: usize, max: usize) -> impl Generator {
+    separated(g, prefix(""), min, max)
+}
+
+/// Create a generator for [`many`](crate::combinators::many) with a separator, producing
+/// between `min` and `max` outputs of `g`, separated by outputs of `separator`.
+#[inline]
+pub fn separated(g: impl Generator, separator: impl Generator, min: usize, max: usize) -> impl Generator {
+    move |rng: &mut Rng, out: &mut String| {
+        for i in 0..rng.range(min as u64, max as u64) {
+            if i > 0 {
+                separator(rng, out);
+            }
+            g(rng, out);
+        }
+    }
+}
+
+/// Get an infinite iterator over inputs produced by `g`, using a [`Rng`] with the seed `seed`.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::{many_to_vec, middle, separator};
+/// use anpa::number::integer;
+/// use anpa::parsers::skip;
+/// use anpa::testing::{self, samples};
+///
+/// let gen = testing::seq(testing::prefix("["),
+///                        testing::seq(testing::separated(testing::integer(0, 999), testing::prefix(","), 0, 5),
+///                                     testing::prefix("]")));
+///
+/// for input in samples(gen, 1).take(100) {
+///     let p = middle(skip('['), many_to_vec(integer::<u32, _, _, _>(), true, separator(skip(','), false)), skip(']'));
+///     let numbers = testing::check_accepts(p, input.as_str());
+///     assert!(numbers.len() <= 5 && numbers.iter().all(|n| *n <= 999));
+/// }
+/// ```
+pub fn samples(g: impl Generator, seed: u64) -> impl Iterator<Item = String> {
+    let mut rng = Rng::new(seed);
+    core::iter::repeat_with(move || {
+        let mut out = String::new();
+        g(&mut rng, &mut out);
+        out
+    })
+}
+
+/// Check that `p` consumes exactly the input reported by wrapping it in
+/// [`get_parsed`], and that both succeed or fail on `input`. Returns the parsed input, if
+/// the parse succeeded.
+///
+/// ### Panics
+/// Panics if the checks fail.
+pub fn check_get_parsed<I: SliceLike + Debug + PartialEq, O>(p: impl Parser<I, O>, input: I) -> Option<I> {
+    let res = parse(p, input);
+    let parsed = parse(get_parsed(p), input);
+    assert_eq!(res.result.is_some(), parsed.result.is_some(), "get_parsed changed the result for {:?}", input);
+
+    let parsed_input = parsed.result?;
+    assert_eq!(res.state, parsed.state, "get_parsed changed the remaining input for {:?}", input);
+    assert_eq!(parsed_input, res.consumed(input),
+               "get_parsed reported the wrong input for {:?}", input);
+    Some(parsed_input)
+}
+
+/// Check that `p` accepts all of `input`, using [`parse_all`], and consumes the input
+/// reported by [`get_parsed`]. Returns the result of the parse.
+///
+/// ### Panics
+/// Panics if the checks fail.
+pub fn check_accepts<I: SliceLike + Debug + PartialEq, O>(p: impl Parser<I, O>, input: I) -> O where I::Idx: Debug {
+    check_get_parsed(p, input);
+    match parse_all(p, input) {
+        Ok(result) => result,
+        Err(error) => panic!("{:?} was not accepted: {:?}", input, error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::{combinators::{many, not_empty, or, separator}, core::ParserExt, number::integer_signed, parsers::{item_while, skip}, testing::{self, check_accepts, check_get_parsed, samples, Rng}};
+
+    #[test]
+    fn generators() {
+        let mut rng = Rng::new(0);
+        assert!((0..1000).map(|_| rng.range(3, 5)).all(|n| (3..=5).contains(&n)));
+        assert_eq!(samples(testing::integer_signed(i64::MIN, i64::MIN), 0).next().unwrap(), i64::MIN.to_string());
+        assert_eq!(samples(testing::integer(u64::MAX, u64::MAX), 0).next().unwrap(), u64::MAX.to_string());
+        assert!(samples(testing::prefix("x"), 0).take(2).eq(["x", "x"]));
+
+        let word = testing::chars("abcé", 1, 3);
+        let item = testing::or(testing::integer_signed(-50, 50), word);
+        let gen = testing::separated(item, testing::many(testing::prefix(" "), 1, 2), 1, 10);
+
+        for input in samples(gen, 42).take(200) {
+            let word = not_empty(item_while(|c: char| c.is_alphabetic()));
+            let item = or(integer_signed::<i8, _, _, _>().void(), word.void());
+            check_accepts(many(item, false, separator(not_empty(item_while(|c| c == ' ')), false)), input.as_str());
+        }
+
+        assert_eq!(check_get_parsed(skip("ab"), "abc"), Some("ab"));
+        assert_eq!(check_get_parsed(skip("ab"), "b"), None);
+    }
+}
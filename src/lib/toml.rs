@@ -0,0 +1,851 @@
+use alloc::{borrow::Cow, collections::{btree_map::Entry as MapEntry, BTreeMap}, string::String, vec, vec::Vec};
+
+use crate::{combinators::{fold_nested, NestedFrame}, core::{AnpaState, StrParser}};
+
+/// A table of TOML values.
+pub type Table<'a> = BTreeMap<Cow<'a, str>, TomlValue<'a>>;
+
+/// A TOML value. Strings are borrowed from the input, unless they contain escape sequences.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TomlValue<'a> {
+    Str(Cow<'a, str>),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Datetime(Datetime),
+    Arr(Vec<TomlValue<'a>>),
+    Table(Table<'a>)
+}
+
+/// A TOML date and/or time. An offset date-time has all fields, a local date-time has no
+/// offset, and a local date or local time has only a date or a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Datetime {
+    pub date: Option<Date>,
+    pub time: Option<Time>,
+
+    /// The offset from UTC in minutes, where `Z` is 0.
+    pub offset: Option<i16>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub nanosecond: u32
+}
+
+/// How a table was created, which determines how it may be extended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Kind {
+    /// Created as the parent of a table header, e.g. `a` by `[a.b]`.
+    #[default]
+    Implicit,
+
+    /// Defined by a table header.
+    Header,
+
+    /// Created by a dotted key, e.g. `a` by `a.b = 1`.
+    Dotted
+}
+
+/// A table being built, which keeps track of how its tables were created.
+#[derive(Debug, Default)]
+struct Builder<'a> {
+    entries: BTreeMap<Cow<'a, str>, Entry<'a>>,
+    kind: Kind
+}
+
+#[derive(Debug)]
+enum Entry<'a> {
+    Value(TomlValue<'a>),
+    Table(Builder<'a>),
+    TableArray(Vec<Builder<'a>>)
+}
+
+impl<'a> Builder<'a> {
+    fn new(kind: Kind) -> Self {
+        Self { entries: BTreeMap::new(), kind }
+    }
+
+    fn into_value(self) -> TomlValue<'a> {
+        TomlValue::Table(self.entries.into_iter().map(|(key, entry)| (key, match entry {
+            Entry::Value(value) => value,
+            Entry::Table(table) => table.into_value(),
+            Entry::TableArray(tables) => TomlValue::Arr(tables.into_iter().map(Builder::into_value).collect())
+        })).collect())
+    }
+
+    /// Insert `value` at the dotted `key`, creating the tables of the key. Fails if the key
+    /// is already defined, or if it extends a table that wasn't created by a dotted key.
+    fn insert(&mut self, key: Vec<Cow<'a, str>>, value: TomlValue<'a>) -> Option<()> {
+        let mut table = self;
+        let mut parts = key.into_iter();
+        let mut last = parts.next()?;
+
+        for part in parts {
+            table = match table.entries.entry(last).or_insert_with(|| Entry::Table(Builder::new(Kind::Dotted))) {
+                Entry::Table(table) if table.kind == Kind::Dotted => table,
+                _ => return None
+            };
+            last = part;
+        }
+
+        match table.entries.entry(last) {
+            MapEntry::Vacant(entry) => entry.insert(Entry::Value(value)),
+            MapEntry::Occupied(_) => return None
+        };
+        Some(())
+    }
+
+    /// Define the table, or add a table to the array of tables, with the header `key`.
+    fn define(&mut self, key: &[Cow<'a, str>], array: bool) -> Option<()> {
+        let (last, parents) = key.split_last()?;
+        let mut table = self;
+
+        for part in parents {
+            table = match table.entries.entry(part.clone()).or_insert_with(|| Entry::Table(Builder::default())) {
+                Entry::Table(table) => table,
+                Entry::TableArray(tables) => tables.last_mut()?,
+                Entry::Value(_) => return None
+            };
+        }
+
+        match (table.entries.entry(last.clone()), array) {
+            (MapEntry::Vacant(entry), false) => {
+                entry.insert(Entry::Table(Builder::new(Kind::Header)));
+            },
+            (MapEntry::Vacant(entry), true) => {
+                entry.insert(Entry::TableArray(vec![Builder::new(Kind::Header)]));
+            },
+            (MapEntry::Occupied(mut entry), false) => match entry.get_mut() {
+                Entry::Table(table) if table.kind == Kind::Implicit => table.kind = Kind::Header,
+                _ => return None
+            },
+            (MapEntry::Occupied(mut entry), true) => match entry.get_mut() {
+                Entry::TableArray(tables) => tables.push(Builder::new(Kind::Header)),
+                _ => return None
+            }
+        }
+        Some(())
+    }
+
+    /// Get the table with the header `key`, which must have been defined.
+    fn table(&mut self, key: &[Cow<'a, str>]) -> Option<&mut Builder<'a>> {
+        key.iter().try_fold(self, |table, part| match table.entries.get_mut(part)? {
+            Entry::Table(table) => Some(table),
+            Entry::TableArray(tables) => tables.last_mut(),
+            Entry::Value(_) => None
+        })
+    }
+}
+
+fn skip_whitespace(input: &str) -> &str {
+    input.trim_start_matches([' ', '\t'])
+}
+
+/// Skip whitespace, comments and newlines.
+fn skip_blank(mut input: &str) -> &str {
+    loop {
+        input = skip_whitespace(input);
+        if input.starts_with('#') {
+            input = &input[input.find('\n').unwrap_or(input.len())..];
+        } else if let Some(rest) = input.strip_prefix('\n').or_else(|| input.strip_prefix("\r\n")) {
+            input = rest;
+        } else {
+            return input
+        }
+    }
+}
+
+/// Parse the end of a line, i.e. whitespace, an optional comment, and a newline or the end
+/// of the input.
+fn line_end<'a, S>() -> impl StrParser<'a, (), S> {
+    create_parser!(s, {
+        let rest = skip_whitespace(s.input);
+        let rest = match rest.strip_prefix('#') {
+            Some(comment) => &comment[comment.find('\n').unwrap_or(comment.len())..],
+            None => rest
+        };
+
+        s.input = match rest.strip_prefix('\n').or_else(|| rest.strip_prefix("\r\n")) {
+            Some(rest) => rest,
+            None if rest.is_empty() => rest,
+            None => return None
+        };
+        Some(())
+    })
+}
+
+fn unicode_escape(hex: &str) -> Option<char> {
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None
+    }
+    char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+}
+
+/// Parse the content of a string after the opening delimiter, up to and including the
+/// closing delimiter. Returns the content and the remaining input.
+fn string_content(input: &str, quote: u8, multiline: bool, escapes: bool) -> Option<(Cow<'_, str>, &str)> {
+    let input = match multiline {
+        true => input.strip_prefix('\n').or_else(|| input.strip_prefix("\r\n")).unwrap_or(input),
+        false => input
+    };
+
+    let bytes = input.as_bytes();
+    let mut owned: Option<String> = None;
+    let mut segment_start = 0;
+    let mut i = 0;
+
+    let end = loop {
+        let b = *bytes.get(i)?;
+        match b {
+            _ if b == quote && !multiline => break i,
+            _ if b == quote => match bytes[i..].iter().take_while(|q| **q == b).count() {
+                // Up to two quotes are allowed right before the closing delimiter.
+                quotes @ 3..=5 => break i + quotes - 3,
+                quotes @ 1..=2 => i += quotes,
+                _ => return None
+            },
+            b'\\' if escapes => {
+                let string = owned.get_or_insert_with(String::new);
+                string.push_str(&input[segment_start..i]);
+
+                let rest = &input[i + 1..];
+                let after_whitespace = skip_whitespace(rest);
+                let len = if multiline && (after_whitespace.starts_with('\n') || after_whitespace.starts_with("\r\n")) {
+                    // A line ending backslash trims all whitespace up to the next
+                    // non-whitespace character.
+                    rest.len() - rest.trim_start_matches([' ', '\t', '\r', '\n']).len()
+                } else {
+                    let (c, len) = match rest.as_bytes().first()? {
+                        b'b' => ('\u{8}', 1),
+                        b't' => ('\t', 1),
+                        b'n' => ('\n', 1),
+                        b'f' => ('\u{c}', 1),
+                        b'r' => ('\r', 1),
+                        b'"' => ('"', 1),
+                        b'\\' => ('\\', 1),
+                        b'u' => (unicode_escape(rest.get(1..5)?)?, 5),
+                        b'U' => (unicode_escape(rest.get(1..9)?)?, 9),
+                        _ => return None
+                    };
+                    string.push(c);
+                    len
+                };
+
+                i += 1 + len;
+                segment_start = i;
+            },
+            b'\n' if multiline => i += 1,
+            b'\r' if multiline && bytes.get(i + 1) == Some(&b'\n') => i += 2,
+            b'\t' => i += 1,
+            _ if b < b' ' || b == 0x7f => return None,
+            _ => i += 1
+        }
+    };
+
+    let content = match owned {
+        Some(mut string) => {
+            string.push_str(&input[segment_start..end]);
+            Cow::Owned(string)
+        },
+        None => Cow::Borrowed(&input[..end])
+    };
+
+    let delimiter_len = if multiline { 3 } else { 1 };
+    Some((content, &input[end + delimiter_len..]))
+}
+
+/// Parse a basic or literal string, on a single line or multiline.
+fn string_parser<'a, S>(allow_multiline: bool) -> impl StrParser<'a, Cow<'a, str>, S> {
+    create_parser!(s, {
+        let quote = *s.input.as_bytes().first()?;
+        if quote != b'"' && quote != b'\'' {
+            return None
+        }
+
+        let multiline = allow_multiline && s.input.as_bytes()[1..].starts_with(&[quote, quote]);
+        let delimiter_len = if multiline { 3 } else { 1 };
+        let (content, rest) = string_content(&s.input[delimiter_len..], quote, multiline, quote == b'"')?;
+        s.input = rest;
+        Some(content)
+    })
+}
+
+/// Parse a key, which may be dotted, e.g. `a."b.c".d`.
+fn key_parser<'a, S>(max_depth: usize) -> impl StrParser<'a, Vec<Cow<'a, str>>, S> {
+    create_parser!(s, {
+        let mut key = Vec::new();
+        loop {
+            let bare_len = s.input.bytes().take_while(|b| b.is_ascii_alphanumeric() || *b == b'_' || *b == b'-').count();
+            let part = match bare_len {
+                0 => string_parser(false)(s)?,
+                _ => {
+                    let (part, rest) = s.input.split_at(bare_len);
+                    s.input = rest;
+                    Cow::Borrowed(part)
+                }
+            };
+
+            key.push(part);
+            if key.len() > max_depth {
+                return None
+            }
+
+            let rest = skip_whitespace(s.input);
+            match rest.strip_prefix('.') {
+                Some(rest) => s.input = skip_whitespace(rest),
+                None => return Some(key)
+            }
+        }
+    })
+}
+
+/// Parse a key followed by `=`, with any surrounding whitespace.
+fn key_value_prefix<'a, S>(max_depth: usize) -> impl StrParser<'a, Vec<Cow<'a, str>>, S> {
+    create_parser!(s, {
+        s.input = skip_whitespace(s.input);
+        let key = key_parser(max_depth)(s)?;
+        s.input = skip_whitespace(skip_whitespace(s.input).strip_prefix('=')?);
+        Some(key)
+    })
+}
+
+/// Remove the underscores between the digits of a number, e.g. `1_000`.
+fn digits(number: &str, radix: u32) -> Option<Cow<'_, str>> {
+    let valid = number.split('_').all(|group| !group.is_empty() && group.chars().all(|c| c.is_digit(radix)));
+    match number.contains('_') {
+        _ if !valid => None,
+        true => Some(Cow::Owned(number.replace('_', ""))),
+        false => Some(Cow::Borrowed(number))
+    }
+}
+
+/// Remove the underscores of a decimal number, which must not have leading zeros.
+fn decimal(number: &str) -> Option<Cow<'_, str>> {
+    let unsigned = number.strip_prefix(['+', '-']).unwrap_or(number);
+    if unsigned.len() > 1 && unsigned.starts_with('0') {
+        return None
+    }
+    digits(unsigned, 10)?;
+
+    match number.contains('_') {
+        true => Some(Cow::Owned(number.replace('_', ""))),
+        false => Some(Cow::Borrowed(number))
+    }
+}
+
+fn integer(token: &str) -> Option<i64> {
+    let radix = match token.get(..2) {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        _ => return decimal(token)?.parse().ok()
+    };
+    i64::from_str_radix(&digits(&token[2..], radix)?, radix).ok()
+}
+
+fn float(token: &str) -> Option<f64> {
+    let unsigned = token.strip_prefix(['+', '-']).unwrap_or(token);
+    match unsigned {
+        "inf" => return Some(if token.starts_with('-') { f64::NEG_INFINITY } else { f64::INFINITY }),
+        "nan" => return Some(f64::NAN),
+        _ => {}
+    }
+
+    let (mantissa, exponent) = match token.find(['e', 'E']) {
+        Some(pos) => (&token[..pos], Some(&token[pos + 1..])),
+        None => (token, None)
+    };
+    let (int, fraction) = match mantissa.split_once('.') {
+        Some((int, fraction)) => (int, Some(fraction)),
+        None => (mantissa, None)
+    };
+
+    let mut number = String::from(decimal(int)?);
+    if let Some(fraction) = fraction {
+        number.push('.');
+        number.push_str(&digits(fraction, 10)?);
+    }
+    if let Some(exponent) = exponent {
+        number.push('e');
+        if exponent.starts_with('-') {
+            number.push('-');
+        }
+        number.push_str(&digits(exponent.strip_prefix(['+', '-']).unwrap_or(exponent), 10)?);
+    }
+    number.parse().ok()
+}
+
+/// Parse exactly `n` digits.
+fn fixed_digits(input: &str, n: usize) -> Option<(u32, &str)> {
+    let digits = input.get(..n)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None
+    }
+    Some((digits.parse().ok()?, &input[n..]))
+}
+
+fn date(input: &str) -> Option<(Date, &str)> {
+    let (year, rest) = fixed_digits(input, 4)?;
+    let (month, rest) = fixed_digits(rest.strip_prefix('-')?, 2)?;
+    let (day, rest) = fixed_digits(rest.strip_prefix('-')?, 2)?;
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None
+    };
+
+    (1..=days).contains(&day).then_some((Date { year: year as u16, month: month as u8, day: day as u8 }, rest))
+}
+
+fn time(input: &str) -> Option<(Time, &str)> {
+    let (hour, rest) = fixed_digits(input, 2)?;
+    let (minute, rest) = fixed_digits(rest.strip_prefix(':')?, 2)?;
+    let (second, mut rest) = fixed_digits(rest.strip_prefix(':')?, 2)?;
+    if hour > 23 || minute > 59 || second > 60 {
+        return None
+    }
+
+    let mut nanosecond = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let len = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if len == 0 {
+            return None
+        }
+        // Digits beyond nanosecond precision are truncated.
+        for i in 0..9 {
+            nanosecond = nanosecond * 10 + fraction.as_bytes()[..len].get(i).map_or(0, |b| (b - b'0') as u32);
+        }
+        rest = &fraction[len..];
+    }
+
+    Some((Time { hour: hour as u8, minute: minute as u8, second: second as u8, nanosecond }, rest))
+}
+
+fn offset(input: &str) -> Option<(i16, &str)> {
+    if let Some(rest) = input.strip_prefix(['Z', 'z']) {
+        return Some((0, rest))
+    }
+
+    let sign = match input.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None
+    };
+    let (hours, rest) = fixed_digits(&input[1..], 2)?;
+    let (minutes, rest) = fixed_digits(rest.strip_prefix(':')?, 2)?;
+    (hours < 24 && minutes < 60).then_some((sign * (hours * 60 + minutes) as i16, rest))
+}
+
+/// Parse a date-time, local date-time, local date or local time.
+fn datetime_parser<'a, S>() -> impl StrParser<'a, Datetime, S> {
+    create_parser!(s, {
+        let (date, rest) = match date(s.input) {
+            Some((date, rest)) => (Some(date), rest),
+            None => (None, s.input)
+        };
+
+        let (time, rest) = match (date, rest.as_bytes().first()) {
+            (None, _) => time(rest).map(|(time, rest)| (Some(time), rest))?,
+            (Some(_), Some(b'T' | b't')) => time(&rest[1..]).map(|(time, rest)| (Some(time), rest))?,
+            (Some(_), Some(b' ')) => time(&rest[1..]).map_or((None, rest), |(time, rest)| (Some(time), rest)),
+            _ => (None, rest)
+        };
+
+        let (offset, rest) = match (date, time) {
+            (Some(_), Some(_)) => offset(rest).map_or((None, rest), |(offset, rest)| (Some(offset), rest)),
+            _ => (None, rest)
+        };
+
+        s.input = rest;
+        Some(Datetime { date, time, offset })
+    })
+}
+
+/// Parse a value that is not an array or inline table.
+fn atom_parser<'a, S>() -> impl StrParser<'a, TomlValue<'a>, S> {
+    create_parser!(s, {
+        let bytes = s.input.as_bytes();
+        match bytes.first()? {
+            b'"' | b'\'' => return string_parser(true)(s).map(TomlValue::Str),
+            _ if bytes.get(4) == Some(&b'-') || bytes.get(2) == Some(&b':') => {
+                return datetime_parser()(s).map(TomlValue::Datetime)
+            },
+            _ => {}
+        }
+
+        let len = bytes.iter().take_while(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'+' | b'-' | b'.')).count();
+        let (token, rest) = s.input.split_at(len);
+        let value = match token {
+            "true" => TomlValue::Bool(true),
+            "false" => TomlValue::Bool(false),
+            _ if token.starts_with("0x") || token.starts_with("0o") || token.starts_with("0b") => TomlValue::Int(integer(token)?),
+            _ if token.contains(['.', 'e', 'E', 'i', 'n']) => TomlValue::Float(float(token)?),
+            _ => TomlValue::Int(integer(token)?)
+        };
+
+        s.input = rest;
+        Some(value)
+    })
+}
+
+/// A partially parsed array or inline table.
+enum Frame<'a> {
+    Arr(Vec<TomlValue<'a>>),
+
+    /// An inline table, the key of the next value, and whether all keys have been valid.
+    Table(Builder<'a>, Vec<Cow<'a, str>>, bool, usize)
+}
+
+impl<'a, S> NestedFrame<&'a str, S> for Frame<'a> {
+    type Value = TomlValue<'a>;
+
+    fn element_prefix(&mut self, s: &mut AnpaState<&'a str, S>) -> Option<()> {
+        s.input = skip_blank(s.input);
+        if let Frame::Table(_, key, _, max_depth) = self {
+            *key = key_value_prefix(*max_depth)(s)?;
+        }
+        Some(())
+    }
+
+    fn close(&self, s: &mut AnpaState<&'a str, S>) -> Option<()> {
+        let (close, empty) = match self {
+            Frame::Arr(values) => (']', values.is_empty()),
+            Frame::Table(_, _, false, _) => return None,
+            Frame::Table(table, ..) => ('}', table.entries.is_empty())
+        };
+
+        // A trailing comma is allowed after the last element.
+        let rest = skip_blank(s.input);
+        let rest = match rest.strip_prefix(',') {
+            Some(rest) if !empty => skip_blank(rest),
+            _ => rest
+        };
+        s.input = rest.strip_prefix(close)?;
+        Some(())
+    }
+
+    fn push(&mut self, value: TomlValue<'a>) {
+        match self {
+            Frame::Arr(values) => values.push(value),
+            Frame::Table(table, key, valid, _) => {
+                *valid &= table.insert(core::mem::take(key), value).is_some()
+            }
+        }
+    }
+
+    fn finish(self) -> TomlValue<'a> {
+        match self {
+            Frame::Arr(values) => TomlValue::Arr(values),
+            Frame::Table(table, ..) => table.into_value()
+        }
+    }
+}
+
+/// Parse the separator between elements, unless it is a trailing comma.
+fn separator_parser<'a, S>() -> impl StrParser<'a, (), S> {
+    create_parser!(s, {
+        let rest = skip_blank(s.input).strip_prefix(',')?;
+        if skip_blank(rest).starts_with([']', '}']) {
+            return None
+        }
+        s.input = rest;
+        Some(())
+    })
+}
+
+fn open_parser<'a, S>(max_depth: usize) -> impl StrParser<'a, Frame<'a>, S> {
+    create_parser!(s, {
+        let frame = match s.input.as_bytes().first()? {
+            b'[' => Frame::Arr(Vec::new()),
+            b'{' => Frame::Table(Builder::new(Kind::Dotted), Vec::new(), true, max_depth),
+            _ => return None
+        };
+        s.input = &s.input[1..];
+        Some(frame)
+    })
+}
+
+fn value_parser_internal<'a, S>(max_depth: usize) -> impl StrParser<'a, TomlValue<'a>, S> {
+    // Arrays and inline tables are parsed iteratively to support arbitrarily deep nesting.
+    fold_nested(open_parser(max_depth), atom_parser(), separator_parser(), max_depth)
+}
+
+fn document_parser_internal<'a, S>(max_depth: usize) -> impl StrParser<'a, TomlValue<'a>, S> {
+    create_parser!(s, {
+        let mut root = Builder::new(Kind::Header);
+        let mut current = Vec::new();
+
+        loop {
+            s.input = skip_blank(s.input);
+            if s.input.is_empty() {
+                return Some(root.into_value())
+            }
+
+            if let Some(rest) = s.input.strip_prefix('[') {
+                let array = rest.starts_with('[');
+                s.input = skip_whitespace(if array { &rest[1..] } else { rest });
+                current = key_parser(max_depth)(s)?;
+                let close = if array { "]]" } else { "]" };
+                s.input = skip_whitespace(s.input).strip_prefix(close)?;
+                root.define(&current, array)?;
+            } else {
+                let key = key_value_prefix(max_depth)(s)?;
+                let value = value_parser_internal(max_depth)(s)?;
+                root.table(&current)?.insert(key, value)?;
+            }
+
+            line_end()(s)?;
+        }
+    })
+}
+
+/// Get a TOML parser that parses a whole document into a [`TomlValue::Table`].
+///
+/// Inline tables are parsed like arrays, i.e. they may span several lines and have a
+/// trailing comma. Otherwise, invalid documents are rejected, including documents that
+/// define a key or table more than once.
+///
+/// ### Example
+/// ```
+/// use anpa::core::parse;
+/// use anpa::toml::{document_parser, TomlValue};
+///
+/// let input = r#"
+/// title = "Example"
+///
+/// [server]
+/// ports = [ 8000, 8001 ]
+/// limits = { memory = 1.5e9, "max connections" = 0x400 }
+///
+/// [[user]]
+/// name = 'root'
+///
+/// [[user]]
+/// name = """
+/// guest"""
+/// "#;
+///
+/// let Some(TomlValue::Table(doc)) = parse(document_parser(), input).result else { panic!() };
+/// assert_eq!(doc["title"], TomlValue::Str("Example".into()));
+///
+/// let TomlValue::Table(server) = &doc["server"] else { panic!() };
+/// assert_eq!(server["ports"], TomlValue::Arr(vec![TomlValue::Int(8000), TomlValue::Int(8001)]));
+///
+/// let TomlValue::Table(limits) = &server["limits"] else { panic!() };
+/// assert_eq!(limits["max connections"], TomlValue::Int(1024));
+///
+/// let TomlValue::Arr(users) = &doc["user"] else { panic!() };
+/// assert!(matches!(&users[1], TomlValue::Table(user) if user["name"] == TomlValue::Str("guest".into())));
+/// ```
+pub fn document_parser<'a>() -> impl StrParser<'a, TomlValue<'a>> {
+    document_parser_internal(usize::MAX)
+}
+
+/// Get a TOML parser that parses a whole document, failing if arrays, inline tables or keys
+/// are nested deeper than `max_depth`. Use this parser to limit memory usage for untrusted
+/// input. See [`document_parser`].
+pub fn document_parser_with_max_depth<'a>(max_depth: usize) -> impl StrParser<'a, TomlValue<'a>> {
+    document_parser_internal(max_depth)
+}
+
+/// Get a TOML parser that parses a single value, e.g. for the right hand side of a
+/// `key=value` command line option.
+///
+/// ### Example
+/// ```
+/// use anpa::core::parse;
+/// use anpa::toml::{value_parser, Date, Datetime, TomlValue};
+///
+/// let date = Date { year: 1979, month: 5, day: 27 };
+/// assert_eq!(parse(value_parser(), "1979-05-27").result,
+///            Some(TomlValue::Datetime(Datetime { date: Some(date), time: None, offset: None })));
+/// ```
+pub fn value_parser<'a>() -> impl StrParser<'a, TomlValue<'a>> {
+    value_parser_internal(usize::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{borrow::Cow, vec};
+
+    use crate::{core::parse, toml::{document_parser, document_parser_with_max_depth, value_parser, Date, Datetime, Time, TomlValue}};
+
+    fn value(input: &str) -> Option<TomlValue<'_>> {
+        let res = parse(value_parser(), input);
+        res.state.is_empty().then_some(res.result?)
+    }
+
+    #[test]
+    fn values() {
+        assert_eq!(value("+1_000"), Some(TomlValue::Int(1000)));
+        assert_eq!(value("-9223372036854775808"), Some(TomlValue::Int(i64::MIN)));
+        assert_eq!(value("0xdead_BEEF"), Some(TomlValue::Int(0xdeadbeef)));
+        assert_eq!(value("0o17"), Some(TomlValue::Int(15)));
+        assert_eq!(value("0b101"), Some(TomlValue::Int(5)));
+        assert_eq!(value("-1_0.5e-0_1"), Some(TomlValue::Float(-1.05)));
+        assert_eq!(value("6.626E+34"), Some(TomlValue::Float(6.626e34)));
+        assert_eq!(value("-inf"), Some(TomlValue::Float(f64::NEG_INFINITY)));
+        assert!(matches!(value("nan"), Some(TomlValue::Float(f)) if f.is_nan()));
+        for invalid in ["01", "1__0", "_1", "1_", "+0x1", "1.", ".5", "1e", "9223372036854775808", "truex", "1.e5", "0x"] {
+            assert_eq!(value(invalid), None, "{}", invalid);
+        }
+
+        assert_eq!(value(r#""a\tb\u00e9\U0001F600""#), Some(TomlValue::Str(Cow::Owned("a\tbé😀".into()))));
+        assert!(matches!(value(r#"'C:\path'"#), Some(TomlValue::Str(Cow::Borrowed(r"C:\path")))));
+        assert_eq!(value("\"\"\"\na \\\n    b\"\"\"\"\""), Some(TomlValue::Str("a b\"\"".into())));
+        assert_eq!(value("'''\r\nit's\n'''"), Some(TomlValue::Str("it's\n".into())));
+        for invalid in ["\"a\nb\"", r#""\x""#, r#""\uD800""#, "'a", "\"\"\"a\"\"\"\"\"\""] {
+            assert_eq!(value(invalid), None, "{}", invalid);
+        }
+
+        let date = Some(Date { year: 2000, month: 2, day: 29 });
+        let time = Time { hour: 7, minute: 32, second: 0, nanosecond: 999_999_000 };
+        assert_eq!(value("2000-02-29T07:32:00.999999-07:30"),
+                   Some(TomlValue::Datetime(Datetime { date, time: Some(time), offset: Some(-450) })));
+        assert_eq!(value("2000-02-29 07:32:00.9999999999Z"),
+                   Some(TomlValue::Datetime(Datetime { date, time: Some(Time { nanosecond: 999_999_999, ..time }), offset: Some(0) })));
+        assert_eq!(value("07:32:00.999999"), Some(TomlValue::Datetime(Datetime { date: None, time: Some(time), offset: None })));
+        for invalid in ["1900-02-29", "2000-13-01", "2000-01-01T", "24:00:00", "07:32", "07:32:00Z"] {
+            assert_eq!(value(invalid), None, "{}", invalid);
+        }
+
+        assert_eq!(value("[ 1, [ 'a', ], # comment\n {}, ]"),
+                   Some(TomlValue::Arr(vec![TomlValue::Int(1), TomlValue::Arr(vec![TomlValue::Str("a".into())]), TomlValue::Table(Default::default())])));
+        assert!(matches!(value("{ a.b = 1, a.c = 2 }"), Some(TomlValue::Table(t)) if matches!(&t["a"], TomlValue::Table(a) if a.len() == 2)));
+        for invalid in ["[,]", "[1,,]", "{ a = 1, a = 2 }", "{ a = {}, a.b = 1 }", "{ a = 1 b = 2 }"] {
+            assert_eq!(value(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn documents() {
+        let input = "a.b = 1 # comment\r\n[x.y]\nz = 2\n[x]\nw = 3\n[[t]]\n[[t]]\nv.u = 4\n[t.s]\n";
+        let Some(TomlValue::Table(doc)) = parse(document_parser(), input).result else { panic!() };
+        assert_eq!(doc.len(), 3);
+        let TomlValue::Table(x) = &doc["x"] else { panic!() };
+        assert_eq!(x["w"], TomlValue::Int(3));
+        let TomlValue::Arr(t) = &doc["t"] else { panic!() };
+        assert!(matches!(&t[..], [TomlValue::Table(first), TomlValue::Table(second)] if first.is_empty() && second.len() == 2));
+
+        let invalid = [
+            "a = 1\na = 2", "[a]\n[a]", "a = 1\n[a]", "[a]\nb.c = 1\n[a.b]", "[[a]]\n[a]", "a = []\n[[a]]",
+            "a = {}\n[a.b]", "[a.b.c]\n[a]\nb.c.d = 1", "a = 1 b = 2", "a = ", "[a", "[[a]", "= 1"
+        ];
+        for input in invalid {
+            assert!(parse(document_parser(), input).result.is_none(), "{}", input);
+        }
+
+        assert!(parse(document_parser(), "[a.b]\n[a]\nc.d = 1\n[a.c.e]").result.is_some());
+        assert!(parse(document_parser_with_max_depth(2), "a.b = [[1]]").result.is_some());
+        assert!(parse(document_parser_with_max_depth(2), "a.b.c = 1").result.is_none());
+        assert!(parse(document_parser_with_max_depth(2), "a = [[[1]]]").result.is_none());
+    }
+    fn document(input: &str) -> Option<TomlValue<'_>> {
+        let res = parse(document_parser(), input);
+        res.state.is_empty().then_some(res.result?)
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        for input in ["a = 1\na = 2", "a = 1\n\"a\" = 2", "a = 1\n'a' = 2", "a.b = 1\na.b = 2",
+                      "a = 1\na.b = 2", "a.b = 1\na = 2", "[t]\nx = 1\nx = 2", "[[t]]\nx = 1\nx = 2"] {
+            assert_eq!(document(input), None, "{}", input);
+        }
+
+        assert!(document("a.b = 1\na.c = 2\n[[t]]\nx = 1\n[[t]]\nx = 2").is_some());
+    }
+
+    #[test]
+    fn table_redefinition() {
+        for input in ["[a]\n[a]", "[a]\n[\"a\"]", "[a.b]\n[a.b]", "[a]\nb = 1\n[a.b]", "[[a]]\n[a]", "[a]\n[[a]]",
+                      // Tables defined by dotted keys can't be defined by headers, or vice versa.
+                      "a.b = 1\n[a]", "a.b.c = 1\n[a.b]", "[t]\nx.y = 1\n[t.x]", "[a.b]\nx = 1\n[a]\nb.y = 2"] {
+            assert_eq!(document(input), None, "{}", input);
+        }
+
+        // Sub-tables of tables defined by dotted keys can be defined by headers.
+        let input = "[fruit]\napple.color = 'red'\napple.taste.sweet = true\n[fruit.apple.texture]\nsmooth = true";
+        let Some(TomlValue::Table(doc)) = document(input) else { panic!() };
+        let TomlValue::Table(fruit) = &doc["fruit"] else { panic!() };
+        assert!(matches!(&fruit["apple"], TomlValue::Table(apple) if apple.len() == 3));
+    }
+
+    #[test]
+    fn inline_table_extension() {
+        for input in ["a = {}\n[a]", "a = { b = 1 }\na.c = 2", "a = { b = 1 }\n[a.c]", "a = { b = {} }\na.b.c = 1",
+                      "[t]\nx = { y = 1 }\n[t.x.z]", "a = { b = 1 }\n[[a.c]]", "a = { b.c = 1, b = {} }"] {
+            assert_eq!(document(input), None, "{}", input);
+        }
+    }
+
+    #[test]
+    fn numbers() {
+        for invalid in ["01", "-01", "+00", "0_1", "00.5", "01e2", "-9223372036854775809", "9_223_372_036_854_775_808",
+                        "0x8000000000000000", "0o1000000000000000000000",
+                        "0b1111111111111111111111111111111111111111111111111111111111111111"] {
+            assert_eq!(value(invalid), None, "{}", invalid);
+        }
+
+        for (valid, expected) in [("0", 0), ("-0", 0), ("+0", 0), ("9223372036854775807", i64::MAX),
+                                  ("0x7FFFFFFFFFFFFFFF", i64::MAX)] {
+            assert_eq!(value(valid), Some(TomlValue::Int(expected)), "{}", valid);
+        }
+        assert_eq!(value("0.05"), Some(TomlValue::Float(0.05)));
+        assert_eq!(value("1e06"), Some(TomlValue::Float(1e6)));
+    }
+
+    #[test]
+    fn invalid_dates() {
+        for invalid in ["2001-02-29", "2000-04-31", "2000-00-10", "2000-01-00", "2000-1-01", "20000-01-01",
+                        "1979-05-27T25:00:00", "1979-05-27T00:60:00", "1979-05-27T00:00:61", "1979-05-27T00:00:00.Z",
+                        "1979-05-27T00:00:00+24:00", "1979-05-27T00:00:00+01", "07:60:00"] {
+            assert_eq!(value(invalid), None, "{}", invalid);
+        }
+        assert_eq!(document("d = 2001-02-29"), None);
+        assert!(value("2000-02-29").is_some());
+    }
+
+    #[test]
+    fn line_ending_backslash() {
+        let valid = [("\"\"\"a \\  \n  b\"\"\"", "a b"), ("\"\"\"a\\\n\n\n  b\"\"\"", "ab"),
+                     ("\"\"\"a\\\r\n  \r\n  b\"\"\"", "ab"), ("\"\"\"a\\\n\"\"\"", "a"), ("'''a\\\n b'''", "a\\\n b")];
+        for (input, expected) in valid {
+            assert_eq!(value(input), Some(TomlValue::Str(expected.into())), "{}", input);
+        }
+
+        // Only multi-line basic strings can have line ending backslashes.
+        for invalid in ["\"a\\\nb\"", "\"\"\"a\\ b\"\"\"", "\"\"\"a\\\rb\"\"\""] {
+            assert_eq!(value(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn crlf() {
+        let input = "a = 1\r\n# comment\r\n[t]\r\nb = '''\r\nx\r\ny'''\r\nc = [\r\n  1, # one\r\n  2,\r\n]\r\n";
+        let Some(TomlValue::Table(doc)) = document(input) else { panic!() };
+        assert_eq!(doc["a"], TomlValue::Int(1));
+        let TomlValue::Table(t) = &doc["t"] else { panic!() };
+        assert_eq!(t["b"], TomlValue::Str("x\r\ny".into()));
+        assert_eq!(t["c"], TomlValue::Arr(vec![TomlValue::Int(1), TomlValue::Int(2)]));
+
+        // A carriage return must be followed by a newline.
+        for invalid in ["a = 1\rb = 2", "a = 1\r", "[t]\r", "a = \"x\ry\"", "a = '''x\ry'''"] {
+            assert_eq!(document(invalid), None, "{:?}", invalid);
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use core::fmt::{self, Debug};
+use std::eprintln;
+
+use crate::{core::Parser, slicelike::{consumed_input, SliceLike}};
+
+/// Tracing configuration and state, to be used as, or be part of, the user state when
+/// tracing parsers using [`traced`] or [`trace`](crate::core::ParserExt::trace).
+///
+/// The traced parsers require the user state to implement `AsMut<Tracer>`, which is
+/// implemented for `Tracer` itself. For a custom user state, implement `AsMut` to return
+/// its tracer.
+///
+/// Each traced parser logs its entry with a preview of the input, and its exit with a preview
+/// of the consumed input, indented according to the nesting of the traced parsers:
+/// ```text
+/// pair <- "(1,2)"
+///   number <- "1,2)"
+///   number -> ok "1"
+///   number <- "2)"
+///   number -> ok "2"
+/// pair -> ok "(1,2)"
+/// ```
+pub struct Tracer {
+    /// Whether tracing is enabled. Can be changed during parsing, e.g. from
+    /// [`lift_to_state`](crate::combinators::lift_to_state).
+    pub enabled: bool,
+
+    /// The maximum number of items of the input to show in the previews.
+    pub preview_len: usize,
+
+    depth: usize,
+    sink: fn(fmt::Arguments<'_>)
+}
+
+impl Tracer {
+    /// Create a tracer that prints to stderr.
+    pub fn new() -> Self {
+        Self::with_sink(|args| eprintln!("{}", args))
+    }
+
+    /// Create a tracer that logs to the `log` crate, with level `Trace`.
+    #[cfg(feature = "log")]
+    pub fn log() -> Self {
+        Self::with_sink(|args| log::trace!("{}", args))
+    }
+
+    /// Create a tracer that passes each line of the trace to `sink`.
+    pub fn with_sink(sink: fn(fmt::Arguments<'_>)) -> Self {
+        Self { enabled: true, preview_len: 20, depth: 0, sink }
+    }
+
+    fn write(&self, args: fmt::Arguments<'_>) {
+        (self.sink)(format_args!("{:indent$}{}", "", args, indent = self.depth * 2))
+    }
+}
+
+impl Default for Tracer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AsMut<Tracer> for Tracer {
+    fn as_mut(&mut self) -> &mut Tracer {
+        self
+    }
+}
+
+/// Preview of the start of an input, limited to `len` items.
+struct Preview<I>(I, usize);
+
+impl<I: SliceLike + Debug> Debug for Preview<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rest = self.0;
+        for _ in 0..self.1 {
+            match rest.slice_first_if(|_| true) {
+                Some((_, r)) => rest = r,
+                None => return self.0.fmt(f)
+            }
+        }
+
+        if rest.slice_is_empty() {
+            self.0.fmt(f)
+        } else {
+            self.0.slice_to(self.0.slice_len() - rest.slice_len()).fmt(f)?;
+            f.write_str("...")
+        }
+    }
+}
+
+/// Transform a parser to log its entry and exit using the [`Tracer`] in the user state.
+/// See [`Tracer`] for the format.
+///
+/// Also available as an extension function: [`trace`](crate::core::ParserExt::trace)
+///
+/// ### Arguments
+/// * `p` - the parser
+/// * `name` - the name of the parser in the trace
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::middle;
+/// use anpa::number::integer;
+/// use anpa::parsers::skip;
+/// use anpa::trace::Tracer;
+///
+/// let number = integer::<u32, _, _, _>().trace("number");
+/// let pair = middle(skip('('), number.left(skip(',')).bind(move |a| number.map(move |b| (a, b))), skip(')'))
+///     .trace("pair");
+///
+/// let mut tracer = Tracer::new();
+/// assert_eq!(parse_state(pair, "(1,2)", &mut tracer).result, Some((1, 2)));
+/// ```
+#[inline]
+pub fn traced<I: SliceLike + Debug, O, S: AsMut<Tracer>>(p: impl Parser<I, O, S>,
+                                                         name: &'static str
+) -> impl Parser<I, O, S> {
+    create_parser!(s, {
+        let tracer = s.user_state.as_mut();
+        if !tracer.enabled {
+            return p(s)
+        }
+
+        let start = s.input;
+        tracer.write(format_args!("{} <- {:?}", name, Preview(start, tracer.preview_len)));
+        tracer.depth += 1;
+
+        let res = p(s);
+
+        let tracer = s.user_state.as_mut();
+        tracer.depth = tracer.depth.saturating_sub(1);
+        if res.is_some() {
+            let consumed = consumed_input(start, s.input);
+            tracer.write(format_args!("{} -> ok {:?}", name, Preview(consumed, tracer.preview_len)));
+        } else {
+            tracer.write(format_args!("{} -> failed", name));
+        }
+        res
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, string::String, thread_local};
+
+    use crate::{combinators::{or, right}, core::{parse_state, ParserExt}, parsers::take, trace::Tracer};
+
+    thread_local! {
+        static TRACE: RefCell<String> = const { RefCell::new(String::new()) };
+    }
+
+    #[test]
+    fn trace_test() {
+        let p = right(take("ab").trace("ab"), or(take("x").trace("x"), take("cdefg").trace("cdefg"))).trace("all");
+
+        let mut tracer = Tracer::with_sink(|args| TRACE.with(|t| {
+            use core::fmt::Write;
+            writeln!(t.borrow_mut(), "{}", args).unwrap();
+        }));
+        tracer.preview_len = 4;
+
+        parse_state(p, "abcdefg", &mut tracer);
+        assert_eq!(TRACE.with(|t| t.take()), "\
+all <- \"abcd\"...
+  ab <- \"abcd\"...
+  ab -> ok \"ab\"
+  x <- \"cdef\"...
+  x -> failed
+  cdefg <- \"cdef\"...
+  cdefg -> ok \"cdef\"...
+all -> ok \"abcd\"...
+");
+
+        tracer.enabled = false;
+        parse_state(p, "abcdefg", &mut tracer);
+        assert!(TRACE.with(|t| t.take()).is_empty());
+    }
+}
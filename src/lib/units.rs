@@ -0,0 +1,177 @@
+use core::time::Duration;
+
+use crate::{core::Parser, findbyte::ByteInput};
+
+/// A unit of a quantity, i.e. its name and its size in the base unit, e.g. `("ms", 1_000_000)`
+/// for milliseconds in nanoseconds.
+pub type Unit<'b> = (&'b str, u64);
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// The units of [`duration`], in nanoseconds.
+pub const DURATION_UNITS: &[Unit<'static>] = &[
+    ("ns", 1), ("us", 1_000), ("µs", 1_000), ("ms", 1_000_000), ("s", NANOS_PER_SEC),
+    ("m", 60 * NANOS_PER_SEC), ("min", 60 * NANOS_PER_SEC), ("h", 3600 * NANOS_PER_SEC),
+    ("d", 86400 * NANOS_PER_SEC), ("w", 604800 * NANOS_PER_SEC)
+];
+
+/// The units of [`byte_size`], in bytes. Both decimal (e.g. `MB`) and binary (e.g. `MiB`)
+/// units are included.
+pub const BYTE_UNITS: &[Unit<'static>] = &[
+    ("B", 1),
+    ("kB", 1000), ("KB", 1000), ("MB", 1000_u64.pow(2)), ("GB", 1000_u64.pow(3)),
+    ("TB", 1000_u64.pow(4)), ("PB", 1000_u64.pow(5)), ("EB", 1000_u64.pow(6)),
+    ("KiB", 1 << 10), ("MiB", 1 << 20), ("GiB", 1 << 30), ("TiB", 1 << 40), ("PiB", 1 << 50),
+    ("EiB", 1 << 60)
+];
+
+/// Parse a single number and unit, e.g. `2.5 GiB`. Returns the value in the base unit and
+/// the length of the component.
+fn component(bytes: &[u8], units: &[Unit]) -> Option<(u64, usize)> {
+    let int_len = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+    let frac_len = match bytes.get(int_len) {
+        Some(b'.') => bytes[int_len + 1..].iter().take_while(|b| b.is_ascii_digit()).count(),
+        _ => 0
+    };
+
+    let number_len = int_len + if frac_len > 0 { 1 + frac_len } else { 0 };
+    let unit_start = number_len + bytes[number_len..].iter().take_while(|b| **b == b' ').count();
+
+    // Use the longest unit that is not followed by a letter, e.g. `min` rather than `m`.
+    let (name, scale) = units.iter().filter(|(name, _)| {
+        bytes[unit_start..].starts_with(name.as_bytes())
+            && !bytes.get(unit_start + name.len()).is_some_and(u8::is_ascii_alphabetic)
+    }).max_by_key(|(name, _)| name.len())?;
+
+    if int_len == 0 || name.is_empty() {
+        return None
+    }
+
+    let mut value = 0u64;
+    for digit in &bytes[..int_len] {
+        value = value.checked_mul(10)?.checked_add((digit - b'0') as u64)?;
+    }
+    value = value.checked_mul(*scale)?;
+
+    // Digits beyond the precision of the scale don't affect the (truncated) result.
+    let frac = &bytes[int_len + 1..int_len + 1 + frac_len.min(19)];
+    if !frac.is_empty() {
+        let numerator = frac.iter().fold(0u128, |acc, d| acc * 10 + (d - b'0') as u128);
+        let fraction = numerator * *scale as u128 / 10u128.pow(frac.len() as u32);
+        value = value.checked_add(fraction as u64)?;
+    }
+
+    Some((value, unit_start + name.len()))
+}
+
+/// Create a parser for a quantity with units, e.g. `10MB` or `1h30m`, using the units in
+/// `units`. The quantity is one or more components, each consisting of a number with an
+/// optional fraction, optional spaces and a unit. The result is the sum of the components,
+/// in the base unit of `units`, truncated to an integer.
+///
+/// The units are case sensitive, and the longest matching unit is used. A unit must not be
+/// followed by an ASCII letter.
+///
+/// ### Consuming
+/// Consumes all components, on successful parse. The parser fails if there is no component,
+/// or if the result doesn't fit in a `u64`.
+///
+/// ### Arguments
+/// * `units` - the units and their sizes in the base unit, e.g. [`BYTE_UNITS`]
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::units::quantity;
+///
+/// let lengths = [("mm", 1), ("cm", 10), ("m", 1000), ("km", 1_000_000)];
+/// assert_eq!(parse(quantity(&lengths), "1km250m").result, Some(1_250_000));
+/// assert_eq!(parse(quantity(&lengths), "1.25 cm").result, Some(12));
+/// assert_eq!(parse(quantity(&lengths), "1 mi").result, None);
+/// ```
+#[inline]
+pub fn quantity<'a, 'b, I: ByteInput<'a>, S>(units: &'b [Unit<'b>]) -> impl Parser<I, u64, S> + 'b {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice();
+        let (mut total, mut len) = component(bytes, units)?;
+
+        while let Some((value, component_len)) = component(&bytes[len..], units) {
+            total = total.checked_add(value)?;
+            len += component_len;
+        }
+
+        s.input = s.input.slice_from(len);
+        Some(total)
+    })
+}
+
+/// Create a parser for a human readable duration, e.g. `90s`, `1h30m` or `1.5 ms`. See
+/// [`quantity`] and [`DURATION_UNITS`] for the syntax.
+///
+/// ### Consuming
+/// Consumes the duration, on successful parse. The parser fails if the duration is longer
+/// than `u64::MAX` nanoseconds (about 584 years).
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::units::duration;
+/// use core::time::Duration;
+///
+/// assert_eq!(parse(duration(), "1h30m").result, Some(Duration::from_secs(5400)));
+/// assert_eq!(parse(duration(), "2.5s").result, Some(Duration::from_millis(2500)));
+/// assert_eq!(parse(duration(), "250 µs").result, Some(Duration::from_micros(250)));
+/// ```
+#[inline]
+pub fn duration<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Duration, S> {
+    create_parser!(s, {
+        quantity(DURATION_UNITS)(s).map(Duration::from_nanos)
+    })
+}
+
+/// Create a parser for a human readable size in bytes, e.g. `10MB` or `2.5 GiB`. See
+/// [`quantity`] and [`BYTE_UNITS`] for the syntax.
+///
+/// ### Consuming
+/// Consumes the size, on successful parse. The parser fails if the size doesn't fit in a
+/// `u64`.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::units::byte_size;
+///
+/// assert_eq!(parse(byte_size(), "10MB").result, Some(10_000_000));
+/// assert_eq!(parse(byte_size(), "2.5 GiB").result, Some(2_684_354_560));
+/// assert_eq!(parse(byte_size(), "16EiB").result, None);
+/// ```
+#[inline]
+pub fn byte_size<'a, I: ByteInput<'a>, S>() -> impl Parser<I, u64, S> {
+    quantity(BYTE_UNITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{core::parse, units::{byte_size, duration}};
+
+    #[test]
+    fn quantities() {
+        assert_eq!(parse(duration(), "1d2h3min4s5ms6us7ns").result, Some(Duration::new(93784, 5_006_007)));
+        assert_eq!(parse(duration(), "1w").result, Some(Duration::from_secs(604800)));
+        assert_eq!(parse(duration(), "0.000000001s").result, Some(Duration::from_nanos(1)));
+
+        let res = parse(duration(), "1h 30m");
+        assert_eq!((res.result, res.state), (Some(Duration::from_secs(3600)), " 30m"));
+
+        let res = parse(byte_size(), b"1.5KiB/s".as_slice());
+        assert_eq!((res.result, res.state), (Some(1536), &b"/s"[..]));
+        assert_eq!(parse(byte_size(), "15EiB").result, Some(15 << 60));
+        assert_eq!(parse(byte_size(), "0.99999999999999999999999B").result, Some(0));
+
+        for invalid in ["", "s", "1", "1.s", ".5s", "5sec", "1S", "18446744073709551616ns"] {
+            assert_eq!(parse(duration(), invalid).result, None, "{}", invalid);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{charlike::CharLike, core::Parser, prefix::Prefix, slicelike::SliceLike};
+use crate::{charlike::CharLike, combinators::{left, middle, right}, core::Parser, findbyte::{byteset, eq, find, get_seq_pos, take_while_byteclass, ByteInput}, prefix::Prefix, slicelike::{consumed_input, SliceLike}};
 
 /// Trait for inputs that can be trimmed, i.e. having ASCII whitespace removed
 /// from the start.
@@ -30,6 +30,14 @@ impl TrimmableUtf8 for &str {
     }
 }
 
+// All Unicode whitespace is in the basic multilingual plane, so UTF-16 code units can be
+// checked one by one.
+impl TrimmableUtf8 for &[u16] {
+    fn prefix() -> impl Prefix<Self, Self> {
+        Utf8Whitespace()
+    }
+}
+
 /// Create a parser that parses and returns ASCII whitespace.
 #[inline]
 pub fn ascii_whitespace<I: TrimmableAscii, S>() -> impl Parser<I, I, S> {
@@ -54,17 +62,466 @@ pub fn skip_whitespace<I: TrimmableUtf8, S>() -> impl Parser<I, (), S> {
     skip!(I::prefix())
 }
 
+/// Create a parser that parses and returns whitespace as defined by `pred`, e.g. to also
+/// include `\u{00A0}`. For `&str`, this uses the same search as [`str::trim_start_matches`].
+/// This parser never fails.
+///
+/// Use [`whitespace_set`] for a faster parser if all whitespace is ASCII.
+///
+/// ### Arguments
+/// * `pred` - whether a character is whitespace
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::whitespace::whitespace_of;
+///
+/// let p = whitespace_of(|c: char| c == '\u{00A0}' || c.is_ascii_whitespace());
+///
+/// let res = parse(p, " \u{00A0}x");
+/// assert_eq!(res.result, Some(" \u{00A0}"));
+/// assert_eq!(res.state, "x");
+/// ```
+#[inline]
+pub fn whitespace_of<A: CharLike, I: SliceLike<RefItem = A>, S>(pred: impl FnOnce(char) -> bool + Copy) -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        let len = s.input.slice_find_pred(|c: A| !pred(c.as_char())).unwrap_or(s.input.slice_len());
+        let res;
+        (res, s.input) = s.input.slice_split_at(len);
+        Some(res)
+    })
+}
+
+/// Create a parser that parses and returns whitespace consisting of the bytes in `bytes`, e.g.
+/// to exclude form feeds from ASCII whitespace. The bytes should be ASCII. This uses the same
+/// fast search as [`take_while_byteclass`](crate::findbyte::take_while_byteclass), and never
+/// fails.
+///
+/// ### Arguments
+/// * `bytes` - the whitespace bytes
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::whitespace::whitespace_set;
+///
+/// let p = whitespace_set(b" \t\r\n");
+///
+/// let res = parse(p, " \n\x0cx");
+/// assert_eq!(res.result, Some(" \n"));
+/// assert_eq!(res.state, "\x0cx");
+/// ```
+#[inline]
+pub fn whitespace_set<'a, I: ByteInput<'a>, S>(bytes: &[u8]) -> impl Parser<I, I, S> {
+    take_while_byteclass(byteset(bytes))
+}
+
+/// Get the length of the spaces and tabs at the start of `input`.
+#[inline(always)]
+fn inline_whitespace_len<A: CharLike, I: SliceLike<RefItem = A>>(input: I) -> I::Idx {
+    input.slice_find_pred(|c: A| !matches!(c.as_char(), ' ' | '\t')).unwrap_or(input.slice_len())
+}
+
+/// Create a parser that parses and returns inline whitespace, i.e. spaces and tabs, but not
+/// newlines. This parser never fails.
+#[inline]
+pub fn inline_whitespace<A: CharLike, I: SliceLike<RefItem = A>, S>() -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        let (res, rest) = s.input.slice_split_at(inline_whitespace_len(s.input));
+        s.input = rest;
+        Some(res)
+    })
+}
+
+/// Create a parser that parses and ignores inline whitespace, i.e. spaces and tabs, but not
+/// newlines, e.g. for line based grammars. This parser never fails.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::right;
+/// use anpa::whitespace::{newline, skip_inline_whitespace};
+///
+/// let p = right(skip_inline_whitespace(), newline());
+///
+/// assert_eq!(parse(p, " \t\r\nx").state, "x");
+/// assert_eq!(parse(p, " \t x").result, None);
+/// ```
+#[inline]
+pub fn skip_inline_whitespace<A: CharLike, I: SliceLike<RefItem = A>, S>() -> impl Parser<I, (), S> {
+    create_parser!(s, {
+        s.input = s.input.slice_from(inline_whitespace_len(s.input));
+        Some(())
+    })
+}
+
+/// Create a parser that parses and returns a newline, i.e. `\n` or `\r\n`.
+///
+/// ### Consuming
+/// The newline, on successful parse.
+#[inline]
+pub fn newline<A: CharLike, I: SliceLike<RefItem = A>, S>() -> impl Parser<I, I, S> {
+    create_parser!(s, {
+        let rest = s.input.slice_first_if(|c: A| c.as_char() == '\r').map_or(s.input, |(_, rest)| rest);
+        let (_, rest) = rest.slice_first_if(|c: A| c.as_char() == '\n')?;
+        let res = consumed_input(s.input, rest);
+        s.input = rest;
+        Some(res)
+    })
+}
+
+/// Create a parser for the end of a line, i.e. a newline (`\n` or `\r\n`) or the end of the
+/// input.
+///
+/// ### Consuming
+/// The newline, if any, on successful parse.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::left;
+/// use anpa::parsers::item_while;
+/// use anpa::whitespace::eol;
+///
+/// let line = left(item_while(|c: char| c != '\r' && c != '\n'), eol());
+///
+/// let res = parse(line, "a\r\nb");
+/// assert_eq!(res.result, Some("a"));
+/// assert_eq!(res.state, "b");
+/// assert_eq!(parse(line, "b").result, Some("b"));
+/// ```
+#[inline]
+pub fn eol<A: CharLike, I: SliceLike<RefItem = A>, S>() -> impl Parser<I, (), S> {
+    create_parser!(s, {
+        if s.input.slice_is_empty() {
+            return Some(())
+        }
+        newline()(s).map(|_| ())
+    })
+}
+
+/// Create a parser that skips any combination of whitespace and comments, in the spirit of
+/// megaparsec's `space`. This parser never fails.
+///
+/// To disable either kind of comment, pass [`failure()`](crate::parsers::failure).
+///
+/// ### Arguments
+/// * `space` - a parser for whitespace, e.g. [`skip_ascii_whitespace()`]
+/// * `line_comment` - a parser for a line comment
+/// * `block_comment` - a parser for a block comment
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::combinators::right;
+/// use anpa::parsers::{skip, until};
+/// use anpa::whitespace::{skip_ascii_whitespace, space_consumer};
+///
+/// let sc = space_consumer(skip_ascii_whitespace(),
+///                         right(skip("//"), until('\n')),
+///                         right(skip("/*"), until("*/")));
+///
+/// let input = "  // comment\n /* block */ x";
+/// assert_eq!(parse(sc, input).state, "x");
+/// ```
+#[inline]
+pub fn space_consumer<I: SliceLike, O1, O2, O3, S>(space: impl Parser<I, O1, S>,
+                                                   line_comment: impl Parser<I, O2, S>,
+                                                   block_comment: impl Parser<I, O3, S>
+) -> impl Parser<I, (), S> {
+    create_parser!(s, {
+        loop {
+            space(s);
+            let pos = s.input;
+
+            if line_comment(s).is_none() {
+                s.input = pos;
+                if block_comment(s).is_none() {
+                    s.input = pos;
+                    return Some(())
+                }
+            }
+
+            // Stop if the comment parsers succeed without consuming anything.
+            if s.input.slice_len() == pos.slice_len() {
+                return Some(())
+            }
+        }
+    })
+}
+
+/// Create a parser for a lexeme, i.e. `p` followed by anything skipped by the space
+/// consumer `sc`.
+///
+/// ### Arguments
+/// * `sc` - a parser skipping whitespace, e.g. [`space_consumer`]
+/// * `p` - the parser
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::parsers::take;
+/// use anpa::whitespace::{lexeme, skip_ascii_whitespace};
+///
+/// let p = lexeme(skip_ascii_whitespace(), take("let"));
+///
+/// let res = parse(p, "let   x");
+/// assert_eq!(res.result, Some("let"));
+/// assert_eq!(res.state, "x");
+/// ```
+#[inline]
+pub fn lexeme<I: SliceLike, O, O2, S>(sc: impl Parser<I, O2, S>,
+                                      p: impl Parser<I, O, S>
+) -> impl Parser<I, O, S> {
+    left(p, sc)
+}
+
+/// Create a parser for `p` surrounded by anything skipped by the space consumer `sc`.
+///
+/// ### Arguments
+/// * `sc` - a parser skipping whitespace, e.g. [`space_consumer`]
+/// * `p` - the parser
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::parsers::take;
+/// use anpa::whitespace::{padded, skip_ascii_whitespace};
+///
+/// let p = padded(skip_ascii_whitespace(), take("let"));
+///
+/// let res = parse(p, "  let   x");
+/// assert_eq!(res.result, Some("let"));
+/// assert_eq!(res.state, "x");
+/// ```
+#[inline]
+pub fn padded<I: SliceLike, O, O2, S>(sc: impl Parser<I, O2, S>,
+                                      p: impl Parser<I, O, S>
+) -> impl Parser<I, O, S> {
+    middle(sc, p, sc)
+}
+
+/// Create a parser for `p` with any ASCII whitespace before and after it skipped. This is
+/// [`padded`] with [`skip_ascii_whitespace()`].
+///
+/// ### Arguments
+/// * `p` - the parser
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::number::integer;
+/// use anpa::whitespace::trimmed;
+///
+/// let p = trimmed(integer::<u32, _, _, _>());
+///
+/// let res = parse(p, " 12\n x");
+/// assert_eq!(res.result, Some(12));
+/// assert_eq!(res.state, "x");
+/// ```
+#[inline]
+pub fn trimmed<I: TrimmableAscii, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, O, S> {
+    padded(skip_ascii_whitespace(), p)
+}
+
+/// Create a parser for `p` with any ASCII whitespace before it skipped.
+///
+/// ### Arguments
+/// * `p` - the parser
+#[inline]
+pub fn pad_left<I: TrimmableAscii, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, O, S> {
+    right(skip_ascii_whitespace(), p)
+}
+
+/// Create a parser for `p` with any ASCII whitespace after it skipped. This is [`lexeme`]
+/// with [`skip_ascii_whitespace()`].
+///
+/// ### Arguments
+/// * `p` - the parser
+#[inline]
+pub fn pad_right<I: TrimmableAscii, O, S>(p: impl Parser<I, O, S>) -> impl Parser<I, O, S> {
+    lexeme(skip_ascii_whitespace(), p)
+}
+
+/// Create a parser for a line comment starting with `start`, e.g. `//`. The result is the
+/// content of the comment, i.e. the text after `start` up to the end of the line.
+///
+/// ### Consuming
+/// Consumes the comment, but not the newline ending it, on successful parse.
+///
+/// ### Arguments
+/// * `start` - the start of the comment, e.g. `//` or `#`
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::whitespace::line_comment;
+///
+/// let res = parse(line_comment("//"), "// note\nx");
+/// assert_eq!(res.result, Some(" note"));
+/// assert_eq!(res.state, "\nx");
+/// ```
+#[inline]
+pub fn line_comment<'a, 'b, I: ByteInput<'a>, S>(start: &'b str) -> impl Parser<I, I, S> + 'b {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice().strip_prefix(start.as_bytes())?;
+        let len = find(bytes, eq(b'\n')).unwrap_or(bytes.len());
+        let content = s.input.slice_to(start.len() + len).slice_from(start.len());
+        s.input = s.input.slice_from(start.len() + len);
+        Some(content)
+    })
+}
+
+/// Get the length of the content of the block comment starting at the start of `bytes`,
+/// and the length of the whole comment.
+fn block_comment_len(bytes: &[u8], open: &[u8], close: &[u8], nested: bool) -> Option<(usize, usize)> {
+    let mut pos = open.len();
+    let mut depth = 1;
+    bytes.starts_with(open).then_some(())?;
+
+    loop {
+        let close_pos = pos + get_seq_pos(&bytes[pos..], close)?;
+
+        // An `open` overlapping `close`, e.g. `(*)`, opens a comment.
+        let open_end = (close_pos + open.len()).min(bytes.len());
+        match get_seq_pos(&bytes[pos..open_end], open).filter(|_| nested) {
+            Some(open_pos) => {
+                depth += 1;
+                pos += open_pos + open.len();
+            },
+            None if depth == 1 => return Some((close_pos - open.len(), close_pos + close.len())),
+            None => {
+                depth -= 1;
+                pos = close_pos + close.len();
+            }
+        }
+    }
+}
+
+/// Create a parser for a block comment, e.g. `/* comment */`. The result is the content of
+/// the comment, i.e. the text between the delimiters.
+///
+/// ### Consuming
+/// Consumes the comment, on successful parse. The parser fails if the comment is not
+/// closed.
+///
+/// ### Arguments
+/// * `open` - the start of the comment, e.g. `/*`
+/// * `close` - the end of the comment, e.g. `*/`
+/// * `nested` - whether comments nest, as in Rust, or end at the first `close`, as in C
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::whitespace::block_comment;
+///
+/// let input = "/* a /* b */ c */";
+/// assert_eq!(parse(block_comment("/*", "*/", true), input).result, Some(" a /* b */ c "));
+/// assert_eq!(parse(block_comment("/*", "*/", false), input).result, Some(" a /* b "));
+/// ```
+#[inline]
+pub fn block_comment<'a, 'b, I: ByteInput<'a>, S>(open: &'b str, close: &'b str, nested: bool) -> impl Parser<I, I, S> + 'b {
+    create_parser!(s, {
+        let (content_len, len) = block_comment_len(s.input.as_byte_slice(), open.as_bytes(), close.as_bytes(), nested)?;
+        let content = s.input.slice_to(open.len() + content_len).slice_from(open.len());
+        s.input = s.input.slice_from(len);
+        Some(content)
+    })
+}
+
+/// The comment syntax used by [`skip_trivia`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trivia<'b> {
+    /// The starts of line comments, e.g. `//`.
+    pub line_comments: &'b [&'b str],
+
+    /// The delimiters of block comments, e.g. `("/*", "*/")`.
+    pub block_comments: &'b [(&'b str, &'b str)],
+
+    /// Whether block comments nest.
+    pub nested: bool
+}
+
+impl Trivia<'static> {
+    /// C style comments, i.e. `// line` and `/* block */`.
+    pub const C: Trivia<'static> = Trivia { line_comments: &["//"], block_comments: &[("/*", "*/")], nested: false };
+
+    /// Rust style comments, i.e. C style comments where block comments nest.
+    pub const RUST: Trivia<'static> = Trivia { nested: true, ..Trivia::C };
+
+    /// Shell style comments, i.e. `# line`.
+    pub const SHELL: Trivia<'static> = Trivia { line_comments: &["#"], block_comments: &[], nested: false };
+}
+
+/// Create a parser that skips any combination of ASCII whitespace and comments, e.g. for use
+/// with [`lexeme`] and [`padded`]. This parser never fails. This is a faster alternative to
+/// [`space_consumer`] for the common comment styles.
+///
+/// An unclosed block comment is not skipped, so that it can be reported as an error.
+///
+/// ### Arguments
+/// * `trivia` - the comment syntax, e.g. [`Trivia::C`]
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::parsers::take;
+/// use anpa::whitespace::{lexeme, skip_trivia, Trivia};
+///
+/// let p = lexeme(skip_trivia(Trivia::RUST), take("x"));
+///
+/// let res = parse(p, "x // a\n /* b /* c */ */ y");
+/// assert_eq!(res.result, Some("x"));
+/// assert_eq!(res.state, "y");
+/// ```
+#[inline]
+pub fn skip_trivia<'a, 'b, I: ByteInput<'a>, S>(trivia: Trivia<'b>) -> impl Parser<I, (), S> + 'b {
+    create_parser!(s, {
+        let bytes = s.input.as_byte_slice();
+        let mut pos = 0;
+
+        loop {
+            pos += bytes[pos..].iter().take_while(|b| b.is_ascii_whitespace()).count();
+            let rest = &bytes[pos..];
+
+            if let Some(start) = trivia.line_comments.iter().find(|start| rest.starts_with(start.as_bytes())) {
+                pos += start.len() + find(&rest[start.len()..], eq(b'\n')).unwrap_or(rest.len() - start.len());
+            } else if let Some(len) = trivia.block_comments.iter().find_map(|(open, close)| {
+                block_comment_len(rest, open.as_bytes(), close.as_bytes(), trivia.nested).map(|(_, len)| len)
+            }) {
+                pos += len;
+            } else {
+                break
+            }
+        }
+
+        s.input = s.input.slice_from(pos);
+        Some(())
+    })
+}
+
 /// `Prefix` that matches zero or more ASCII whitespaces.
 #[derive(Clone, Copy)]
 pub struct AsciiWhitespace();
 
-/// `Prefix` that matches zero or more UTF-8 whitespaces.
+/// `Prefix` that matches zero or more UTF-8 (or UTF-16) whitespaces.
 #[derive(Clone, Copy)]
 pub struct Utf8Whitespace();
 
 impl AsciiWhitespace {
     fn count_whitespace<A: CharLike>(slice: &[A]) -> usize {
-        slice.iter().position(|a| !a.as_char().is_ascii_whitespace()).unwrap_or(slice.len())
+        slice.iter().position(|a| !a.is_ascii_whitespace()).unwrap_or(slice.len())
+    }
+}
+
+impl<'a> Prefix<&'a [u16], &'a [u16]> for Utf8Whitespace {
+    fn take_prefix(&self, haystack: &'a [u16]) -> Option<(&'a [u16], &'a [u16])> {
+        let idx = haystack.iter().position(|c| !c.is_whitespace()).unwrap_or(haystack.len());
+        Some(haystack.split_at(idx))
+    }
+
+    fn skip_prefix(&self, haystack: &'a [u16]) -> Option<&'a [u16]> {
+        self.take_prefix(haystack).map(|(_, rest)| rest)
     }
 }
 
@@ -99,7 +556,7 @@ impl_whitespace_prefix_str!(Utf8Whitespace, trim_start);
 
 #[cfg(test)]
 mod tests {
-    use crate::{core::parse, whitespace::{ascii_whitespace, skip_ascii_whitespace}};
+    use crate::{combinators::right, core::parse, number::integer, parsers::{failure, skip, take, until}, whitespace::{ascii_whitespace, block_comment, eol, whitespace_of, whitespace_set, inline_whitespace, lexeme, line_comment, newline, pad_left, pad_right, skip_ascii_whitespace, skip_inline_whitespace, skip_trivia, space_consumer, trimmed, Trivia}};
 
     #[test]
     fn test_whitespace_u8() {
@@ -115,7 +572,7 @@ mod tests {
 
     #[test]
     fn test_whitespace_u8_nothing() {
-        let input = &[1, 2];
+        let input = &[1u8, 2];
         let res = parse(skip_ascii_whitespace(), input.as_slice());
         assert_eq!(res.result, Some(()));
         assert_eq!(res.state, &[1, 2]);
@@ -148,4 +605,95 @@ mod tests {
         assert_eq!(res.result, Some("  "));
         assert_eq!(res.state, "12");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_space_consumer() {
+        let sc = space_consumer(skip_ascii_whitespace(), right(skip('#'), until('\n')), failure::<_, (), _>());
+        let p = lexeme(sc, take("a"));
+
+        let res = parse(p, "a # one\n  # two\n b");
+        assert_eq!(res.result, Some("a"));
+        assert_eq!(res.state, "b");
+
+        // Unterminated comments are left alone.
+        let res = parse(p, "a # one");
+        assert_eq!(res.result, Some("a"));
+        assert_eq!(res.state, "# one");
+    }
+
+    #[test]
+    fn test_comments() {
+        let res = parse(block_comment("(*", "*)", true), "(* (* *) (*) *) *) x");
+        assert_eq!(res.result, Some(" (* *) (*) *) "));
+        assert_eq!(res.state, " x");
+        assert_eq!(parse(block_comment("(*", "*)", true), "(* (* *)").result, None);
+        assert_eq!(parse(block_comment("/*", "*/", false), "/*/").result, None);
+        assert_eq!(parse(line_comment("--"), b"--".as_slice()).result, Some(&b""[..]));
+
+        let res = parse(skip_trivia(Trivia::C), " /**/ // a\n\t/* b // */ # c");
+        assert_eq!(res.state, "# c");
+        let res = parse(skip_trivia(Trivia::SHELL), "# a\n  # b\n/* c */");
+        assert_eq!(res.state, "/* c */");
+
+        // Unclosed comments are left alone.
+        let res = parse(skip_trivia(Trivia::RUST), "\n/* /* */ x");
+        assert_eq!(res.result, Some(()));
+        assert_eq!(res.state, "/* /* */ x");
+    }
+
+    #[test]
+    fn test_lines() {
+        let res = parse(inline_whitespace(), " \t \n x");
+        assert_eq!(res.result, Some(" \t "));
+        assert_eq!(res.state, "\n x");
+        let res = parse(skip_inline_whitespace(), b"\t\r\n".as_slice());
+        assert_eq!(res.result, Some(()));
+        assert_eq!(res.state, b"\r\n");
+
+        let res = parse(newline(), "\r\n\n");
+        assert_eq!(res.result, Some("\r\n"));
+        assert_eq!(res.state, "\n");
+        assert_eq!(parse(newline(), "\n").result, Some("\n"));
+        assert_eq!(parse(newline(), b"\r\r\n".as_slice()).result, None);
+        assert_eq!(parse(newline(), "").result, None);
+
+        assert_eq!(parse(eol(), "").result, Some(()));
+        assert_eq!(parse(eol(), "\r\nx").state, "x");
+        assert_eq!(parse(eol(), " \n").result, None);
+    }
+
+    #[test]
+    fn test_custom_whitespace() {
+        let p = whitespace_of(|c| c == '\u{00A0}' || c == ' ');
+        let res = parse(p, "\u{00A0} \u{00A0}\tx");
+        assert_eq!(res.result, Some("\u{00A0} \u{00A0}"));
+        assert_eq!(res.state, "\tx");
+        assert_eq!(parse(p, "x").result, Some(""));
+
+        let p = whitespace_set(b" \t");
+        let res = parse(p, b"\t \n".as_slice());
+        assert_eq!(res.result, Some(b"\t ".as_slice()));
+        assert_eq!(res.state, b"\n");
+        assert_eq!(parse(whitespace_set(b" \t"), "").result, Some(""));
+    }
+
+    #[test]
+    fn test_trimmed() {
+        let p = integer::<u32, _, _, _>();
+
+        let res = parse(trimmed(p), " \t1 \n2");
+        assert_eq!(res.result, Some(1));
+        assert_eq!(res.state, "2");
+        assert_eq!(parse(trimmed(p), "1").result, Some(1));
+        assert_eq!(parse(trimmed(p), " ").result, None);
+
+        let res = parse(pad_left(integer::<u32, _, _, _>()), b" 1 ".as_slice());
+        assert_eq!(res.result, Some(1));
+        assert_eq!(res.state, b" ");
+
+        let res = parse(pad_right(take("x")), "x  y");
+        assert_eq!(res.result, Some("x"));
+        assert_eq!(res.state, "y");
+        assert_eq!(parse(pad_right(take("x")), " x").result, None);
+    }
+}
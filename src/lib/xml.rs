@@ -0,0 +1,223 @@
+use crate::{core::Parser, findbyte::{eq, find, get_seq_pos, whitespace, ByteFinder, ByteInput}};
+
+/// An event of an XML or HTML document, as produced by [`event_parser`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event<I> {
+    /// A start tag, e.g. `<a href="/">`, or an empty element tag, e.g. `<br/>`.
+    StartTag { name: I, attrs: Attributes<I>, self_closing: bool },
+
+    /// An end tag, e.g. `</a>`.
+    EndTag(I),
+
+    /// Text between markup. Entities, e.g. `&amp;`, are not decoded.
+    Text(I),
+
+    /// The content of a comment, e.g. ` note ` for `<!-- note -->`.
+    Comment(I),
+
+    /// The content of a CDATA section, e.g. `a < b` for `<![CDATA[a < b]]>`.
+    CData(I),
+
+    /// The content of a processing instruction, e.g. `xml version="1.0"` for
+    /// `<?xml version="1.0"?>`.
+    ProcessingInstruction(I),
+
+    /// The content of a declaration, e.g. `DOCTYPE html` for `<!DOCTYPE html>`.
+    Declaration(I)
+}
+
+/// The unparsed attributes of a start tag. Iterate over it to get the name and value of each
+/// attribute. The value is `None` for attributes without a value, e.g. `disabled` in HTML,
+/// and quotes are removed from quoted values. Entities are not decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attributes<I>(I);
+
+impl<'a, I: ByteInput<'a>> Attributes<I> {
+    /// Get the unparsed attributes.
+    pub fn raw(self) -> I {
+        self.0
+    }
+
+    /// Get the value of the first attribute named `name`. Returns `Some(None)` if the
+    /// attribute has no value.
+    pub fn get(self, name: &str) -> Option<Option<I>> {
+        self.into_iter().find(|(n, _)| n.as_byte_slice() == name.as_bytes()).map(|(_, v)| v)
+    }
+}
+
+/// Get the position of the first byte in `bytes` matched by `finder`, or the length of
+/// `bytes` if there is none.
+fn find_or_end(bytes: &[u8], finder: impl ByteFinder) -> usize {
+    find(bytes, finder).unwrap_or(bytes.len())
+}
+
+impl<'a, I: ByteInput<'a>> Iterator for Attributes<I> {
+    type Item = (I, Option<I>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.0.as_byte_slice();
+        let start = bytes.iter().position(|b| !whitespace().matches(*b))?;
+        let name_end = start + find_or_end(&bytes[start..], whitespace() | eq(b'='));
+        let name = self.0.slice_to(name_end).slice_from(start);
+
+        let after_name = name_end + bytes[name_end..].iter().take_while(|b| whitespace().matches(**b)).count();
+        if bytes.get(after_name) != Some(&b'=') {
+            self.0 = self.0.slice_from(name_end);
+            return Some((name, None))
+        }
+
+        let value_start = after_name + 1 + bytes[after_name + 1..].iter().take_while(|b| whitespace().matches(**b)).count();
+        let (value, end) = match bytes.get(value_start) {
+            Some(quote @ (b'"' | b'\'')) => {
+                let value_end = value_start + 1 + find_or_end(&bytes[value_start + 1..], eq(*quote));
+                (self.0.slice_to(value_end).slice_from(value_start + 1), (value_end + 1).min(bytes.len()))
+            },
+            _ => {
+                let value_end = value_start + find_or_end(&bytes[value_start..], whitespace());
+                (self.0.slice_to(value_end).slice_from(value_start), value_end)
+            }
+        };
+
+        self.0 = self.0.slice_from(end);
+        Some((name, Some(value)))
+    }
+}
+
+/// A constructor of an event with content.
+type Constructor<I> = fn(I) -> Event<I>;
+
+/// Check if the bytes after a `<` start markup, as opposed to a literal `<` in text.
+fn starts_markup(bytes: &[u8]) -> bool {
+    matches!(bytes.first(), Some(b) if b.is_ascii_alphabetic() || matches!(b, b'/' | b'!' | b'?' | b'_' | b':' | 0x80..=0xff))
+}
+
+/// Get the position of the `>` ending a start tag, ignoring any `>` in quoted attribute
+/// values.
+fn tag_end(bytes: &[u8]) -> Option<usize> {
+    let mut pos = 0;
+    loop {
+        pos += find(&bytes[pos..], eq(b'>') | eq(b'"') | eq(b'\''))?;
+        match bytes[pos] {
+            b'>' => return Some(pos),
+            quote => pos += 1 + find(&bytes[pos + 1..], eq(quote))? + 1
+        }
+    }
+}
+
+/// Create a parser for a single event of an XML or HTML document, i.e. a tag, text, a
+/// comment, a CDATA section, a processing instruction or a declaration. Use e.g.
+/// [`parse_iter`](crate::core::parse_iter) to get an iterator over the events of a
+/// document. No allocation is needed, and the events are slices of the input.
+///
+/// This is a tokenizer, so it doesn't check that tags are balanced or decode entities. A
+/// `<` that doesn't start markup is part of the text, and the content of HTML elements
+/// like `<script>` is not treated specially.
+///
+/// ### Consuming
+/// Consumes the event, on successful parse. The parser fails on empty input, or if the
+/// markup is not terminated.
+///
+/// ### Example
+/// ```
+/// use anpa::core::*;
+/// use anpa::xml::{event_parser, Event};
+///
+/// let input = r#"<?xml version="1.0"?><p class='x' hidden>a &lt; b<br/></p>"#;
+/// let events: Vec<_> = parse_iter(event_parser(), input).collect();
+///
+/// assert_eq!(events[0], Event::ProcessingInstruction(r#"xml version="1.0""#));
+/// let Event::StartTag { name: "p", attrs, self_closing: false } = events[1] else { panic!() };
+/// assert_eq!(attrs.collect::<Vec<_>>(), [("class", Some("x")), ("hidden", None)]);
+/// assert_eq!(events[2], Event::Text("a &lt; b"));
+/// assert!(matches!(events[3], Event::StartTag { name: "br", self_closing: true, .. }));
+/// assert_eq!(events[4], Event::EndTag("p"));
+/// ```
+#[inline]
+pub fn event_parser<'a, I: ByteInput<'a>, S>() -> impl Parser<I, Event<I>, S> {
+    create_parser!(s, {
+        let input = s.input;
+        let bytes = input.as_byte_slice();
+
+        let (event, len) = match bytes {
+            [] => return None,
+            [b'<', rest @ ..] if starts_markup(rest) => {
+                let (start, end_delimiter, event): (&[u8], &[u8], Constructor<I>) = match rest {
+                    [b'!', b'-', b'-', ..] => (b"<!--", b"-->", Event::Comment),
+                    [b'!', b'[', b'C', b'D', b'A', b'T', b'A', b'[', ..] => (b"<![CDATA[", b"]]>", Event::CData),
+                    [b'!', ..] => (b"<!", b">", Event::Declaration),
+                    [b'?', ..] => (b"<?", b"?>", Event::ProcessingInstruction),
+                    [b'/', ..] => {
+                        let name_end = 2 + find_or_end(&bytes[2..], whitespace() | eq(b'>'));
+                        let end = name_end + find(&bytes[name_end..], eq(b'>'))?;
+                        if name_end == 2 || !bytes[name_end..end].iter().all(|b| whitespace().matches(*b)) {
+                            return None
+                        }
+                        s.input = input.slice_from(end + 1);
+                        return Some(Event::EndTag(input.slice_to(name_end).slice_from(2)))
+                    },
+                    _ => {
+                        let end = 1 + tag_end(rest)?;
+                        let self_closing = bytes[end - 1] == b'/';
+                        let content = input.slice_to(end - self_closing as usize);
+                        let name_end = 1 + find_or_end(&bytes[1..end], whitespace() | eq(b'/'));
+                        let name = content.slice_to(name_end).slice_from(1);
+                        let attrs = Attributes(content.slice_from(name_end));
+                        s.input = input.slice_from(end + 1);
+                        return Some(Event::StartTag { name, attrs, self_closing })
+                    }
+                };
+
+                let content_len = get_seq_pos(&bytes[start.len()..], end_delimiter)?;
+                let content = input.slice_to(start.len() + content_len).slice_from(start.len());
+                (event(content), start.len() + content_len + end_delimiter.len())
+            },
+            _ => {
+                let mut pos = 1;
+                let len = loop {
+                    let Some(lt) = find(&bytes[pos..], eq(b'<')).map(|lt| pos + lt) else {
+                        break bytes.len()
+                    };
+                    if starts_markup(&bytes[lt + 1..]) {
+                        break lt
+                    }
+                    pos = lt + 1;
+                };
+                (Event::Text(input.slice_to(len)), len)
+            }
+        };
+
+        s.input = input.slice_from(len);
+        Some(event)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{core::{parse, parse_iter}, xml::{event_parser, Event::*}};
+
+    #[test]
+    fn events() {
+        let input = "<!DOCTYPE html><!-- <a> --><a href=\"x>y\" t = 'z' u=v/>1 < 2<![CDATA[<]]></a >";
+        let mut events = parse_iter(event_parser(), input);
+
+        assert_eq!(events.next(), Some(Declaration("DOCTYPE html")));
+        assert_eq!(events.next(), Some(Comment(" <a> ")));
+        let Some(StartTag { name: "a", attrs, self_closing: true }) = events.next() else { panic!() };
+        assert!(attrs.eq([("href", Some("x>y")), ("t", Some("z")), ("u", Some("v"))]));
+        assert_eq!(attrs.get("t"), Some(Some("z")));
+        assert_eq!(attrs.get("v"), None);
+        assert_eq!(events.next(), Some(Text("1 < 2")));
+        assert_eq!(events.next(), Some(CData("<")));
+        assert_eq!(events.next(), Some(EndTag("a")));
+        assert_eq!(events.next(), None);
+        assert_eq!(events.error(), None);
+
+        let Some(StartTag { name, attrs, self_closing: false }) = parse(event_parser(), b"<x\na=1 b>".as_slice()).result else { panic!() };
+        assert_eq!(name, b"x");
+        assert!(attrs.eq([(&b"a"[..], Some(&b"1"[..])), (b"b", None)]));
+
+        for invalid in ["<a", "<a b='>", "</a", "</a b>", "</>", "<!-- a ->", "<?a>", ""] {
+            assert_eq!(parse(event_parser(), invalid).result, None, "{}", invalid);
+        }
+    }
+}
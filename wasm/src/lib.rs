@@ -0,0 +1,53 @@
+//! JavaScript bindings for the JSON and SemVer parsers of anpa, built with `wasm-bindgen`.
+//!
+//! Build with e.g. `wasm-pack build --target web`, and use from JavaScript:
+//! ```js
+//! import init, { parse_json_to_js, parse_semver } from "./pkg/anpa_wasm.js";
+//!
+//! await init();
+//! parse_json_to_js('{"a": [1, 2]}'); // { a: [1, 2] }
+//! parse_semver("1.2.3-beta+build"); // { major: 1, minor: 2, patch: 3, preRelease: "beta", build: "build" }
+//! ```
+
+use anpa::{core::parse_all, json::{self, JsonLimits}, semver};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// A parsed version, with the same fields as `AnpaVersion`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Version<'a> {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    pre_release: &'a str,
+    build: &'a str
+}
+
+/// Parse a JSON document to the corresponding JavaScript value. Objects are converted to
+/// plain objects, not `Map`s.
+///
+/// The limits for untrusted input are used, since the input typically comes from outside.
+#[wasm_bindgen]
+pub fn parse_json_to_js(input: &str) -> Result<JsValue, JsError> {
+    let value = parse_all(json::value_parser_with_limits::<&str>(JsonLimits::UNTRUSTED), input)
+        .map_err(|e| JsError::new(&e.to_string()))?;
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    Ok(value.serialize(&serializer)?)
+}
+
+/// Parse a SemVer version to an object with the fields `major`, `minor`, `patch`,
+/// `preRelease` and `build`. The last two are empty strings if not present.
+#[wasm_bindgen]
+pub fn parse_semver(input: &str) -> Result<JsValue, JsError> {
+    let version = semver::parse_inline(input).ok_or_else(|| JsError::new("invalid version"))?;
+    let version = Version {
+        major: version.major,
+        minor: version.minor,
+        patch: version.patch,
+        pre_release: version.pre_release,
+        build: version.build
+    };
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    Ok(version.serialize(&serializer)?)
+}